@@ -0,0 +1,393 @@
+//! Thread safe communication channels implementing `Evented`.
+//!
+//! See the [`channel`] and [`sync_channel`] functions for documentation.
+
+use crate::event::{Evented, Registration, SetReadiness};
+use crate::{Interests, Registry, Token};
+
+use std::error::Error;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::{fmt, io};
+
+pub use std::sync::mpsc::TryRecvError;
+
+/// Create a new asynchronous channel, where the [`Receiver`] implements
+/// [`Evented`].
+///
+/// This is a wrapper around [`std::sync::mpsc::channel`]. The `Receiver` is
+/// readable once messages are sent to it, or once all [`Sender`]s are
+/// dropped.
+///
+/// A readiness event is only returned once the channel goes from empty to
+/// non-empty, meaning a burst of sends results in a single event and at most a
+/// single wakeup of the [`Poll`]. In turn the receiving side must receive
+/// messages until [`Receiver::try_recv`] returns [`TryRecvError::Empty`]
+/// before it can expect another event.
+///
+/// [`Poll`]: crate::Poll
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use mio::channel;
+/// use mio::{Events, Interests, Poll, Token};
+/// use std::thread;
+///
+/// const COMMANDS: Token = Token(0);
+///
+/// let mut poll = Poll::new()?;
+/// let mut events = Events::with_capacity(8);
+///
+/// let (sender, receiver) = channel::channel();
+/// poll.registry().register(&receiver, COMMANDS, Interests::READABLE)?;
+///
+/// let handle = thread::spawn(move || {
+///     for n in 0..3 {
+///         sender.send(n).unwrap();
+///     }
+/// });
+///
+/// let mut received = Vec::new();
+/// while received.len() < 3 {
+///     poll.poll(&mut events, None)?;
+///     while let Ok(n) = receiver.try_recv() {
+///         received.push(n);
+///     }
+/// }
+/// assert_eq!(received, [0, 1, 2]);
+/// # handle.join().unwrap();
+/// #     Ok(())
+/// # }
+/// ```
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let (sender_ctl, receiver_ctl) = ctl_pair();
+    let (tx, rx) = mpsc::channel();
+    let sender = Sender {
+        tx,
+        ctl: sender_ctl,
+    };
+    let receiver = Receiver {
+        rx,
+        ctl: receiver_ctl,
+    };
+    (sender, receiver)
+}
+
+/// Create a new synchronous, bounded channel, where the [`Receiver`]
+/// implements [`Evented`].
+///
+/// This is a wrapper around [`std::sync::mpsc::sync_channel`], the channel
+/// holds at most `bound` messages after which [`SyncSender::send`] blocks and
+/// [`SyncSender::try_send`] returns [`TrySendError::Full`]. Readiness works
+/// the same as for a [`channel`].
+///
+/// # Panics
+///
+/// This panics if `bound` is zero. A rendezvous channel only completes a send
+/// while the receiver is blocked in `recv`, which the [`Receiver`] never is.
+pub fn sync_channel<T>(bound: usize) -> (SyncSender<T>, Receiver<T>) {
+    assert!(bound > 0, "sync_channel doesn't support a bound of zero");
+    let (sender_ctl, receiver_ctl) = ctl_pair();
+    let (tx, rx) = mpsc::sync_channel(bound);
+    let sender = SyncSender {
+        tx,
+        ctl: sender_ctl,
+    };
+    let receiver = Receiver {
+        rx,
+        ctl: receiver_ctl,
+    };
+    (sender, receiver)
+}
+
+/// The sending half of a [`channel`].
+///
+/// `Sender` can be cloned to send from multiple threads.
+pub struct Sender<T> {
+    tx: mpsc::Sender<T>,
+    ctl: SenderCtl,
+}
+
+/// The sending half of a [`sync_channel`].
+///
+/// `SyncSender` can be cloned to send from multiple threads.
+pub struct SyncSender<T> {
+    tx: mpsc::SyncSender<T>,
+    ctl: SenderCtl,
+}
+
+/// The receiving half of a [`channel`] or [`sync_channel`].
+pub struct Receiver<T> {
+    rx: mpsc::Receiver<T>,
+    ctl: ReceiverCtl,
+}
+
+/// Error returned by [`Sender::send`] and [`SyncSender::send`].
+pub enum SendError<T> {
+    /// Error waking up the `Poll` the `Receiver` is registered with, the
+    /// message was sent.
+    Io(io::Error),
+    /// The `Receiver` was dropped, the message is returned.
+    Disconnected(T),
+}
+
+/// Error returned by [`SyncSender::try_send`].
+pub enum TrySendError<T> {
+    /// Error waking up the `Poll` the `Receiver` is registered with, the
+    /// message was sent.
+    Io(io::Error),
+    /// The channel is full, the message is returned.
+    Full(T),
+    /// The `Receiver` was dropped, the message is returned.
+    Disconnected(T),
+}
+
+/// State shared between the senders and the receiver.
+struct Inner {
+    /// Number of messages sent, but not yet received. Also incremented when
+    /// the last sender is dropped, so that the receiver becomes readable.
+    ///
+    /// A message can be received before the sender increments the count, in
+    /// which case the count wraps around and the increment brings it back.
+    pending: AtomicUsize,
+    /// Number of senders alive.
+    senders: AtomicUsize,
+    set_readiness: SetReadiness,
+}
+
+struct SenderCtl {
+    inner: Arc<Inner>,
+}
+
+struct ReceiverCtl {
+    registration: Registration,
+    inner: Arc<Inner>,
+}
+
+fn ctl_pair() -> (SenderCtl, ReceiverCtl) {
+    let (registration, set_readiness) = Registration::new();
+    let inner = Arc::new(Inner {
+        pending: AtomicUsize::new(0),
+        senders: AtomicUsize::new(1),
+        set_readiness,
+    });
+    let sender_ctl = SenderCtl {
+        inner: inner.clone(),
+    };
+    let receiver_ctl = ReceiverCtl {
+        registration,
+        inner,
+    };
+    (sender_ctl, receiver_ctl)
+}
+
+impl<T> Sender<T> {
+    /// Send a message to the `Receiver`, this never blocks.
+    pub fn send(&self, msg: T) -> Result<(), SendError<T>> {
+        self.tx.send(msg).map_err(SendError::from)?;
+        self.ctl.inc().map_err(SendError::Io)
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Sender<T> {
+        Sender {
+            tx: self.tx.clone(),
+            ctl: self.ctl.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender").finish()
+    }
+}
+
+impl<T> SyncSender<T> {
+    /// Send a message to the `Receiver`, blocking while the channel is full.
+    pub fn send(&self, msg: T) -> Result<(), SendError<T>> {
+        self.tx.send(msg).map_err(SendError::from)?;
+        self.ctl.inc().map_err(SendError::Io)
+    }
+
+    /// Attempt to send a message to the `Receiver`, without blocking.
+    pub fn try_send(&self, msg: T) -> Result<(), TrySendError<T>> {
+        self.tx.try_send(msg).map_err(TrySendError::from)?;
+        self.ctl.inc().map_err(TrySendError::Io)
+    }
+}
+
+impl<T> Clone for SyncSender<T> {
+    fn clone(&self) -> SyncSender<T> {
+        SyncSender {
+            tx: self.tx.clone(),
+            ctl: self.ctl.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for SyncSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SyncSender").finish()
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Attempt to receive a message, without blocking.
+    ///
+    /// Returns [`TryRecvError::Disconnected`] once the channel is empty and
+    /// all senders are dropped.
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        let msg = self.rx.try_recv()?;
+        // Failing to update the readiness only means a spurious event.
+        let _ = self.ctl.dec();
+        Ok(msg)
+    }
+}
+
+impl<T> Evented for Receiver<T> {
+    fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        self.ctl.registration.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &self,
+        registry: &Registry,
+        token: Token,
+        interests: Interests,
+    ) -> io::Result<()> {
+        self.ctl.registration.reregister(registry, token, interests)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.ctl.registration.deregister(registry)
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver").finish()
+    }
+}
+
+impl SenderCtl {
+    /// Mark a message as sent, making the receiver readable if it was empty.
+    fn inc(&self) -> io::Result<()> {
+        if self.inner.pending.fetch_add(1, Ordering::AcqRel) == 0 {
+            self.inner
+                .set_readiness
+                .set_readiness(Some(Interests::READABLE))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Clone for SenderCtl {
+    fn clone(&self) -> SenderCtl {
+        self.inner.senders.fetch_add(1, Ordering::Relaxed);
+        SenderCtl {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl Drop for SenderCtl {
+    fn drop(&mut self) {
+        if self.inner.senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            // Make the receiver readable, so it can detect the disconnect.
+            let _ = self.inc();
+        }
+    }
+}
+
+impl ReceiverCtl {
+    /// Mark a message as received, clearing the readiness if it was the last.
+    fn dec(&self) -> io::Result<()> {
+        let first = self.inner.pending.load(Ordering::Acquire);
+        if first == 1 {
+            self.inner.set_readiness.set_readiness(None)?;
+        }
+
+        let second = self.inner.pending.fetch_sub(1, Ordering::AcqRel);
+        if first == 1 && second > 1 {
+            // A message was sent after the readiness was cleared above.
+            self.inner
+                .set_readiness
+                .set_readiness(Some(Interests::READABLE))?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> From<mpsc::SendError<T>> for SendError<T> {
+    fn from(err: mpsc::SendError<T>) -> SendError<T> {
+        SendError::Disconnected(err.0)
+    }
+}
+
+impl<T> From<io::Error> for SendError<T> {
+    fn from(err: io::Error) -> SendError<T> {
+        SendError::Io(err)
+    }
+}
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            SendError::Io(ref err) => f.debug_tuple("Io").field(err).finish(),
+            SendError::Disconnected(..) => f.write_str("Disconnected(..)"),
+        }
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            SendError::Io(ref err) => fmt::Display::fmt(err, f),
+            SendError::Disconnected(..) => f.write_str("sending on a closed channel"),
+        }
+    }
+}
+
+impl<T> Error for SendError<T> {}
+
+impl<T> From<mpsc::TrySendError<T>> for TrySendError<T> {
+    fn from(err: mpsc::TrySendError<T>) -> TrySendError<T> {
+        match err {
+            mpsc::TrySendError::Full(msg) => TrySendError::Full(msg),
+            mpsc::TrySendError::Disconnected(msg) => TrySendError::Disconnected(msg),
+        }
+    }
+}
+
+impl<T> From<io::Error> for TrySendError<T> {
+    fn from(err: io::Error) -> TrySendError<T> {
+        TrySendError::Io(err)
+    }
+}
+
+impl<T> fmt::Debug for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            TrySendError::Io(ref err) => f.debug_tuple("Io").field(err).finish(),
+            TrySendError::Full(..) => f.write_str("Full(..)"),
+            TrySendError::Disconnected(..) => f.write_str("Disconnected(..)"),
+        }
+    }
+}
+
+impl<T> fmt::Display for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            TrySendError::Io(ref err) => fmt::Display::fmt(err, f),
+            TrySendError::Full(..) => f.write_str("sending on a full channel"),
+            TrySendError::Disconnected(..) => f.write_str("sending on a closed channel"),
+        }
+    }
+}
+
+impl<T> Error for TrySendError<T> {}
@@ -1,5 +1,7 @@
 use crate::event::Event;
 use crate::sys;
+#[cfg(unix)]
+use crate::{Interests, Token};
 
 use std::fmt;
 
@@ -42,6 +44,7 @@ use std::fmt;
 /// #     try_main().unwrap();
 /// # }
 /// ```
+#[repr(transparent)]
 pub struct Events {
     inner: sys::Events,
 }
@@ -197,9 +200,47 @@ impl Events {
         self.inner.clear();
     }
 
+    /// Add an event with `token` and `readiness`, returns `false` if there is
+    /// no room for it.
+    ///
+    /// This is used by implementations of [`Selector`] that return events of
+    /// their own, e.g. a selector returning scripted events in tests.
+    ///
+    /// [`Selector`]: crate::selector::Selector
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mio::{Events, Interests, Token};
+    ///
+    /// let mut events = Events::with_capacity(1);
+    /// assert!(events.push(Token(0), Interests::READABLE));
+    /// // No room left.
+    /// assert!(!events.push(Token(1), Interests::WRITABLE));
+    ///
+    /// let event = events.iter().next().unwrap();
+    /// assert_eq!(event.token(), Token(0));
+    /// assert!(event.is_readable());
+    /// ```
+    #[cfg(unix)]
+    pub fn push(&mut self, token: Token, readiness: Interests) -> bool {
+        self.inner.push(token, readiness)
+    }
+
     pub(crate) fn sys(&mut self) -> &mut sys::Events {
         &mut self.inner
     }
+
+    /// Create a mutable reference to `Events` from the platform specific
+    /// events.
+    #[cfg(unix)]
+    pub(crate) fn from_sys_mut(sys_events: &mut sys::Events) -> &mut Events {
+        unsafe {
+            // This is safe because `Events` is a transparent wrapper around
+            // `sys::Events`.
+            &mut *(sys_events as *mut sys::Events as *mut Events)
+        }
+    }
 }
 
 impl<'a> IntoIterator for &'a Events {
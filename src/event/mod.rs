@@ -3,7 +3,9 @@
 mod event;
 mod evented;
 mod events;
+mod registration;
 
 pub use self::event::Event;
 pub use self::evented::Evented;
 pub use self::events::{Events, Iter};
+pub use self::registration::{Registration, SetReadiness};
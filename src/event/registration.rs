@@ -0,0 +1,143 @@
+use crate::event::Evented;
+use crate::poll::SelectorId;
+use crate::{sys, Interests, Registry, Token};
+
+use std::{fmt, io};
+
+/// Handle to a user space `Evented` source.
+///
+/// `Registration` allows implementing [`Evented`] for types that aren't backed
+/// by a system handle, e.g. an in-process queue. The `Registration` is
+/// registered like any other handle, after which the readiness of the source
+/// is set using the [`SetReadiness`] handle, possibly from another thread.
+///
+/// A readiness event is returned by [`Poll::poll`] if the readiness is set to
+/// include the interests the `Registration` is registered with. Using
+/// [edge-triggered] registrations an event is returned for each call to
+/// [`SetReadiness::set_readiness`], using [level-triggered] registrations
+/// events are returned until the readiness is cleared.
+///
+/// Unlike most other `Evented` types `Registration` doesn't need to be
+/// deregistered before it's dropped.
+///
+/// [`Poll::poll`]: crate::Poll::poll
+/// [edge-triggered]: crate::Trigger::Edge
+/// [level-triggered]: crate::Trigger::Level
+///
+/// # Implementation notes
+///
+/// The readiness events are queued in user space, [`Poll`] is woken up using
+/// a [`Waker`], which on Linux uses [eventfd].
+///
+/// [`Poll`]: crate::Poll
+/// [`Waker`]: crate::Waker
+/// [eventfd]: http://man7.org/linux/man-pages/man2/eventfd.2.html
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use mio::event::Registration;
+/// use mio::{Events, Interests, Poll, Token};
+/// use std::thread;
+///
+/// const QUEUE: Token = Token(0);
+///
+/// let mut poll = Poll::new()?;
+/// let mut events = Events::with_capacity(8);
+///
+/// let (registration, set_readiness) = Registration::new();
+/// poll.registry().register(&registration, QUEUE, Interests::READABLE)?;
+///
+/// let handle = thread::spawn(move || {
+///     set_readiness.set_readiness(Some(Interests::READABLE))
+/// });
+///
+/// poll.poll(&mut events, None)?;
+///
+/// let event = events.iter().next().unwrap();
+/// assert_eq!(event.token(), QUEUE);
+/// assert!(event.is_readable());
+/// # handle.join().unwrap()?;
+/// #     Ok(())
+/// # }
+/// ```
+pub struct Registration {
+    inner: sys::Registration,
+    selector_id: SelectorId,
+}
+
+/// Sets the readiness of a [`Registration`].
+///
+/// `SetReadiness` can be cloned and used from any thread.
+#[derive(Clone)]
+pub struct SetReadiness {
+    inner: sys::SetReadiness,
+}
+
+impl Registration {
+    /// Create a new `Registration`, not registered with any `Poll` yet, and
+    /// the `SetReadiness` handle to set its readiness.
+    pub fn new() -> (Registration, SetReadiness) {
+        let (registration, set_readiness) = sys::Registration::new();
+        (
+            Registration {
+                inner: registration,
+                selector_id: SelectorId::new(),
+            },
+            SetReadiness {
+                inner: set_readiness,
+            },
+        )
+    }
+}
+
+impl Evented for Registration {
+    fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        self.selector_id.associate_selector(registry)?;
+        self.inner.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &self,
+        registry: &Registry,
+        token: Token,
+        interests: Interests,
+    ) -> io::Result<()> {
+        self.inner.reregister(registry, token, interests)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.inner.deregister(registry)
+    }
+}
+
+impl fmt::Debug for Registration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.inner, f)
+    }
+}
+
+impl SetReadiness {
+    /// Returns the current readiness, `None` if not ready.
+    pub fn readiness(&self) -> Option<Interests> {
+        self.inner.readiness()
+    }
+
+    /// Set the readiness of the `Registration`, `None` clears it.
+    ///
+    /// If the `Registration` is registered with interests included in
+    /// `readiness`, a readiness event is returned by a call to `Poll::poll`,
+    /// waking it up if it's blocked. Note that this doesn't establish any
+    /// memory ordering, data shared with the event loop thread must be
+    /// synchronised separately.
+    pub fn set_readiness(&self, readiness: Option<Interests>) -> io::Result<()> {
+        self.inner.set_readiness(readiness)
+    }
+}
+
+impl fmt::Debug for SetReadiness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.inner, f)
+    }
+}
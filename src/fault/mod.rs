@@ -0,0 +1,170 @@
+//! Fault injection, for tests (requires the `fault` feature).
+//!
+//! Faults that are rare in practice, such as spurious wakeups, `EINTR`,
+//! short reads and writes, `WouldBlock` errors with data available or failing
+//! `epoll_ctl` calls, are hard to trigger in tests. This module injects them
+//! on purpose, so that tests can prove they're handled.
+//!
+//! The fault [`Selector`] wraps another selector, by default the selector of
+//! the platform, and sits between [`Poll`] and its [`Registry`] and the
+//! wrapped selector. The handles in [`mio::net`] registered with it consult it
+//! before each system call, it may then fail the call or shorten a read or
+//! write. Which operations fail, and how, is picked by a [`Policy`], e.g.
+//! [`Random`], which injects faults using a seeded random number generator so
+//! that failures are reproducible.
+//!
+//! Injected `WouldBlock` errors are followed by an event for the handle, like
+//! a selector would return once the handle is ready, as the handle may be
+//! ready already.
+//!
+//! # Examples
+//!
+//! ```
+//! # use std::error::Error;
+//! # fn try_main() -> Result<(), Box<dyn Error>> {
+//! use mio::fault::{self, Random};
+//! use mio::net::{TcpListener, TcpStream};
+//! use mio::{Events, Interests, Poll, Token};
+//! use std::io::{self, Write};
+//!
+//! // Short writes half of the time, using seed 42.
+//! let mut policy = Random::new(42);
+//! policy.set_short(0.5);
+//! let mut poll = Poll::with_selector(fault::Selector::new(policy)?);
+//! let mut events = Events::with_capacity(8);
+//!
+//! let listener = TcpListener::bind("127.0.0.1:0".parse()?)?;
+//! let mut stream = TcpStream::connect(listener.local_addr()?)?;
+//! poll.registry().register(&stream, Token(0), Interests::WRITABLE)?;
+//!
+//! let data = [0; 4096];
+//! let mut written = 0;
+//! while written < data.len() {
+//!     match stream.write(&data[written..]) {
+//!         Ok(n) => written += n,
+//!         Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {
+//!             poll.poll(&mut events, None)?;
+//!         }
+//!         Err(err) => return Err(err.into()),
+//!     }
+//! }
+//! #     Ok(())
+//! # }
+//! #
+//! # fn main() {
+//! #     try_main().unwrap();
+//! # }
+//! ```
+//!
+//! [`Poll`]: crate::Poll
+//! [`Registry`]: crate::Registry
+//! [`mio::net`]: crate::net
+
+mod random;
+mod selector;
+
+pub use self::random::Random;
+pub use self::selector::Selector;
+
+use crate::Interests;
+
+/// An operation in which a fault may be injected.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Operation {
+    /// `Registry::register`, i.e. `epoll_ctl(EPOLL_CTL_ADD)` on Linux.
+    Register,
+    /// `Registry::reregister`, i.e. `epoll_ctl(EPOLL_CTL_MOD)` on Linux.
+    Reregister,
+    /// `Registry::deregister`, i.e. `epoll_ctl(EPOLL_CTL_DEL)` on Linux.
+    Deregister,
+    /// `Poll::poll`.
+    Poll,
+    /// Reading into a buffer of the given length from a stream, e.g.
+    /// `TcpStream::read`.
+    Read(usize),
+    /// Writing a buffer of the given length to a stream, e.g.
+    /// `TcpStream::write`.
+    Write(usize),
+    /// Any other operation waiting for readability, e.g.
+    /// `TcpListener::accept`, `UdpSocket::recv_from` or vectored reads.
+    Recv,
+    /// Any other operation waiting for writability, e.g. `UdpSocket::send_to`
+    /// or vectored writes.
+    Send,
+}
+
+impl Operation {
+    /// Returns the readiness the operation waits for, if it's an I/O
+    /// operation.
+    pub(crate) fn interests(self) -> Option<Interests> {
+        match self {
+            Operation::Read(_) | Operation::Recv => Some(Interests::READABLE),
+            Operation::Write(_) | Operation::Send => Some(Interests::WRITABLE),
+            _ => None,
+        }
+    }
+}
+
+/// A fault injected in an [`Operation`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Fault {
+    /// Fail the operation with the OS error `errno`, e.g. `libc::EINTR`,
+    /// `libc::EAGAIN` (`WouldBlock`) or `libc::ENOMEM`, without performing
+    /// it. Applies to all operations.
+    Error(i32),
+    /// Transfer at most the given number of bytes, at least one. Only applies
+    /// to `Read` and `Write`.
+    Short(usize),
+    /// Return from `Poll::poll` immediately, with an event for each
+    /// registered handle whether it's ready or not. Only applies to `Poll`.
+    Spurious,
+}
+
+/// Picks the operations that fail, and how.
+///
+/// Implemented for closures, e.g. to script faults in a test.
+///
+/// # Examples
+///
+/// Fail the first registration.
+///
+/// ```
+/// # use std::error::Error;
+/// # fn try_main() -> Result<(), Box<dyn Error>> {
+/// use mio::fault::{self, Fault, Operation};
+/// use mio::net::UdpSocket;
+/// use mio::{Interests, Poll, Token};
+///
+/// let mut failed = false;
+/// let policy = move |operation| match operation {
+///     Operation::Register if !failed => {
+///         failed = true;
+///         Some(Fault::Error(libc::ENOMEM))
+///     }
+///     _ => None,
+/// };
+/// let poll = Poll::with_selector(fault::Selector::new(policy)?);
+///
+/// let socket = UdpSocket::bind("127.0.0.1:0".parse()?)?;
+/// assert!(poll.registry().register(&socket, Token(0), Interests::READABLE).is_err());
+/// poll.registry().register(&socket, Token(0), Interests::READABLE)?;
+/// #     Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #     try_main().unwrap();
+/// # }
+/// ```
+pub trait Policy: Send + 'static {
+    /// Returns the fault to inject in `operation`, or `None` to perform it.
+    fn fault(&mut self, operation: Operation) -> Option<Fault>;
+}
+
+impl<F> Policy for F
+where
+    F: FnMut(Operation) -> Option<Fault> + Send + 'static,
+{
+    fn fault(&mut self, operation: Operation) -> Option<Fault> {
+        (self)(operation)
+    }
+}
@@ -0,0 +1,99 @@
+use crate::fault::{Fault, Operation, Policy};
+use crate::rng::Rng;
+
+/// A [`Policy`] injecting faults at random, using a seeded random number
+/// generator.
+///
+/// Each kind of fault is injected with its own probability, between 0.0 and
+/// 1.0, all of which default to zero. The same seed and the same sequence of
+/// operations inject the same faults.
+#[derive(Debug)]
+pub struct Random {
+    rng: Rng,
+    spurious: f64,
+    interrupt: f64,
+    would_block: f64,
+    short: f64,
+    register_error: f64,
+}
+
+impl Random {
+    /// Create a new policy using `seed`, not injecting any faults until the
+    /// probabilities are set.
+    pub fn new(seed: u64) -> Random {
+        Random {
+            rng: Rng::new(seed),
+            spurious: 0.0,
+            interrupt: 0.0,
+            would_block: 0.0,
+            short: 0.0,
+            register_error: 0.0,
+        }
+    }
+
+    /// Set the probability of a spurious wakeup in `Poll::poll`, see
+    /// [`Fault::Spurious`].
+    pub fn set_spurious(&mut self, probability: f64) {
+        self.spurious = probability;
+    }
+
+    /// Set the probability of an `EINTR` error in `Poll::poll` and I/O
+    /// operations. Note that `Poll::poll` retries, `Poll::poll_interruptible`
+    /// returns the error.
+    pub fn set_interrupt(&mut self, probability: f64) {
+        self.interrupt = probability;
+    }
+
+    /// Set the probability of a `WouldBlock` error in I/O operations.
+    pub fn set_would_block(&mut self, probability: f64) {
+        self.would_block = probability;
+    }
+
+    /// Set the probability of a short read or write, transferring a random
+    /// number of bytes less than the length of the buffer.
+    pub fn set_short(&mut self, probability: f64) {
+        self.short = probability;
+    }
+
+    /// Set the probability of an `ENOMEM` error in registrations, i.e.
+    /// `Registry::register`, `reregister` and `deregister`.
+    pub fn set_register_error(&mut self, probability: f64) {
+        self.register_error = probability;
+    }
+}
+
+impl Policy for Random {
+    fn fault(&mut self, operation: Operation) -> Option<Fault> {
+        match operation {
+            Operation::Register | Operation::Reregister | Operation::Deregister => {
+                if self.rng.chance(self.register_error) {
+                    return Some(Fault::Error(libc::ENOMEM));
+                }
+            }
+            Operation::Poll => {
+                if self.rng.chance(self.interrupt) {
+                    return Some(Fault::Error(libc::EINTR));
+                } else if self.rng.chance(self.spurious) {
+                    return Some(Fault::Spurious);
+                }
+            }
+            Operation::Read(_) | Operation::Write(_) | Operation::Recv | Operation::Send => {
+                if self.rng.chance(self.interrupt) {
+                    return Some(Fault::Error(libc::EINTR));
+                } else if self.rng.chance(self.would_block) {
+                    return Some(Fault::Error(libc::EAGAIN));
+                }
+            }
+        }
+        match operation {
+            Operation::Read(len) | Operation::Write(len)
+                if len > 1 && self.rng.chance(self.short) =>
+            {
+                Some(Fault::Short(
+                    1 + (self.rng.next_u64() % (len as u64 - 1)) as usize,
+                ))
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use crate::fault::{Fault, Operation, Policy};
+use crate::{selector, Events, Interests, Token, Trigger};
+
+use std::collections::{BTreeMap, VecDeque};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::sync::Mutex;
+use std::time::Duration;
+use std::{fmt, io};
+
+/// A selector injecting faults, picked by a [`Policy`], into another
+/// selector and the handles registered with it.
+///
+/// Faults in registrations fail them without calling the wrapped selector.
+/// Faults in `Poll::poll` either fail it, or return immediately with spurious
+/// events, see [`Fault::Spurious`]. Faults in the I/O operations of handles
+/// are injected by the handles themselves, once they're registered.
+pub struct Selector {
+    selector: Box<dyn selector::Selector>,
+    policy: Mutex<Box<dyn Policy>>,
+    state: Mutex<State>,
+}
+
+struct State {
+    /// The token and interests of the registered file descriptors, ordered by
+    /// file descriptor so that spurious events are returned in a
+    /// reproducible order.
+    registrations: BTreeMap<RawFd, (Token, Interests)>,
+    /// Events of handles that returned an injected `WouldBlock` error.
+    pending: VecDeque<(Token, Interests)>,
+}
+
+impl Selector {
+    /// Create a new selector, injecting faults into the default selector of
+    /// the platform, see the [`selector`] module.
+    ///
+    /// [`selector`]: crate::selector
+    pub fn new<P>(policy: P) -> io::Result<Selector>
+    where
+        P: Policy,
+    {
+        selector::default().map(|selector| Selector::with_selector(selector, policy))
+    }
+
+    /// Create a new selector, injecting faults into `selector`.
+    pub fn with_selector<S, P>(selector: S, policy: P) -> Selector
+    where
+        S: selector::Selector,
+        P: Policy,
+    {
+        Selector {
+            selector: Box::new(selector),
+            policy: Mutex::new(Box::new(policy)),
+            state: Mutex::new(State {
+                registrations: BTreeMap::new(),
+                pending: VecDeque::new(),
+            }),
+        }
+    }
+
+    fn policy(&self, operation: Operation) -> Option<Fault> {
+        self.policy.lock().unwrap().fault(operation)
+    }
+
+    /// Returns the error to inject in a registration, if any.
+    fn registration_fault(&self, operation: Operation) -> io::Result<()> {
+        match self.policy(operation) {
+            Some(Fault::Error(errno)) => Err(io::Error::from_raw_os_error(errno)),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl selector::Selector for Selector {
+    fn register(
+        &self,
+        fd: RawFd,
+        token: Token,
+        interests: Interests,
+        trigger: Trigger,
+    ) -> io::Result<()> {
+        self.registration_fault(Operation::Register)?;
+        self.selector.register(fd, token, interests, trigger)?;
+        let mut state = self.state.lock().unwrap();
+        state.registrations.insert(fd, (token, interests));
+        Ok(())
+    }
+
+    fn reregister(
+        &self,
+        fd: RawFd,
+        token: Token,
+        interests: Interests,
+        trigger: Trigger,
+    ) -> io::Result<()> {
+        self.registration_fault(Operation::Reregister)?;
+        self.selector.reregister(fd, token, interests, trigger)?;
+        let mut state = self.state.lock().unwrap();
+        state.registrations.insert(fd, (token, interests));
+        Ok(())
+    }
+
+    fn deregister(&self, fd: RawFd) -> io::Result<()> {
+        self.registration_fault(Operation::Deregister)?;
+        self.selector.deregister(fd)?;
+        self.state.lock().unwrap().registrations.remove(&fd);
+        Ok(())
+    }
+
+    fn select(&self, events: &mut Events, timeout: Option<Duration>) -> io::Result<()> {
+        match self.policy(Operation::Poll) {
+            Some(Fault::Error(errno)) => return Err(io::Error::from_raw_os_error(errno)),
+            Some(Fault::Spurious) => {
+                let state = self.state.lock().unwrap();
+                for &(token, interests) in state.registrations.values() {
+                    if !events.push(token, interests) {
+                        break;
+                    }
+                }
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        let mut state = self.state.lock().unwrap();
+        if !state.pending.is_empty() {
+            while let Some(&(token, interests)) = state.pending.front() {
+                if !events.push(token, interests) {
+                    break;
+                }
+                state.pending.pop_front();
+            }
+            return Ok(());
+        }
+        drop(state);
+        self.selector.select(events, timeout)
+    }
+
+    fn waker(&self, token: Token) -> io::Result<Box<dyn selector::Wake>> {
+        self.selector.waker(token)
+    }
+
+    fn hooks(&self) -> Option<selector::Hooks<'_>> {
+        Some(selector::Hooks::new(self))
+    }
+}
+
+impl selector::Hook for Selector {
+    fn rearm(&self, fd: RawFd, interests: Interests) {
+        if let Some(hooks) = self.selector.hooks() {
+            hooks.rearm(fd, interests)
+        }
+    }
+
+    fn release(&self, fd: RawFd) {
+        self.state.lock().unwrap().registrations.remove(&fd);
+        if let Some(hooks) = self.selector.hooks() {
+            hooks.release(fd)
+        }
+    }
+
+    fn fault(&self, fd: RawFd, operation: Operation) -> Option<Fault> {
+        let fault = self.policy(operation);
+        if let Some(Fault::Error(errno)) = fault {
+            // The handle waits for an event after a `WouldBlock` error, which
+            // the wrapped selector doesn't return if the handle is ready.
+            if errno == libc::EAGAIN || errno == libc::EWOULDBLOCK {
+                let mut state = self.state.lock().unwrap();
+                let event = match (state.registrations.get(&fd), operation.interests()) {
+                    (Some(&(token, registered)), Some(interests))
+                        if registered.is_readable() && interests.is_readable()
+                            || registered.is_writable() && interests.is_writable() =>
+                    {
+                        Some((token, interests))
+                    }
+                    _ => None,
+                };
+                if let Some(event) = event {
+                    if !state.pending.contains(&event) {
+                        state.pending.push_back(event);
+                    }
+                }
+            }
+        }
+        fault
+    }
+}
+
+impl AsRawFd for Selector {
+    fn as_raw_fd(&self) -> RawFd {
+        self.selector.as_raw_fd()
+    }
+}
+
+impl fmt::Debug for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock().unwrap();
+        f.debug_struct("Selector")
+            .field("registrations", &state.registrations.len())
+            .field("pending", &state.pending.len())
+            .finish()
+    }
+}
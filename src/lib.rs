@@ -10,6 +10,7 @@
 //!
 //! * Non-blocking TCP, UDP
 //! * I/O event queue backed by epoll, kqueue, and IOCP
+//! * Timers, delivered as readiness events (Linux) or tracked in a timing wheel
 //! * Zero allocations at runtime
 //! * Platform specific extensions
 //!
@@ -20,7 +21,6 @@
 //!
 //! * File operations
 //! * Thread pools / multi-threaded event loop
-//! * Timers
 //!
 //! # Platforms
 //!
@@ -115,25 +115,37 @@
 
 mod interests;
 mod poll;
+#[cfg(all(unix, any(feature = "fault", feature = "sim")))]
+mod rng;
 mod sys;
 mod token;
+mod trigger;
 mod waker;
 
+pub mod channel;
 pub mod event;
+#[cfg(all(unix, feature = "fault"))]
+pub mod fault;
 pub mod net;
+#[cfg(all(unix, feature = "record"))]
+pub mod record;
+#[cfg(unix)]
+pub mod selector;
+#[cfg(all(unix, feature = "sim"))]
+pub mod sim;
+pub mod time;
+#[cfg(all(feature = "io_uring", any(target_os = "linux", target_os = "android")))]
+pub mod uring;
 
 pub use event::Events;
 pub use interests::Interests;
 pub use poll::{Poll, Registry};
 pub use token::Token;
+pub use trigger::Trigger;
 pub use waker::Waker;
 
 #[cfg(unix)]
-pub mod unix {
-    //! Unix only extensions
-
-    pub use crate::sys::EventedFd;
-}
+pub mod unix;
 
 /// Windows-only extensions to the mio crate.
 ///
@@ -6,9 +6,22 @@
 //! matter the target platform.
 //!
 //! [portability guidelines]: ../struct.Poll.html#portability
+//!
+//! # Unix domain sockets
+//!
+//! On Unix platforms this module also provides Unix domain sockets, which are
+//! addressed using paths. On Linux and Android a path starting with a null
+//! byte (`\0`) is an address in the abstract namespace, which isn't backed by
+//! a file, e.g. `Path::new("\0my-service")`.
 
 mod tcp;
 mod udp;
+#[cfg(unix)]
+mod uds;
 
 pub use self::tcp::{TcpListener, TcpStream};
 pub use self::udp::UdpSocket;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub use self::uds::UCred;
+#[cfg(unix)]
+pub use self::uds::{UnixDatagram, UnixListener, UnixSeqpacket, UnixSeqpacketListener, UnixStream};
@@ -297,7 +297,7 @@ impl TcpStream {
     /// Successive calls return the same data. This is accomplished by passing
     /// `MSG_PEEK` as a flag to the underlying recv system call.
     pub fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
-        self.sys.peek(buf)
+        self.selector_id.io_read(buf.len(), || self.sys.peek(buf))
     }
 
     /// Read in a list of buffers all at once.
@@ -315,7 +315,8 @@ impl TcpStream {
     ///
     /// On Unix this corresponds to the `readv` syscall.
     pub fn read_bufs(&self, bufs: &mut [&mut IoVec]) -> io::Result<usize> {
-        self.sys.readv(bufs)
+        let len = bufs.iter().map(|buf| buf.len()).sum();
+        self.selector_id.io_read(len, || self.sys.readv(bufs))
     }
 
     /// Write a list of buffers all at once.
@@ -333,7 +334,8 @@ impl TcpStream {
     ///
     /// On Unix this corresponds to the `writev` syscall.
     pub fn write_bufs(&self, bufs: &[&IoVec]) -> io::Result<usize> {
-        self.sys.writev(bufs)
+        self.selector_id
+            .io(Interests::WRITABLE, || self.sys.writev(bufs))
     }
 }
 
@@ -354,19 +356,28 @@ fn inaddr_any(other: SocketAddr) -> SocketAddr {
 
 impl Read for TcpStream {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        (&self.sys).read(buf)
+        self.selector_id
+            .io_len(Interests::READABLE, buf.len(), |len| {
+                (&self.sys).read(&mut buf[..len])
+            })
     }
 }
 
 impl<'a> Read for &'a TcpStream {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        (&self.sys).read(buf)
+        self.selector_id
+            .io_len(Interests::READABLE, buf.len(), |len| {
+                (&self.sys).read(&mut buf[..len])
+            })
     }
 }
 
 impl Write for TcpStream {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        (&self.sys).write(buf)
+        self.selector_id
+            .io_len(Interests::WRITABLE, buf.len(), |len| {
+                (&self.sys).write(&buf[..len])
+            })
     }
 
     fn flush(&mut self) -> io::Result<()> {
@@ -376,7 +387,10 @@ impl Write for TcpStream {
 
 impl<'a> Write for &'a TcpStream {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        (&self.sys).write(buf)
+        self.selector_id
+            .io_len(Interests::WRITABLE, buf.len(), |len| {
+                (&self.sys).write(&buf[..len])
+            })
     }
 
     fn flush(&mut self) -> io::Result<()> {
@@ -386,6 +400,9 @@ impl<'a> Write for &'a TcpStream {
 
 impl Evented for TcpStream {
     fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        #[cfg(unix)]
+        self.selector_id.associate_fd(registry, self.as_raw_fd())?;
+        #[cfg(windows)]
         self.selector_id.associate_selector(registry)?;
         self.sys.register(registry, token, interests)
     }
@@ -524,7 +541,8 @@ impl TcpListener {
     /// *in blocking mode* which isn't bound to `mio`. This can be later then
     /// converted to a `mio` type, if necessary.
     pub fn accept_std(&self) -> io::Result<(net::TcpStream, SocketAddr)> {
-        self.sys.accept()
+        self.selector_id
+            .io(Interests::READABLE, || self.sys.accept())
     }
 
     /// Returns the local socket address of this listener.
@@ -573,6 +591,9 @@ impl TcpListener {
 
 impl Evented for TcpListener {
     fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        #[cfg(unix)]
+        self.selector_id.associate_fd(registry, self.as_raw_fd())?;
+        #[cfg(windows)]
         self.selector_id.associate_selector(registry)?;
         self.sys.register(registry, token, interests)
     }
@@ -606,10 +627,18 @@ impl fmt::Debug for TcpListener {
 #[cfg(unix)]
 use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
 
+#[cfg(unix)]
+impl Drop for TcpStream {
+    fn drop(&mut self) {
+        self.selector_id.release();
+    }
+}
+
 #[cfg(unix)]
 impl IntoRawFd for TcpStream {
     fn into_raw_fd(self) -> RawFd {
-        self.sys.into_raw_fd()
+        unsafe { crate::poll::into_sys(self, |stream| (&stream.selector_id, &stream.sys)) }
+            .into_raw_fd()
     }
 }
 
@@ -630,10 +659,18 @@ impl FromRawFd for TcpStream {
     }
 }
 
+#[cfg(unix)]
+impl Drop for TcpListener {
+    fn drop(&mut self) {
+        self.selector_id.release();
+    }
+}
+
 #[cfg(unix)]
 impl IntoRawFd for TcpListener {
     fn into_raw_fd(self) -> RawFd {
-        self.sys.into_raw_fd()
+        unsafe { crate::poll::into_sys(self, |listener| (&listener.selector_id, &listener.sys)) }
+            .into_raw_fd()
     }
 }
 
@@ -237,7 +237,8 @@ impl UdpSocket {
     /// # }
     /// ```
     pub fn send_to(&self, buf: &[u8], target: SocketAddr) -> io::Result<usize> {
-        self.sys.send_to(buf, target)
+        self.selector_id
+            .io(Interests::WRITABLE, || self.sys.send_to(buf, target))
     }
 
     /// Receives data from the socket. On success, returns the number of bytes
@@ -268,19 +269,22 @@ impl UdpSocket {
     /// # }
     /// ```
     pub fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
-        self.sys.recv_from(buf)
+        self.selector_id
+            .io(Interests::READABLE, || self.sys.recv_from(buf))
     }
 
     /// Sends data on the socket to the address previously bound via connect(). On success,
     /// returns the number of bytes written.
     pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
-        self.sys.send(buf)
+        self.selector_id
+            .io(Interests::WRITABLE, || self.sys.send(buf))
     }
 
     /// Receives data from the socket previously bound with connect(). On success, returns
     /// the number of bytes read.
     pub fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
-        self.sys.recv(buf)
+        self.selector_id
+            .io(Interests::READABLE, || self.sys.recv(buf))
     }
 
     /// Connects the UDP socket setting the default destination for `send()`
@@ -556,7 +560,8 @@ impl UdpSocket {
     /// [link]: https://doc.rust-lang.org/nightly/std/io/enum.ErrorKind.html#variant.WouldBlock
     #[cfg(unix)]
     pub fn recv_bufs(&self, bufs: &mut [&mut IoVec]) -> io::Result<usize> {
-        self.sys.readv(bufs)
+        self.selector_id
+            .io(Interests::READABLE, || self.sys.readv(bufs))
     }
 
     /// Sends data on the socket to the address previously bound via connect.
@@ -577,12 +582,16 @@ impl UdpSocket {
     /// [link]: https://doc.rust-lang.org/nightly/std/io/enum.ErrorKind.html#variant.WouldBlock
     #[cfg(unix)]
     pub fn send_bufs(&self, bufs: &[&IoVec]) -> io::Result<usize> {
-        self.sys.writev(bufs)
+        self.selector_id
+            .io(Interests::WRITABLE, || self.sys.writev(bufs))
     }
 }
 
 impl Evented for UdpSocket {
     fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        #[cfg(unix)]
+        self.selector_id.associate_fd(registry, self.as_raw_fd())?;
+        #[cfg(windows)]
         self.selector_id.associate_selector(registry)?;
         self.sys.register(registry, token, interests)
     }
@@ -616,10 +625,18 @@ impl fmt::Debug for UdpSocket {
 #[cfg(unix)]
 use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
 
+#[cfg(unix)]
+impl Drop for UdpSocket {
+    fn drop(&mut self) {
+        self.selector_id.release();
+    }
+}
+
 #[cfg(unix)]
 impl IntoRawFd for UdpSocket {
     fn into_raw_fd(self) -> RawFd {
-        self.sys.into_raw_fd()
+        unsafe { crate::poll::into_sys(self, |socket| (&socket.selector_id, &socket.sys)) }
+            .into_raw_fd()
     }
 }
 
@@ -0,0 +1,320 @@
+use crate::event::Evented;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use crate::net::UCred;
+use crate::poll::SelectorId;
+use crate::unix::OwnedFd;
+use crate::{sys, Interests, Registry, Token};
+
+use iovec::IoVec;
+use std::fmt;
+use std::io;
+use std::net::Shutdown;
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use std::os::unix::net::{self, SocketAddr};
+use std::path::Path;
+
+/// A non-blocking Unix domain datagram socket.
+///
+/// Like [`UdpSocket`] this keeps message boundaries, but delivery is reliable
+/// and ordered. See the [module documentation] for the supported addresses.
+///
+/// [`UdpSocket`]: crate::net::UdpSocket
+/// [module documentation]: crate::net#unix-domain-sockets
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use mio::net::UnixDatagram;
+/// use mio::{Events, Interests, Poll, Token};
+/// use tempdir::TempDir;
+///
+/// const RECEIVER: Token = Token(0);
+///
+/// let dir = TempDir::new("mio")?;
+/// let path = dir.path().join("receiver.sock");
+/// let receiver = UnixDatagram::bind(&path)?;
+/// let sender = UnixDatagram::unbound()?;
+///
+/// let mut poll = Poll::new()?;
+/// let mut events = Events::with_capacity(128);
+/// poll.registry().register(&receiver, RECEIVER, Interests::READABLE)?;
+///
+/// sender.send_to(b"Hello", &path)?;
+/// poll.poll(&mut events, None)?;
+///
+/// let mut buf = [0; 16];
+/// let (n, _) = receiver.recv_from(&mut buf)?;
+/// assert_eq!(&buf[..n], b"Hello");
+/// #     Ok(())
+/// # }
+/// ```
+pub struct UnixDatagram {
+    sys: sys::UnixDatagram,
+    selector_id: SelectorId,
+}
+
+impl UnixDatagram {
+    /// Creates a Unix datagram socket bound to `path`.
+    pub fn bind<P: AsRef<Path>>(path: P) -> io::Result<UnixDatagram> {
+        sys::UnixDatagram::bind(path.as_ref()).map(UnixDatagram::new)
+    }
+
+    /// Creates a Unix datagram socket which isn't bound to any address.
+    pub fn unbound() -> io::Result<UnixDatagram> {
+        sys::UnixDatagram::unbound().map(UnixDatagram::new)
+    }
+
+    /// Creates an unnamed pair of connected sockets.
+    pub fn pair() -> io::Result<(UnixDatagram, UnixDatagram)> {
+        sys::UnixDatagram::pair().map(|(a, b)| (UnixDatagram::new(a), UnixDatagram::new(b)))
+    }
+
+    /// Creates a new `UnixDatagram` from a standard `net::UnixDatagram`.
+    ///
+    /// This function will set the `socket` provided into nonblocking mode, the
+    /// returned object is ready to get associated with an event loop.
+    pub fn from_std(socket: net::UnixDatagram) -> io::Result<UnixDatagram> {
+        sys::UnixDatagram::from_std(socket).map(UnixDatagram::new)
+    }
+
+    fn new(sys: sys::UnixDatagram) -> UnixDatagram {
+        UnixDatagram {
+            sys,
+            selector_id: SelectorId::new(),
+        }
+    }
+
+    /// Connects the socket to `path`, setting the default destination for
+    /// `send` and limiting datagrams that are read via `recv` to those sent
+    /// from `path`.
+    pub fn connect<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.sys.connect(path.as_ref())
+    }
+
+    /// Returns the address of this socket.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.sys.local_addr()
+    }
+
+    /// Returns the address of this socket's peer, set by [`connect`].
+    ///
+    /// [`connect`]: UnixDatagram::connect
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.sys.peer_addr()
+    }
+
+    /// Creates a new independently owned handle to the underlying socket.
+    ///
+    /// The returned `UnixDatagram` is a reference to the same socket that this
+    /// object references. Both handles can be used to send and receive
+    /// datagrams, and options set on one socket will affect the other.
+    pub fn try_clone(&self) -> io::Result<UnixDatagram> {
+        self.sys.try_clone().map(|s| UnixDatagram {
+            sys: s,
+            selector_id: self.selector_id.clone(),
+        })
+    }
+
+    /// Sends data on the socket to `path`. On success, returns the number of
+    /// bytes written.
+    pub fn send_to<P: AsRef<Path>>(&self, buf: &[u8], path: P) -> io::Result<usize> {
+        self.sys.send_to(buf, path.as_ref())
+    }
+
+    /// Receives data from the socket. On success, returns the number of bytes
+    /// read and the address from whence the data came.
+    ///
+    /// The address is unnamed if the sender isn't bound.
+    pub fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        self.selector_id
+            .io(Interests::READABLE, || self.sys.recv_from(buf))
+    }
+
+    /// Sends data on the socket to the address previously bound via connect().
+    /// On success, returns the number of bytes written.
+    pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        self.selector_id
+            .io(Interests::WRITABLE, || self.sys.send(buf))
+    }
+
+    /// Receives data from the socket previously bound with connect(). On
+    /// success, returns the number of bytes read.
+    pub fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.selector_id
+            .io(Interests::READABLE, || self.sys.recv(buf))
+    }
+
+    /// Shuts down the read, write, or both halves of this socket.
+    ///
+    /// This function will cause all pending and future I/O on the specified
+    /// portions to return immediately with an appropriate value (see the
+    /// documentation of `Shutdown`).
+    pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+        self.sys.shutdown(how)
+    }
+
+    /// Get the value of the `SO_ERROR` option on this socket.
+    ///
+    /// This will retrieve the stored error in the underlying socket, clearing
+    /// the field in the process. This can be useful for checking errors between
+    /// calls.
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.sys.take_error()
+    }
+
+    /// Receives a single datagram on the socket previously bound with connect.
+    ///
+    /// This operation will attempt to read bytes from this socket and place
+    /// them into the list of buffers provided. Note that each buffer is an
+    /// `IoVec` which can be created from a byte slice.
+    ///
+    /// The buffers provided will be filled sequentially. A buffer will be
+    /// entirely filled up before the next is written to.
+    ///
+    /// The number of bytes read is returned, if successful, or an error is
+    /// returned otherwise. If no datagram is available to be read yet then a
+    /// [`WouldBlock`] error is returned. This operation does not block.
+    ///
+    /// [`WouldBlock`]: std::io::ErrorKind::WouldBlock
+    pub fn recv_bufs(&self, bufs: &mut [&mut IoVec]) -> io::Result<usize> {
+        self.selector_id
+            .io(Interests::READABLE, || self.sys.readv(bufs))
+    }
+
+    /// Sends a single datagram on the socket to the address previously bound
+    /// via connect.
+    ///
+    /// This operation will attempt to send a list of byte buffers to this
+    /// socket in a single datagram. Note that each buffer is an `IoVec`
+    /// which can be created from a byte slice.
+    ///
+    /// The number of bytes written is returned, if successful, or an error is
+    /// returned otherwise. If the socket is not currently writable then a
+    /// [`WouldBlock`] error is returned. This operation does not block.
+    ///
+    /// [`WouldBlock`]: std::io::ErrorKind::WouldBlock
+    pub fn send_bufs(&self, bufs: &[&IoVec]) -> io::Result<usize> {
+        self.selector_id
+            .io(Interests::WRITABLE, || self.sys.writev(bufs))
+    }
+
+    /// Sends `buf` along with the file descriptors `fds`, using a
+    /// `SCM_RIGHTS` control message. On success, returns the number of bytes
+    /// written.
+    ///
+    /// The receiving process gets duplicates of `fds`, which remain owned by
+    /// the caller and can be closed once this returns.
+    pub fn send_with_fds(&self, buf: &[u8], fds: &[RawFd]) -> io::Result<usize> {
+        self.selector_id
+            .io(Interests::WRITABLE, || self.sys.send_with_fds(buf, fds))
+    }
+
+    /// Receives data into `buf`, along with at most `max_fds` file descriptors
+    /// sent using [`send_with_fds`]. On success, returns the number of bytes
+    /// read and the received file descriptors, which are closed when dropped.
+    ///
+    /// Received file descriptors have the close-on-exec flag set. If more than
+    /// `max_fds` file descriptors were sent this returns an error, the data is
+    /// consumed and all the sent file descriptors are closed.
+    ///
+    /// [`send_with_fds`]: UnixDatagram::send_with_fds
+    pub fn recv_with_fds(
+        &self,
+        buf: &mut [u8],
+        max_fds: usize,
+    ) -> io::Result<(usize, Vec<OwnedFd>)> {
+        let (n, fds) = self
+            .selector_id
+            .io(Interests::READABLE, || self.sys.recv_with_fds(buf, max_fds))?;
+        Ok((n, fds.into_iter().map(OwnedFd::from_sys).collect()))
+    }
+
+    /// Receives data into `buf`, along with the credentials of the sender. On
+    /// success, returns the number of bytes read and the credentials.
+    ///
+    /// The credentials are only received if the `SO_PASSCRED` option is
+    /// enabled, see [`set_passcred`]. It must be enabled before the datagram
+    /// is sent.
+    ///
+    /// [`set_passcred`]: UnixDatagram::set_passcred
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn recv_with_cred(&self, buf: &mut [u8]) -> io::Result<(usize, Option<UCred>)> {
+        let (n, cred) = self
+            .selector_id
+            .io(Interests::READABLE, || self.sys.recv_with_cred(buf))?;
+        Ok((n, cred.map(UCred::from_sys)))
+    }
+
+    /// Sets the value of the `SO_PASSCRED` option on this socket.
+    ///
+    /// When enabled, the kernel attaches the credentials of the sender to every
+    /// datagram received by this socket, see [`recv_with_cred`].
+    ///
+    /// [`recv_with_cred`]: UnixDatagram::recv_with_cred
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn set_passcred(&self, passcred: bool) -> io::Result<()> {
+        self.sys.set_passcred(passcred)
+    }
+
+    /// Gets the value of the `SO_PASSCRED` option on this socket.
+    ///
+    /// For more information about this option, see [`set_passcred`].
+    ///
+    /// [`set_passcred`]: UnixDatagram::set_passcred
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn passcred(&self) -> io::Result<bool> {
+        self.sys.passcred()
+    }
+}
+
+impl Evented for UnixDatagram {
+    fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        self.selector_id.associate_fd(registry, self.as_raw_fd())?;
+        self.sys.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &self,
+        registry: &Registry,
+        token: Token,
+        interests: Interests,
+    ) -> io::Result<()> {
+        self.sys.reregister(registry, token, interests)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.sys.deregister(registry)
+    }
+}
+
+impl fmt::Debug for UnixDatagram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.sys, f)
+    }
+}
+
+impl Drop for UnixDatagram {
+    fn drop(&mut self) {
+        self.selector_id.release();
+    }
+}
+
+impl IntoRawFd for UnixDatagram {
+    fn into_raw_fd(self) -> RawFd {
+        unsafe { crate::poll::into_sys(self, |socket| (&socket.selector_id, &socket.sys)) }
+            .into_raw_fd()
+    }
+}
+
+impl AsRawFd for UnixDatagram {
+    fn as_raw_fd(&self) -> RawFd {
+        self.sys.as_raw_fd()
+    }
+}
+
+impl FromRawFd for UnixDatagram {
+    unsafe fn from_raw_fd(fd: RawFd) -> UnixDatagram {
+        UnixDatagram::new(FromRawFd::from_raw_fd(fd))
+    }
+}
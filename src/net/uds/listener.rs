@@ -0,0 +1,190 @@
+use crate::event::Evented;
+use crate::net::UnixStream;
+use crate::poll::SelectorId;
+use crate::{sys, Interests, Registry, Token};
+
+use std::fmt;
+use std::io;
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use std::os::unix::net::{self, SocketAddr};
+use std::path::Path;
+
+/// A non-blocking Unix domain socket server.
+///
+/// See the [module documentation] for the supported addresses.
+///
+/// [module documentation]: crate::net#unix-domain-sockets
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use mio::net::UnixListener;
+/// use mio::{Events, Interests, Poll, Token};
+/// use std::time::Duration;
+/// use tempdir::TempDir;
+///
+/// let dir = TempDir::new("mio")?;
+/// let listener = UnixListener::bind(dir.path().join("server.sock"))?;
+///
+/// let mut poll = Poll::new()?;
+/// let mut events = Events::with_capacity(128);
+///
+/// // Register the socket with `Poll`
+/// poll.registry().register(&listener, Token(0), Interests::READABLE)?;
+///
+/// poll.poll(&mut events, Some(Duration::from_millis(100)))?;
+///
+/// // There may be a socket ready to be accepted
+/// #     Ok(())
+/// # }
+/// ```
+pub struct UnixListener {
+    sys: sys::UnixListener,
+    selector_id: SelectorId,
+}
+
+impl UnixListener {
+    /// Creates a new `UnixListener` bound to `path`, listening for new
+    /// connections.
+    ///
+    /// This fails if a file already exists at `path`, including the socket file
+    /// of a previous listener that wasn't removed. See [`bind_unlink_stale`]
+    /// to replace such a file.
+    ///
+    /// [`bind_unlink_stale`]: UnixListener::bind_unlink_stale
+    pub fn bind<P: AsRef<Path>>(path: P) -> io::Result<UnixListener> {
+        sys::UnixListener::bind(path.as_ref(), false).map(UnixListener::new)
+    }
+
+    /// Creates a new `UnixListener` bound to `path`, removing a stale socket
+    /// file at `path` first.
+    ///
+    /// A socket file is considered stale if connecting to it is refused, i.e.
+    /// no process is listening on it any more. Any other file, or a socket that
+    /// is still in use, is left alone and causes `bind` to fail as usual.
+    ///
+    /// Note that the check and removal are not atomic: two processes calling
+    /// this at the same time for the same `path` can both remove the file and
+    /// only one of them will end up bound to it.
+    pub fn bind_unlink_stale<P: AsRef<Path>>(path: P) -> io::Result<UnixListener> {
+        sys::UnixListener::bind(path.as_ref(), true).map(UnixListener::new)
+    }
+
+    /// Creates a new `UnixListener` from a standard `net::UnixListener`.
+    ///
+    /// This function will set the `listener` provided into nonblocking mode,
+    /// the returned object is ready to accept new connections and become
+    /// associated with an event loop.
+    pub fn from_std(listener: net::UnixListener) -> io::Result<UnixListener> {
+        sys::UnixListener::from_std(listener).map(UnixListener::new)
+    }
+
+    fn new(sys: sys::UnixListener) -> UnixListener {
+        UnixListener {
+            sys,
+            selector_id: SelectorId::new(),
+        }
+    }
+
+    /// Accepts a new `UnixStream`.
+    ///
+    /// This may return an `Err(e)` where `e.kind()` is
+    /// `io::ErrorKind::WouldBlock`. This means a stream may be ready at a later
+    /// point and one should wait for an event before calling `accept` again.
+    ///
+    /// If an accepted stream is returned, the address of the peer is returned
+    /// along with it. This address is usually unnamed, as connecting sockets
+    /// are rarely bound.
+    pub fn accept(&self) -> io::Result<(UnixStream, SocketAddr)> {
+        let (s, a) = self.accept_std()?;
+        Ok((UnixStream::from_std(s)?, a))
+    }
+
+    /// Accepts a new `std::os::unix::net::UnixStream`.
+    ///
+    /// This method is the same as `accept`, except that it returns a socket
+    /// *in blocking mode* which isn't bound to `mio`. This can be later then
+    /// converted to a `mio` type, if necessary.
+    pub fn accept_std(&self) -> io::Result<(net::UnixStream, SocketAddr)> {
+        self.selector_id
+            .io(Interests::READABLE, || self.sys.accept())
+    }
+
+    /// Returns the local socket address of this listener.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.sys.local_addr()
+    }
+
+    /// Creates a new independently owned handle to the underlying socket.
+    ///
+    /// The returned `UnixListener` is a reference to the same socket that this
+    /// object references. Both handles can be used to accept incoming
+    /// connections and options set on one listener will affect the other.
+    pub fn try_clone(&self) -> io::Result<UnixListener> {
+        self.sys.try_clone().map(|s| UnixListener {
+            sys: s,
+            selector_id: self.selector_id.clone(),
+        })
+    }
+
+    /// Get the value of the `SO_ERROR` option on this socket.
+    ///
+    /// This will retrieve the stored error in the underlying socket, clearing
+    /// the field in the process. This can be useful for checking errors between
+    /// calls.
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.sys.take_error()
+    }
+}
+
+impl Evented for UnixListener {
+    fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        self.selector_id.associate_fd(registry, self.as_raw_fd())?;
+        self.sys.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &self,
+        registry: &Registry,
+        token: Token,
+        interests: Interests,
+    ) -> io::Result<()> {
+        self.sys.reregister(registry, token, interests)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.sys.deregister(registry)
+    }
+}
+
+impl fmt::Debug for UnixListener {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.sys, f)
+    }
+}
+
+impl Drop for UnixListener {
+    fn drop(&mut self) {
+        self.selector_id.release();
+    }
+}
+
+impl IntoRawFd for UnixListener {
+    fn into_raw_fd(self) -> RawFd {
+        unsafe { crate::poll::into_sys(self, |listener| (&listener.selector_id, &listener.sys)) }
+            .into_raw_fd()
+    }
+}
+
+impl AsRawFd for UnixListener {
+    fn as_raw_fd(&self) -> RawFd {
+        self.sys.as_raw_fd()
+    }
+}
+
+impl FromRawFd for UnixListener {
+    unsafe fn from_raw_fd(fd: RawFd) -> UnixListener {
+        UnixListener::new(FromRawFd::from_raw_fd(fd))
+    }
+}
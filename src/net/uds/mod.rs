@@ -0,0 +1,13 @@
+mod datagram;
+mod listener;
+mod seqpacket;
+mod stream;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod ucred;
+
+pub use self::datagram::UnixDatagram;
+pub use self::listener::UnixListener;
+pub use self::seqpacket::{UnixSeqpacket, UnixSeqpacketListener};
+pub use self::stream::UnixStream;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub use self::ucred::UCred;
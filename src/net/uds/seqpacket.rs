@@ -0,0 +1,389 @@
+use crate::event::Evented;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use crate::net::UCred;
+use crate::poll::SelectorId;
+use crate::unix::OwnedFd;
+use crate::{sys, Interests, Registry, Token};
+
+use iovec::IoVec;
+use std::fmt;
+use std::io;
+use std::net::Shutdown;
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use std::os::unix::net::SocketAddr;
+use std::path::Path;
+
+/// A non-blocking Unix domain seqpacket socket server.
+///
+/// Seqpacket sockets are connection oriented, like [`UnixListener`], but keep
+/// message boundaries, like [`UnixDatagram`]. See the [module documentation]
+/// for the supported addresses.
+///
+/// [`UnixListener`]: crate::net::UnixListener
+/// [`UnixDatagram`]: crate::net::UnixDatagram
+/// [module documentation]: crate::net#unix-domain-sockets
+///
+/// # Notes
+///
+/// Seqpacket sockets are not supported on macOS and iOS, creating one returns
+/// an error.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use mio::net::{UnixSeqpacket, UnixSeqpacketListener};
+/// use mio::{Events, Interests, Poll, Token};
+/// use tempdir::TempDir;
+///
+/// const LISTENER: Token = Token(0);
+///
+/// let dir = TempDir::new("mio")?;
+/// let path = dir.path().join("server.sock");
+/// let listener = UnixSeqpacketListener::bind(&path)?;
+///
+/// let mut poll = Poll::new()?;
+/// let mut events = Events::with_capacity(128);
+/// poll.registry().register(&listener, LISTENER, Interests::READABLE)?;
+///
+/// let client = UnixSeqpacket::connect(&path)?;
+/// poll.poll(&mut events, None)?;
+///
+/// let (server, _) = listener.accept()?;
+/// client.send(b"Hello")?;
+/// client.send(b"world")?;
+///
+/// // Every message is received separately.
+/// let mut buf = [0; 16];
+/// assert_eq!(server.recv(&mut buf)?, 5);
+/// assert_eq!(server.recv(&mut buf)?, 5);
+/// #     Ok(())
+/// # }
+/// ```
+pub struct UnixSeqpacketListener {
+    sys: sys::UnixSeqpacketListener,
+    selector_id: SelectorId,
+}
+
+/// A non-blocking Unix domain seqpacket socket.
+///
+/// The socket will be closed when the value is dropped. See
+/// [`UnixSeqpacketListener`] for an example.
+pub struct UnixSeqpacket {
+    sys: sys::UnixSeqpacket,
+    selector_id: SelectorId,
+}
+
+impl UnixSeqpacketListener {
+    /// Creates a new `UnixSeqpacketListener` bound to `path`, listening for
+    /// new connections.
+    ///
+    /// This fails if a file already exists at `path`, see
+    /// [`UnixListener::bind`].
+    ///
+    /// [`UnixListener::bind`]: crate::net::UnixListener::bind
+    pub fn bind<P: AsRef<Path>>(path: P) -> io::Result<UnixSeqpacketListener> {
+        sys::UnixSeqpacketListener::bind(path.as_ref(), false).map(UnixSeqpacketListener::new)
+    }
+
+    /// Creates a new `UnixSeqpacketListener` bound to `path`, removing a stale
+    /// socket file at `path` first.
+    ///
+    /// See [`UnixListener::bind_unlink_stale`] for details.
+    ///
+    /// [`UnixListener::bind_unlink_stale`]: crate::net::UnixListener::bind_unlink_stale
+    pub fn bind_unlink_stale<P: AsRef<Path>>(path: P) -> io::Result<UnixSeqpacketListener> {
+        sys::UnixSeqpacketListener::bind(path.as_ref(), true).map(UnixSeqpacketListener::new)
+    }
+
+    fn new(sys: sys::UnixSeqpacketListener) -> UnixSeqpacketListener {
+        UnixSeqpacketListener {
+            sys,
+            selector_id: SelectorId::new(),
+        }
+    }
+
+    /// Accepts a new `UnixSeqpacket`.
+    ///
+    /// This may return an `Err(e)` where `e.kind()` is
+    /// `io::ErrorKind::WouldBlock`. This means a socket may be ready at a later
+    /// point and one should wait for an event before calling `accept` again.
+    ///
+    /// If an accepted socket is returned, the address of the peer is returned
+    /// along with it.
+    pub fn accept(&self) -> io::Result<(UnixSeqpacket, SocketAddr)> {
+        self.selector_id
+            .io(Interests::READABLE, || self.sys.accept())
+            .map(|(s, a)| (UnixSeqpacket::new(s), a))
+    }
+
+    /// Returns the local socket address of this listener.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.sys.local_addr()
+    }
+
+    /// Creates a new independently owned handle to the underlying socket.
+    ///
+    /// The returned `UnixSeqpacketListener` is a reference to the same socket
+    /// that this object references. Both handles can be used to accept incoming
+    /// connections and options set on one listener will affect the other.
+    pub fn try_clone(&self) -> io::Result<UnixSeqpacketListener> {
+        self.sys.try_clone().map(|s| UnixSeqpacketListener {
+            sys: s,
+            selector_id: self.selector_id.clone(),
+        })
+    }
+
+    /// Get the value of the `SO_ERROR` option on this socket.
+    ///
+    /// This will retrieve the stored error in the underlying socket, clearing
+    /// the field in the process. This can be useful for checking errors between
+    /// calls.
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.sys.take_error()
+    }
+}
+
+impl UnixSeqpacket {
+    /// Create a new seqpacket socket and issue a non-blocking connect to
+    /// `path`.
+    pub fn connect<P: AsRef<Path>>(path: P) -> io::Result<UnixSeqpacket> {
+        sys::UnixSeqpacket::connect(path.as_ref()).map(UnixSeqpacket::new)
+    }
+
+    /// Creates an unnamed pair of connected sockets.
+    pub fn pair() -> io::Result<(UnixSeqpacket, UnixSeqpacket)> {
+        sys::UnixSeqpacket::pair().map(|(a, b)| (UnixSeqpacket::new(a), UnixSeqpacket::new(b)))
+    }
+
+    fn new(sys: sys::UnixSeqpacket) -> UnixSeqpacket {
+        UnixSeqpacket {
+            sys,
+            selector_id: SelectorId::new(),
+        }
+    }
+
+    /// Returns the socket address of the remote peer of this connection.
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.sys.peer_addr()
+    }
+
+    /// Returns the socket address of the local half of this connection.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.sys.local_addr()
+    }
+
+    /// Creates a new independently owned handle to the underlying socket.
+    ///
+    /// The returned `UnixSeqpacket` is a reference to the same socket that this
+    /// object references. Both handles can be used to send and receive
+    /// messages, and options set on one socket will affect the other.
+    pub fn try_clone(&self) -> io::Result<UnixSeqpacket> {
+        self.sys.try_clone().map(|s| UnixSeqpacket {
+            sys: s,
+            selector_id: self.selector_id.clone(),
+        })
+    }
+
+    /// Sends `buf` as a single message. On success, returns the number of bytes
+    /// written.
+    pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        self.selector_id
+            .io(Interests::WRITABLE, || self.sys.send(buf))
+    }
+
+    /// Receives a single message. On success, returns the number of bytes
+    /// read.
+    ///
+    /// If the message doesn't fit in `buf` the excess bytes are discarded. A
+    /// return value of 0 means the peer closed the connection.
+    pub fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.selector_id
+            .io(Interests::READABLE, || self.sys.recv(buf))
+    }
+
+    /// Shuts down the read, write, or both halves of this connection.
+    ///
+    /// This function will cause all pending and future I/O on the specified
+    /// portions to return immediately with an appropriate value (see the
+    /// documentation of `Shutdown`).
+    pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+        self.sys.shutdown(how)
+    }
+
+    /// Get the value of the `SO_ERROR` option on this socket.
+    ///
+    /// This will retrieve the stored error in the underlying socket, clearing
+    /// the field in the process. This can be useful for checking errors between
+    /// calls.
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.sys.take_error()
+    }
+
+    /// Receives a single message into a list of buffers.
+    ///
+    /// The buffers provided will be filled sequentially. A buffer will be
+    /// entirely filled up before the next is written to.
+    ///
+    /// The number of bytes read is returned, if successful, or an error is
+    /// returned otherwise. If no message is available to be read yet then a
+    /// [`WouldBlock`] error is returned. This operation does not block.
+    ///
+    /// [`WouldBlock`]: std::io::ErrorKind::WouldBlock
+    pub fn recv_bufs(&self, bufs: &mut [&mut IoVec]) -> io::Result<usize> {
+        self.selector_id
+            .io(Interests::READABLE, || self.sys.readv(bufs))
+    }
+
+    /// Sends a list of buffers as a single message.
+    ///
+    /// The number of bytes written is returned, if successful, or an error is
+    /// returned otherwise. If the socket is not currently writable then a
+    /// [`WouldBlock`] error is returned. This operation does not block.
+    ///
+    /// [`WouldBlock`]: std::io::ErrorKind::WouldBlock
+    pub fn send_bufs(&self, bufs: &[&IoVec]) -> io::Result<usize> {
+        self.selector_id
+            .io(Interests::WRITABLE, || self.sys.writev(bufs))
+    }
+
+    /// Sends `buf` along with the file descriptors `fds`, using a
+    /// `SCM_RIGHTS` control message. On success, returns the number of bytes
+    /// written.
+    ///
+    /// The receiving process gets duplicates of `fds`, which remain owned by
+    /// the caller and can be closed once this returns.
+    pub fn send_with_fds(&self, buf: &[u8], fds: &[RawFd]) -> io::Result<usize> {
+        self.selector_id
+            .io(Interests::WRITABLE, || self.sys.send_with_fds(buf, fds))
+    }
+
+    /// Receives data into `buf`, along with at most `max_fds` file descriptors
+    /// sent using [`send_with_fds`]. On success, returns the number of bytes
+    /// read and the received file descriptors, which are closed when dropped.
+    ///
+    /// Received file descriptors have the close-on-exec flag set. If more than
+    /// `max_fds` file descriptors were sent this returns an error, the data is
+    /// consumed and all the sent file descriptors are closed.
+    ///
+    /// [`send_with_fds`]: UnixSeqpacket::send_with_fds
+    pub fn recv_with_fds(
+        &self,
+        buf: &mut [u8],
+        max_fds: usize,
+    ) -> io::Result<(usize, Vec<OwnedFd>)> {
+        let (n, fds) = self
+            .selector_id
+            .io(Interests::READABLE, || self.sys.recv_with_fds(buf, max_fds))?;
+        Ok((n, fds.into_iter().map(OwnedFd::from_sys).collect()))
+    }
+
+    /// Returns the credentials of the peer process, as they were when the
+    /// connection was established, using `SO_PEERCRED`.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn peer_cred(&self) -> io::Result<UCred> {
+        self.sys.peer_cred().map(UCred::from_sys)
+    }
+}
+
+impl Evented for UnixSeqpacketListener {
+    fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        self.selector_id.associate_fd(registry, self.as_raw_fd())?;
+        self.sys.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &self,
+        registry: &Registry,
+        token: Token,
+        interests: Interests,
+    ) -> io::Result<()> {
+        self.sys.reregister(registry, token, interests)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.sys.deregister(registry)
+    }
+}
+
+impl Evented for UnixSeqpacket {
+    fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        self.selector_id.associate_fd(registry, self.as_raw_fd())?;
+        self.sys.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &self,
+        registry: &Registry,
+        token: Token,
+        interests: Interests,
+    ) -> io::Result<()> {
+        self.sys.reregister(registry, token, interests)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.sys.deregister(registry)
+    }
+}
+
+impl fmt::Debug for UnixSeqpacketListener {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.sys, f)
+    }
+}
+
+impl fmt::Debug for UnixSeqpacket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.sys, f)
+    }
+}
+
+impl Drop for UnixSeqpacketListener {
+    fn drop(&mut self) {
+        self.selector_id.release();
+    }
+}
+
+impl IntoRawFd for UnixSeqpacketListener {
+    fn into_raw_fd(self) -> RawFd {
+        unsafe { crate::poll::into_sys(self, |listener| (&listener.selector_id, &listener.sys)) }
+            .into_raw_fd()
+    }
+}
+
+impl AsRawFd for UnixSeqpacketListener {
+    fn as_raw_fd(&self) -> RawFd {
+        self.sys.as_raw_fd()
+    }
+}
+
+impl FromRawFd for UnixSeqpacketListener {
+    unsafe fn from_raw_fd(fd: RawFd) -> UnixSeqpacketListener {
+        UnixSeqpacketListener::new(FromRawFd::from_raw_fd(fd))
+    }
+}
+
+impl Drop for UnixSeqpacket {
+    fn drop(&mut self) {
+        self.selector_id.release();
+    }
+}
+
+impl IntoRawFd for UnixSeqpacket {
+    fn into_raw_fd(self) -> RawFd {
+        unsafe { crate::poll::into_sys(self, |socket| (&socket.selector_id, &socket.sys)) }
+            .into_raw_fd()
+    }
+}
+
+impl AsRawFd for UnixSeqpacket {
+    fn as_raw_fd(&self) -> RawFd {
+        self.sys.as_raw_fd()
+    }
+}
+
+impl FromRawFd for UnixSeqpacket {
+    unsafe fn from_raw_fd(fd: RawFd) -> UnixSeqpacket {
+        UnixSeqpacket::new(FromRawFd::from_raw_fd(fd))
+    }
+}
@@ -0,0 +1,295 @@
+use crate::event::Evented;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use crate::net::UCred;
+use crate::poll::SelectorId;
+use crate::unix::OwnedFd;
+use crate::{sys, Interests, Registry, Token};
+
+use iovec::IoVec;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::Shutdown;
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use std::os::unix::net::{self, SocketAddr};
+use std::path::Path;
+
+/// A non-blocking Unix domain stream socket.
+///
+/// The socket will be closed when the value is dropped. See the [module
+/// documentation] for the supported addresses.
+///
+/// [module documentation]: crate::net#unix-domain-sockets
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use mio::net::UnixStream;
+/// use mio::{Events, Interests, Poll, Token};
+/// use std::io::Write;
+///
+/// let (mut stream, peer) = UnixStream::pair()?;
+///
+/// let mut poll = Poll::new()?;
+/// let mut events = Events::with_capacity(128);
+///
+/// // Register the peer with `Poll`
+/// poll.registry().register(&peer, Token(0), Interests::READABLE)?;
+///
+/// stream.write_all(b"Hello")?;
+/// poll.poll(&mut events, None)?;
+///
+/// // The peer is now readable.
+/// #     Ok(())
+/// # }
+/// ```
+pub struct UnixStream {
+    sys: sys::UnixStream,
+    selector_id: SelectorId,
+}
+
+impl UnixStream {
+    /// Create a new Unix stream socket and issue a non-blocking connect to
+    /// `path`.
+    ///
+    /// If the listener's backlog is full this returns a [`WouldBlock`] error,
+    /// rather than waiting for the listener to accept connections.
+    ///
+    /// [`WouldBlock`]: std::io::ErrorKind::WouldBlock
+    pub fn connect<P: AsRef<Path>>(path: P) -> io::Result<UnixStream> {
+        sys::UnixStream::connect(path.as_ref()).map(UnixStream::new)
+    }
+
+    /// Creates an unnamed pair of connected sockets.
+    pub fn pair() -> io::Result<(UnixStream, UnixStream)> {
+        sys::UnixStream::pair().map(|(a, b)| (UnixStream::new(a), UnixStream::new(b)))
+    }
+
+    /// Creates a new `UnixStream` from a standard `net::UnixStream`.
+    ///
+    /// This function is intended to be used to wrap a Unix stream from the
+    /// standard library in the mio equivalent. The conversion here will
+    /// automatically set `stream` to nonblocking and the returned object should
+    /// be ready to get associated with an event loop.
+    pub fn from_std(stream: net::UnixStream) -> io::Result<UnixStream> {
+        sys::UnixStream::from_std(stream).map(UnixStream::new)
+    }
+
+    fn new(sys: sys::UnixStream) -> UnixStream {
+        UnixStream {
+            sys,
+            selector_id: SelectorId::new(),
+        }
+    }
+
+    /// Returns the socket address of the remote peer of this connection.
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.sys.peer_addr()
+    }
+
+    /// Returns the socket address of the local half of this connection.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.sys.local_addr()
+    }
+
+    /// Creates a new independently owned handle to the underlying socket.
+    ///
+    /// The returned `UnixStream` is a reference to the same stream that this
+    /// object references. Both handles will read and write the same stream of
+    /// data, and options set on one stream will be propagated to the other
+    /// stream.
+    pub fn try_clone(&self) -> io::Result<UnixStream> {
+        self.sys.try_clone().map(|s| UnixStream {
+            sys: s,
+            selector_id: self.selector_id.clone(),
+        })
+    }
+
+    /// Shuts down the read, write, or both halves of this connection.
+    ///
+    /// This function will cause all pending and future I/O on the specified
+    /// portions to return immediately with an appropriate value (see the
+    /// documentation of `Shutdown`).
+    pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+        self.sys.shutdown(how)
+    }
+
+    /// Get the value of the `SO_ERROR` option on this socket.
+    ///
+    /// This will retrieve the stored error in the underlying socket, clearing
+    /// the field in the process. This can be useful for checking errors between
+    /// calls.
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.sys.take_error()
+    }
+
+    /// Read in a list of buffers all at once.
+    ///
+    /// This operation will attempt to read bytes from this socket and place
+    /// them into the list of buffers provided. Note that each buffer is an
+    /// `IoVec` which can be created from a byte slice.
+    ///
+    /// The buffers provided will be filled in sequentially. A buffer will be
+    /// entirely filled up before the next is written to.
+    ///
+    /// The number of bytes read is returned, if successful, or an error is
+    /// returned otherwise. If no bytes are available to be read yet then
+    /// a "would block" error is returned. This operation does not block.
+    pub fn read_bufs(&self, bufs: &mut [&mut IoVec]) -> io::Result<usize> {
+        let len = bufs.iter().map(|buf| buf.len()).sum();
+        self.selector_id.io_read(len, || self.sys.readv(bufs))
+    }
+
+    /// Write a list of buffers all at once.
+    ///
+    /// This operation will attempt to write a list of byte buffers to this
+    /// socket. Note that each buffer is an `IoVec` which can be created from a
+    /// byte slice.
+    ///
+    /// The buffers provided will be written sequentially. A buffer will be
+    /// entirely written before the next is written.
+    ///
+    /// The number of bytes written is returned, if successful, or an error is
+    /// returned otherwise. If the socket is not currently writable then a
+    /// "would block" error is returned. This operation does not block.
+    pub fn write_bufs(&self, bufs: &[&IoVec]) -> io::Result<usize> {
+        self.selector_id
+            .io(Interests::WRITABLE, || self.sys.writev(bufs))
+    }
+
+    /// Sends `buf` along with the file descriptors `fds`, using a
+    /// `SCM_RIGHTS` control message. On success, returns the number of bytes
+    /// written.
+    ///
+    /// The receiving process gets duplicates of `fds`, which remain owned by
+    /// the caller and can be closed once this returns.
+    ///
+    /// `buf` must not be empty, as the file descriptors are attached to the
+    /// data sent.
+    pub fn send_with_fds(&self, buf: &[u8], fds: &[RawFd]) -> io::Result<usize> {
+        self.selector_id
+            .io(Interests::WRITABLE, || self.sys.send_with_fds(buf, fds))
+    }
+
+    /// Receives data into `buf`, along with at most `max_fds` file descriptors
+    /// sent using [`send_with_fds`]. On success, returns the number of bytes
+    /// read and the received file descriptors, which are closed when dropped.
+    ///
+    /// Received file descriptors have the close-on-exec flag set. If more than
+    /// `max_fds` file descriptors were sent this returns an error, the data is
+    /// consumed and all the sent file descriptors are closed.
+    ///
+    /// [`send_with_fds`]: UnixStream::send_with_fds
+    pub fn recv_with_fds(
+        &self,
+        buf: &mut [u8],
+        max_fds: usize,
+    ) -> io::Result<(usize, Vec<OwnedFd>)> {
+        let (n, fds) = self
+            .selector_id
+            .io(Interests::READABLE, || self.sys.recv_with_fds(buf, max_fds))?;
+        Ok((n, fds.into_iter().map(OwnedFd::from_sys).collect()))
+    }
+
+    /// Returns the credentials of the peer process, as they were when the
+    /// connection was established, using `SO_PEERCRED`.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn peer_cred(&self) -> io::Result<UCred> {
+        self.sys.peer_cred().map(UCred::from_sys)
+    }
+}
+
+impl Read for UnixStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.selector_id
+            .io_len(Interests::READABLE, buf.len(), |len| {
+                (&self.sys).read(&mut buf[..len])
+            })
+    }
+}
+
+impl Read for &UnixStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.selector_id
+            .io_len(Interests::READABLE, buf.len(), |len| {
+                (&self.sys).read(&mut buf[..len])
+            })
+    }
+}
+
+impl Write for UnixStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.selector_id
+            .io_len(Interests::WRITABLE, buf.len(), |len| {
+                (&self.sys).write(&buf[..len])
+            })
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        (&self.sys).flush()
+    }
+}
+
+impl Write for &UnixStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.selector_id
+            .io_len(Interests::WRITABLE, buf.len(), |len| {
+                (&self.sys).write(&buf[..len])
+            })
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        (&self.sys).flush()
+    }
+}
+
+impl Evented for UnixStream {
+    fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        self.selector_id.associate_fd(registry, self.as_raw_fd())?;
+        self.sys.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &self,
+        registry: &Registry,
+        token: Token,
+        interests: Interests,
+    ) -> io::Result<()> {
+        self.sys.reregister(registry, token, interests)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.sys.deregister(registry)
+    }
+}
+
+impl fmt::Debug for UnixStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.sys, f)
+    }
+}
+
+impl Drop for UnixStream {
+    fn drop(&mut self) {
+        self.selector_id.release();
+    }
+}
+
+impl IntoRawFd for UnixStream {
+    fn into_raw_fd(self) -> RawFd {
+        unsafe { crate::poll::into_sys(self, |stream| (&stream.selector_id, &stream.sys)) }
+            .into_raw_fd()
+    }
+}
+
+impl AsRawFd for UnixStream {
+    fn as_raw_fd(&self) -> RawFd {
+        self.sys.as_raw_fd()
+    }
+}
+
+impl FromRawFd for UnixStream {
+    unsafe fn from_raw_fd(fd: RawFd) -> UnixStream {
+        UnixStream::new(FromRawFd::from_raw_fd(fd))
+    }
+}
@@ -0,0 +1,39 @@
+use libc;
+
+/// Credentials of a Unix socket peer.
+///
+/// See [`UnixStream::peer_cred`] and [`UnixDatagram::recv_with_cred`].
+///
+/// [`UnixStream::peer_cred`]: crate::net::UnixStream::peer_cred
+/// [`UnixDatagram::recv_with_cred`]: crate::net::UnixDatagram::recv_with_cred
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct UCred {
+    pid: u32,
+    uid: u32,
+    gid: u32,
+}
+
+impl UCred {
+    pub(crate) fn from_sys(cred: libc::ucred) -> UCred {
+        UCred {
+            pid: cred.pid as u32,
+            uid: cred.uid,
+            gid: cred.gid,
+        }
+    }
+
+    /// The process id of the peer.
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// The effective user id of the peer.
+    pub fn uid(&self) -> u32 {
+        self.uid
+    }
+
+    /// The effective group id of the peer.
+    pub fn gid(&self) -> u32 {
+        self.gid
+    }
+}
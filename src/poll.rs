@@ -1,13 +1,24 @@
 use crate::event::{Evented, Events};
-use crate::Interests;
-use crate::{sys, Token};
+#[cfg(all(unix, feature = "fault"))]
+use crate::fault::{Fault, Operation};
+#[cfg(unix)]
+use crate::selector;
+use crate::{sys, Interests, Token, Trigger};
 use log::trace;
 #[cfg(unix)]
+use std::mem::ManuallyDrop;
+#[cfg(unix)]
 use std::os::unix::io::AsRawFd;
 #[cfg(unix)]
 use std::os::unix::io::RawFd;
+#[cfg(unix)]
+use std::ptr;
+#[cfg(unix)]
+use std::sync::atomic::AtomicBool;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+#[cfg(unix)]
+use std::sync::{Mutex, Weak};
 use std::time::{Duration, Instant};
 use std::{fmt, io, usize};
 
@@ -103,6 +114,12 @@ use std::{fmt, io, usize};
 /// there is no guarantee that another readiness event will be delivered, even
 /// if further data is received for the [`Evented`] handle.
 ///
+/// This does not apply to handles registered [level-triggered], those return
+/// a readiness event on every call to [`Poll::poll`] for as long as the handle
+/// is ready.
+///
+/// [level-triggered]: crate::Trigger::Level
+///
 /// [`WouldBlock`]: std::io::ErrorKind::WouldBlock
 ///
 /// ### Readiness operations
@@ -179,11 +196,29 @@ use std::{fmt, io, usize};
 /// | iOS           | [kqueue]  |
 /// | macOS         | [kqueue]  |
 ///
+/// On Linux and Android the `io_uring` feature replaces [epoll] with
+/// [io_uring] poll requests, which requires Linux 5.13 or later. Registration
+/// changes are then batched and submitted by the next call to [`Poll::poll`].
+/// As in-flight poll requests keep the file open, file descriptors registered
+/// using [`EventedFd`] must be deregistered before they are closed.
+///
+/// On all Unix platforms the `force_poll` feature replaces the selector with
+/// one backed by [poll], which takes precedence over the `io_uring` feature.
+/// As `poll` is level-triggered, edge triggers are emulated: once an event is
+/// returned its interests are disarmed until the handle is read from, a write
+/// returns a [`WouldBlock`] error, or the handle is reregistered. Mio's own
+/// types do this automatically, handles registered using [`EventedFd`] must
+/// be reregistered before waiting for the next event.
+///
+/// On Unix platforms the selector can also be picked at runtime, or replaced
+/// by a custom implementation, using [`Poll::with_selector`]. See the
+/// [`selector`] module for the selectors provided by Mio.
+///
 /// On all supported platforms, socket operations are handled by using the
 /// system selector. Platform specific extensions (e.g. [`EventedFd`]) allow
 /// accessing other features provided by individual system selectors. For
-/// example, Linux's [`signalfd`] feature can be used by registering the FD with
-/// `Poll` via [`EventedFd`].
+/// example, Linux's [`signalfd`] feature is used by [`Signals`] to receive
+/// signals as readiness events.
 ///
 /// On all platforms except windows, a call to [`Poll::poll`] is mostly just a
 /// direct call to the system selector. However, [IOCP] uses a completion model
@@ -199,11 +234,17 @@ use std::{fmt, io, usize};
 ///
 /// [epoll]: http://man7.org/linux/man-pages/man7/epoll.7.html
 /// [kqueue]: https://www.freebsd.org/cgi/man.cgi?query=kqueue&sektion=2
+/// [io_uring]: http://man7.org/linux/man-pages/man7/io_uring.7.html
+/// [poll]: http://man7.org/linux/man-pages/man2/poll.2.html
+/// [`WouldBlock`]: std::io::ErrorKind::WouldBlock
 /// [IOCP]: https://msdn.microsoft.com/en-us/library/windows/desktop/aa365198(v=vs.85).aspx
 /// [`signalfd`]: http://man7.org/linux/man-pages/man2/signalfd.2.html
 /// [`EventedFd`]: unix/struct.EventedFd.html
+/// [`Signals`]: crate::unix::Signals
 /// [`SetReadiness`]: struct.SetReadiness.html
 /// [`Poll::poll`]: struct.Poll.html#method.poll
+/// [`Poll::with_selector`]: Poll::with_selector
+/// [`selector`]: crate::selector
 pub struct Poll {
     registry: Registry,
 }
@@ -212,12 +253,21 @@ pub struct Poll {
 #[derive(Clone)]
 pub struct Registry {
     selector: Arc<sys::Selector>,
+    trigger: Trigger,
 }
 
 /// Used to associate an IO type with a Selector
 #[derive(Debug)]
 pub struct SelectorId {
     id: AtomicUsize,
+    /// Set once `registered` is set, so that handles registered with a
+    /// selector without hooks, e.g. epoll, don't lock `registered`.
+    #[cfg(unix)]
+    hooked: AtomicBool,
+    /// Selector and file descriptor of the registered handle, if the selector
+    /// has hooks, see `with_hooks`.
+    #[cfg(unix)]
+    registered: Mutex<Option<(Weak<sys::Selector>, RawFd)>>,
 }
 
 const WAKE: Token = Token(usize::MAX);
@@ -272,11 +322,63 @@ impl Poll {
 
         let selector = Arc::new(sys::Selector::new()?);
 
-        let registry = Registry { selector };
+        let registry = Registry {
+            selector,
+            trigger: Trigger::Edge,
+        };
 
         Ok(Poll { registry })
     }
 
+    /// Return a new `Poll` handle using `selector`.
+    ///
+    /// Unlike [`Poll::new`], which uses the default selector of the platform,
+    /// this uses any implementation of [`Selector`], e.g. one picked at
+    /// runtime. See the [`selector`] module for the selectors provided by Mio.
+    ///
+    /// The selector is called through a trait object, whereas `Poll::new`
+    /// calls the default selector directly.
+    ///
+    /// [`Selector`]: crate::selector::Selector
+    /// [`selector`]: crate::selector
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn try_main() -> Result<(), Box<dyn Error>> {
+    /// use mio::selector::{self, Selector};
+    /// use mio::{Events, Poll};
+    /// use std::time::Duration;
+    ///
+    /// // Pick the selector using an environment variable.
+    /// let selector: Box<dyn Selector> = match std::env::var("SELECTOR") {
+    ///     Ok(ref name) if name == "poll" => Box::new(selector::Poll::new()?),
+    ///     _ => return Ok(()),
+    /// };
+    /// let mut poll = Poll::with_selector(selector);
+    ///
+    /// let mut events = Events::with_capacity(1024);
+    /// poll.poll(&mut events, Some(Duration::from_millis(10)))?;
+    /// #     Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #     try_main().unwrap();
+    /// # }
+    /// ```
+    #[cfg(unix)]
+    pub fn with_selector<S: selector::Selector>(selector: S) -> Poll {
+        let selector = Arc::new(sys::Selector::with_selector(Box::new(selector)));
+
+        let registry = Registry {
+            selector,
+            trigger: Trigger::Edge,
+        };
+
+        Poll { registry }
+    }
+
     /// Return a reference to the associated `Registry`.
     pub fn registry(&self) -> &Registry {
         &self.registry
@@ -304,7 +406,9 @@ impl Poll {
     ///
     /// Note that the `timeout` will be rounded up to the system clock
     /// granularity (usually 1ms), and kernel scheduling delays mean that
-    /// the blocking interval may be overrun by a small amount.
+    /// the blocking interval may be overrun by a small amount. On Linux and
+    /// Android, as well as on kqueue platforms, sub-millisecond timeouts are
+    /// supported.
     ///
     /// `poll` returns the number of readiness events that have been pushed into
     /// `events` or `Err` when an error has been encountered with the system
@@ -665,6 +769,100 @@ impl Registry {
         Ok(())
     }
 
+    /// Register an `Evented` handle with the `Poll` instance using the
+    /// provided `trigger` mode.
+    ///
+    /// [`register`] always registers the handle [edge-triggered], this allows
+    /// selecting a different way of delivering readiness events, for example
+    /// [level-triggered] or [oneshot].
+    ///
+    /// See the [`register`] documentation for details about the other
+    /// arguments.
+    ///
+    /// [`register`]: Registry::register
+    /// [edge-triggered]: Trigger::Edge
+    /// [level-triggered]: Trigger::Level
+    /// [oneshot]: Trigger::Oneshot
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use std::io::Write;
+    /// use std::net::TcpListener;
+    ///
+    /// use mio::net::TcpStream;
+    /// use mio::{Events, Interests, Poll, Token, Trigger};
+    ///
+    /// let mut poll = Poll::new()?;
+    /// let mut events = Events::with_capacity(8);
+    ///
+    /// let listener = TcpListener::bind("127.0.0.1:0")?;
+    /// let stream = TcpStream::connect(listener.local_addr()?)?;
+    /// let (mut peer, _) = listener.accept()?;
+    /// peer.write_all(b"Hello")?;
+    ///
+    /// poll.registry()
+    ///     .register_with(&stream, Token(0), Interests::READABLE, Trigger::Level)?;
+    ///
+    /// // As long as the data isn't read the stream stays readable.
+    /// for _ in 0..2 {
+    ///     poll.poll(&mut events, None)?;
+    ///     assert!(events.iter().any(|event| event.is_readable()));
+    /// }
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn register_with<E: ?Sized>(
+        &self,
+        handle: &E,
+        token: Token,
+        interests: Interests,
+        trigger: Trigger,
+    ) -> io::Result<()>
+    where
+        E: Evented,
+    {
+        self.with_trigger(trigger)
+            .register(handle, token, interests)
+    }
+
+    /// Re-register an `Evented` handle with the `Poll` instance using the
+    /// provided `trigger` mode.
+    ///
+    /// Like [`reregister`] the arguments fully override the previous values,
+    /// this includes the trigger mode. Calling [`reregister`] on a handle
+    /// registered using [`register_with`] makes it edge-triggered again.
+    ///
+    /// See the [`register`] documentation for details about the function
+    /// arguments.
+    ///
+    /// [`reregister`]: Registry::reregister
+    /// [`register_with`]: Registry::register_with
+    /// [`register`]: Registry::register
+    pub fn reregister_with<E: ?Sized>(
+        &self,
+        handle: &E,
+        token: Token,
+        interests: Interests,
+        trigger: Trigger,
+    ) -> io::Result<()>
+    where
+        E: Evented,
+    {
+        self.with_trigger(trigger)
+            .reregister(handle, token, interests)
+    }
+
+    /// Returns a `Registry` for the same `Poll` instance that (re)registers
+    /// handles using `trigger`.
+    fn with_trigger(&self, trigger: Trigger) -> Registry {
+        Registry {
+            selector: self.selector.clone(),
+            trigger,
+        }
+    }
+
     /// Deregister an `Evented` handle with the `Poll` instance.
     ///
     /// When an `Evented` handle is deregistered, the `Poll` instance will
@@ -735,6 +933,10 @@ pub fn selector(registry: &Registry) -> &sys::Selector {
     &registry.selector
 }
 
+pub fn trigger(registry: &Registry) -> Trigger {
+    registry.trigger
+}
+
 fn is_send<T: Send>() {}
 fn is_sync<T: Sync>() {}
 
@@ -742,9 +944,190 @@ impl SelectorId {
     pub fn new() -> SelectorId {
         SelectorId {
             id: AtomicUsize::new(0),
+            #[cfg(unix)]
+            hooked: AtomicBool::new(false),
+            #[cfg(unix)]
+            registered: Mutex::new(None),
         }
     }
 
+    /// Same as `associate_selector`, for handles backed by the file descriptor
+    /// `fd`.
+    #[cfg(unix)]
+    pub fn associate_fd(&self, registry: &Registry, fd: RawFd) -> io::Result<()> {
+        self.associate_selector(registry)?;
+        if registry.selector.hooks().is_some() {
+            *self.registered.lock().unwrap() = Some((Arc::downgrade(&registry.selector), fd));
+            self.hooked.store(true, Ordering::Release);
+        }
+        Ok(())
+    }
+
+    /// Must be called with the result of the I/O operations of handles
+    /// registered using `associate_fd`, `interests` being the direction of the
+    /// operation.
+    ///
+    /// Selectors emulating edge triggers, such as the poll(2) selector,
+    /// disarm the interests of a handle once they're returned. Reads arm the
+    /// readable interest again, as new data may arrive after them, writes arm
+    /// the writable interest once they return a `WouldBlock` error.
+    #[inline]
+    pub fn did_io<T>(&self, interests: Interests, res: io::Result<T>) -> io::Result<T> {
+        #[cfg(unix)]
+        {
+            let rearm = match res {
+                Ok(_) => interests.is_readable(),
+                Err(ref err) => err.kind() == io::ErrorKind::WouldBlock,
+            };
+            if rearm {
+                self.with_hooks(|hooks, fd| hooks.rearm(fd, interests));
+            }
+        }
+        #[cfg(not(unix))]
+        let _ = interests;
+        res
+    }
+
+    /// Same as `did_io` for a read of `len` bytes from a stream. Reading 0
+    /// bytes means the end of the stream was reached, after which no more
+    /// data arrives, so the readable interest isn't armed again.
+    #[inline]
+    pub fn did_read(&self, len: usize, res: io::Result<usize>) -> io::Result<usize> {
+        match res {
+            Ok(0) if len > 0 => res,
+            res => self.did_io(Interests::READABLE, res),
+        }
+    }
+
+    /// Performs the I/O operation `f` of a handle registered using
+    /// `associate_fd`, calling `did_io` with the result.
+    ///
+    /// With the `fault` feature the selector may inject an error in place of
+    /// the operation, see the `fault` module.
+    #[inline]
+    pub fn io<T, F>(&self, interests: Interests, f: F) -> io::Result<T>
+    where
+        F: FnOnce() -> io::Result<T>,
+    {
+        #[cfg(all(unix, feature = "fault"))]
+        {
+            let operation = if interests.is_readable() {
+                Operation::Recv
+            } else {
+                Operation::Send
+            };
+            if let Some(Fault::Error(errno)) = self.fault(operation) {
+                return self.did_io(interests, Err(io::Error::from_raw_os_error(errno)));
+            }
+        }
+        self.did_io(interests, f())
+    }
+
+    /// Same as `io`, for reads of at most `len` bytes from a stream, calling
+    /// `did_read` with the result.
+    #[inline]
+    pub fn io_read<F>(&self, len: usize, f: F) -> io::Result<usize>
+    where
+        F: FnOnce() -> io::Result<usize>,
+    {
+        #[cfg(all(unix, feature = "fault"))]
+        {
+            if let Some(Fault::Error(errno)) = self.fault(Operation::Recv) {
+                return self.did_io(
+                    Interests::READABLE,
+                    Err(io::Error::from_raw_os_error(errno)),
+                );
+            }
+        }
+        self.did_read(len, f())
+    }
+
+    /// Same as `io`, for reads and writes of streams. `f` is called with the
+    /// number of bytes to transfer, at most `len`, which is less than `len`
+    /// if the selector injects a short read or write. Reads call `did_read`
+    /// with the result.
+    #[inline]
+    pub fn io_len<F>(&self, interests: Interests, len: usize, f: F) -> io::Result<usize>
+    where
+        F: FnOnce(usize) -> io::Result<usize>,
+    {
+        #[cfg(all(unix, feature = "fault"))]
+        {
+            let operation = if interests.is_readable() {
+                Operation::Read(len)
+            } else {
+                Operation::Write(len)
+            };
+            match self.fault(operation) {
+                Some(Fault::Error(errno)) => {
+                    return self.did_io(interests, Err(io::Error::from_raw_os_error(errno)));
+                }
+                // Transferring zero bytes would look like the end of the
+                // stream.
+                Some(Fault::Short(n)) if len > 0 => {
+                    let len = n.min(len).max(1);
+                    return self.did_len(interests, len, f(len));
+                }
+                _ => {}
+            }
+        }
+        self.did_len(interests, len, f(len))
+    }
+
+    #[inline]
+    fn did_len(
+        &self,
+        interests: Interests,
+        len: usize,
+        res: io::Result<usize>,
+    ) -> io::Result<usize> {
+        if interests.is_readable() {
+            self.did_read(len, res)
+        } else {
+            self.did_io(interests, res)
+        }
+    }
+
+    #[cfg(all(unix, feature = "fault"))]
+    fn fault(&self, operation: Operation) -> Option<Fault> {
+        self.with_hooks(|hooks, fd| hooks.fault(fd, operation))
+            .and_then(|fault| fault)
+    }
+
+    /// Calls `f` with the hooks of the selector the handle is registered with,
+    /// if any. This is on the path of every I/O operation, it doesn't lock
+    /// anything if the selector has no hooks, e.g. epoll and kqueue.
+    #[cfg(unix)]
+    fn with_hooks<F, T>(&self, f: F) -> Option<T>
+    where
+        F: FnOnce(selector::Hooks<'_>, RawFd) -> T,
+    {
+        if !self.hooked.load(Ordering::Acquire) {
+            return None;
+        }
+        match *self.registered.lock().unwrap() {
+            Some((ref selector, fd)) => {
+                let selector = selector.upgrade()?;
+                let hooks = selector.hooks()?;
+                Some(f(hooks, fd))
+            }
+            None => None,
+        }
+    }
+
+    /// Remove the registration of the handle, called by the `Drop`
+    /// implementation of handles registered using `associate_fd`.
+    ///
+    /// The io_uring selector keeps registered files open and the poll(2)
+    /// selector doesn't know when they're closed, so handles must call this
+    /// before closing their file descriptor. Removing the registration after
+    /// would race with another thread opening a file with the same file
+    /// descriptor.
+    #[cfg(unix)]
+    pub fn release(&self) {
+        self.with_hooks(|hooks, fd| hooks.release(fd));
+    }
+
     pub fn associate_selector(&self, registry: &Registry) -> io::Result<()> {
         let selector_id = self.id.load(Ordering::SeqCst);
 
@@ -764,10 +1147,34 @@ impl Clone for SelectorId {
     fn clone(&self) -> SelectorId {
         SelectorId {
             id: AtomicUsize::new(self.id.load(Ordering::SeqCst)),
+            // The clone is backed by a different file descriptor.
+            #[cfg(unix)]
+            hooked: AtomicBool::new(false),
+            #[cfg(unix)]
+            registered: Mutex::new(None),
         }
     }
 }
 
+/// Moves `sys` out of `handle` without dropping `handle`, for the
+/// `into_raw_fd` implementations of handles releasing their registration
+/// when dropped. Like with epoll, the file descriptor stays registered.
+///
+/// # Safety
+///
+/// `fields` must return the `SelectorId` and the `sys` fields of `handle`,
+/// its other fields are leaked.
+#[cfg(unix)]
+pub unsafe fn into_sys<H, S, F>(handle: H, fields: F) -> S
+where
+    F: FnOnce(&H) -> (&SelectorId, &S),
+{
+    let handle = ManuallyDrop::new(handle);
+    let (selector_id, sys) = fields(&handle);
+    drop(ptr::read(selector_id));
+    ptr::read(sys)
+}
+
 #[test]
 #[cfg(unix)]
 pub fn as_raw_fd() {
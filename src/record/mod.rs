@@ -0,0 +1,207 @@
+//! Recording and replaying of events, to reproduce event ordering bugs
+//! offline (requires the `record` feature).
+//!
+//! The [`Recorder`] wraps another selector, by default the selector of the
+//! platform, and writes every call to `Poll::poll` and every registration
+//! made through the [`Registry`] to a compact binary log. The [`Replayer`]
+//! reads the log and returns the recorded events in the same order, without
+//! any I/O, so that a program can be run again with the exact events it saw
+//! when the log was recorded.
+//!
+//! Events of [`Registration`]s, such as those of the [`channel`] module, are
+//! queued in user space and not recorded.
+//!
+//! # Replaying
+//!
+//! The replayed program must make the same registrations, in the same order,
+//! as the recorded program did. Registrations are matched by their kind
+//! (register, reregister or deregister), token, interests and trigger, but
+//! not by their file descriptor, which may differ between runs. The replayer
+//! returns the recorded result of each registration and each poll, including
+//! errors. Once the program diverges from the log, or calls `Poll::poll` past
+//! the end of the log, the replayer returns an `InvalidData` or
+//! `UnexpectedEof` error.
+//!
+//! Polling doesn't block during replay, the times at which the events were
+//! returned are available from [`Replayer::elapsed`].
+//!
+//! # Log format
+//!
+//! The log starts with the 8 byte header `mio-log` followed by the format
+//! version, 1. Integers are encoded as unsigned [LEB128] varints. Each record
+//! starts with a tag byte and the time at which the call returned, in
+//! microseconds since the recorder was created, followed by:
+//!
+//! | Tag | Call         | Fields                                              |
+//! |-----|--------------|-----------------------------------------------------|
+//! | 1   | `register`   | file descriptor, token, interests, trigger, result  |
+//! | 2   | `reregister` | file descriptor, token, interests, trigger, result  |
+//! | 3   | `deregister` | file descriptor, token, result                      |
+//! | 4   | `poll`       | timeout, result, number of events, events           |
+//!
+//! * Interests are a byte with bit 0 set for readable, bit 1 for writable,
+//!   bit 2 for AIO and bit 3 for LIO interests.
+//! * The trigger is a byte, 0 for edge, 1 for level and 2 for oneshot.
+//! * The timeout is 0 for no timeout, or the timeout in microseconds plus 1.
+//! * The result is 0 for success, 1 for an error without an OS error code or
+//!   the OS error code plus 2. The number of events and the events are only
+//!   present on success.
+//! * An event is its token followed by a readiness byte with bit 0 set for
+//!   readable, bit 1 for writable, bit 2 for error, bit 3 for hup and bit 4
+//!   for priority readiness.
+//!
+//! # Examples
+//!
+//! ```
+//! # use std::error::Error;
+//! # fn try_main() -> Result<(), Box<dyn Error>> {
+//! use mio::record::{Recorder, Replayer};
+//! use mio::{Events, Poll, Token, Waker};
+//! use std::fs::File;
+//! use std::io::BufWriter;
+//! use std::time::Duration;
+//!
+//! // Record a poll returning a wake up.
+//! let path = std::env::temp_dir().join("mio-record-example.log");
+//! {
+//!     let log = BufWriter::new(File::create(&path)?);
+//!     let mut poll = Poll::with_selector(Recorder::new(log)?);
+//!     let mut events = Events::with_capacity(8);
+//!     let waker = Waker::new(poll.registry(), Token(10))?;
+//!     waker.wake()?;
+//!     poll.poll(&mut events, Some(Duration::from_secs(1)))?;
+//! }
+//!
+//! // Replay the log, returning the same event.
+//! let mut poll = Poll::with_selector(Replayer::new(File::open(&path)?)?);
+//! let mut events = Events::with_capacity(8);
+//! poll.poll(&mut events, None)?;
+//! assert_eq!(events.iter().next().unwrap().token(), Token(10));
+//! #     Ok(())
+//! # }
+//! #
+//! # fn main() {
+//! #     try_main().unwrap();
+//! # }
+//! ```
+//!
+//! [`Registry`]: crate::Registry
+//! [`Registration`]: crate::event::Registration
+//! [`channel`]: crate::channel
+//! [LEB128]: https://en.wikipedia.org/wiki/LEB128
+
+mod recorder;
+mod replayer;
+
+pub use self::recorder::Recorder;
+pub use self::replayer::Replayer;
+
+use crate::event::Event;
+use crate::{Interests, Trigger};
+
+use std::io::{self, Read, Write};
+use std::time::Duration;
+
+const HEADER: &[u8; 8] = b"mio-log\x01";
+
+const REGISTER: u8 = 1;
+const REREGISTER: u8 = 2;
+const DEREGISTER: u8 = 3;
+const POLL: u8 = 4;
+
+const READABLE: u8 = 1;
+const WRITABLE: u8 = 1 << 1;
+const ERROR: u8 = 1 << 2;
+const HUP: u8 = 1 << 3;
+const PRIORITY: u8 = 1 << 4;
+
+fn write_varint<W: Write>(writer: &mut W, mut n: u64) -> io::Result<()> {
+    let mut buf = [0; 10];
+    let mut len = 0;
+    loop {
+        let byte = (n & 0x7f) as u8;
+        n >>= 7;
+        if n == 0 {
+            buf[len] = byte;
+            len += 1;
+            break;
+        }
+        buf[len] = byte | 0x80;
+        len += 1;
+    }
+    writer.write_all(&buf[..len])
+}
+
+fn read_varint<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut n = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = read_u8(reader)?;
+        n |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(n);
+        }
+    }
+    Err(invalid("varint too long"))
+}
+
+fn read_u8<R: Read>(reader: &mut R) -> io::Result<u8> {
+    let mut byte = [0];
+    reader.read_exact(&mut byte)?;
+    Ok(byte[0])
+}
+
+fn micros(duration: Duration) -> u64 {
+    duration
+        .as_secs()
+        .saturating_mul(1_000_000)
+        .saturating_add(u64::from(duration.subsec_micros()))
+}
+
+fn interests_bits(interests: Interests) -> u8 {
+    let mut bits = 0;
+    if interests.is_readable() {
+        bits |= 1;
+    }
+    if interests.is_writable() {
+        bits |= 1 << 1;
+    }
+    if interests.is_aio() {
+        bits |= 1 << 2;
+    }
+    if interests.is_lio() {
+        bits |= 1 << 3;
+    }
+    bits
+}
+
+fn trigger_bits(trigger: Trigger) -> u8 {
+    match trigger {
+        Trigger::Edge => 0,
+        Trigger::Level => 1,
+        Trigger::Oneshot => 2,
+    }
+}
+
+fn readiness_bits(event: &Event) -> u8 {
+    let mut bits = 0;
+    if event.is_readable() {
+        bits |= READABLE;
+    }
+    if event.is_writable() {
+        bits |= WRITABLE;
+    }
+    if event.is_error() {
+        bits |= ERROR;
+    }
+    if event.is_hup() {
+        bits |= HUP;
+    }
+    if event.is_priority() {
+        bits |= PRIORITY;
+    }
+    bits
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
@@ -0,0 +1,213 @@
+use crate::record::{
+    interests_bits, micros, readiness_bits, trigger_bits, write_varint, DEREGISTER, HEADER, POLL,
+    REGISTER, REREGISTER,
+};
+use crate::{selector, Events, Interests, Token, Trigger};
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A selector recording the events returned by another selector, and the
+/// registrations made with it, to a log.
+///
+/// The log is written to `W`, which should be buffered, e.g. using
+/// `BufWriter`. It's flushed after each poll, so that the log is complete up
+/// to the last poll if the program crashes. Errors writing the log are
+/// returned by the call being recorded.
+///
+/// See the [module documentation] for the format of the log.
+///
+/// [module documentation]: crate::record
+pub struct Recorder<W> {
+    selector: Box<dyn selector::Selector>,
+    state: Mutex<State<W>>,
+}
+
+struct State<W> {
+    writer: W,
+    start: Instant,
+    /// Tokens of the registered file descriptors, recorded by `deregister`.
+    tokens: HashMap<RawFd, Token>,
+}
+
+impl<W> Recorder<W>
+where
+    W: Write + Send + 'static,
+{
+    /// Create a new recorder, recording the default selector of the
+    /// platform, see the [`selector`] module.
+    ///
+    /// [`selector`]: crate::selector
+    pub fn new(writer: W) -> io::Result<Recorder<W>> {
+        let selector = selector::default()?;
+        Recorder::with_selector(selector, writer)
+    }
+
+    /// Create a new recorder, recording `selector`.
+    pub fn with_selector<S>(selector: S, mut writer: W) -> io::Result<Recorder<W>>
+    where
+        S: selector::Selector,
+    {
+        writer.write_all(HEADER)?;
+        Ok(Recorder {
+            selector: Box::new(selector),
+            state: Mutex::new(State {
+                writer,
+                start: Instant::now(),
+                tokens: HashMap::new(),
+            }),
+        })
+    }
+
+    fn registration(
+        &self,
+        tag: u8,
+        fd: RawFd,
+        token: Token,
+        interests: Option<(Interests, Trigger)>,
+        result: io::Result<()>,
+    ) -> io::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        match (tag, &result) {
+            (DEREGISTER, &Ok(())) => {
+                state.tokens.remove(&fd);
+            }
+            (_, &Ok(())) => {
+                state.tokens.insert(fd, token);
+            }
+            _ => {}
+        }
+        state.record(tag, |writer| {
+            write_varint(writer, fd as u64)?;
+            write_varint(writer, usize::from(token) as u64)?;
+            if let Some((interests, trigger)) = interests {
+                writer.write_all(&[interests_bits(interests), trigger_bits(trigger)])?;
+            }
+            write_result(writer, &result)
+        })?;
+        result
+    }
+}
+
+impl<W: Write> State<W> {
+    /// Write a record with `tag` and the current time, followed by the fields
+    /// written by `f`.
+    fn record<F>(&mut self, tag: u8, f: F) -> io::Result<()>
+    where
+        F: FnOnce(&mut W) -> io::Result<()>,
+    {
+        let elapsed = micros(self.start.elapsed());
+        self.writer.write_all(&[tag])?;
+        write_varint(&mut self.writer, elapsed)?;
+        f(&mut self.writer)
+    }
+}
+
+fn write_result<W: Write, T>(writer: &mut W, result: &io::Result<T>) -> io::Result<()> {
+    let code = match *result {
+        Ok(_) => 0,
+        Err(ref err) => match err.raw_os_error() {
+            Some(errno) => errno as u64 + 2,
+            None => 1,
+        },
+    };
+    write_varint(writer, code)
+}
+
+impl<W> selector::Selector for Recorder<W>
+where
+    W: Write + Send + 'static,
+{
+    fn register(
+        &self,
+        fd: RawFd,
+        token: Token,
+        interests: Interests,
+        trigger: Trigger,
+    ) -> io::Result<()> {
+        let result = self.selector.register(fd, token, interests, trigger);
+        self.registration(REGISTER, fd, token, Some((interests, trigger)), result)
+    }
+
+    fn reregister(
+        &self,
+        fd: RawFd,
+        token: Token,
+        interests: Interests,
+        trigger: Trigger,
+    ) -> io::Result<()> {
+        let result = self.selector.reregister(fd, token, interests, trigger);
+        self.registration(REREGISTER, fd, token, Some((interests, trigger)), result)
+    }
+
+    fn deregister(&self, fd: RawFd) -> io::Result<()> {
+        let result = self.selector.deregister(fd);
+        let token = self
+            .state
+            .lock()
+            .unwrap()
+            .tokens
+            .get(&fd)
+            .cloned()
+            .unwrap_or(Token(0));
+        self.registration(DEREGISTER, fd, token, None, result)
+    }
+
+    fn select(&self, events: &mut Events, timeout: Option<Duration>) -> io::Result<()> {
+        let result = self.selector.select(events, timeout);
+        let mut state = self.state.lock().unwrap();
+        state.record(POLL, |writer| {
+            let timeout = timeout.map_or(0, |timeout| micros(timeout).saturating_add(1));
+            write_varint(writer, timeout)?;
+            write_result(writer, &result)?;
+            if result.is_ok() {
+                write_varint(writer, events.iter().count() as u64)?;
+                for event in events.iter() {
+                    write_varint(writer, usize::from(event.token()) as u64)?;
+                    writer.write_all(&[readiness_bits(event)])?;
+                }
+            }
+            writer.flush()
+        })?;
+        result
+    }
+
+    fn waker(&self, token: Token) -> io::Result<Box<dyn selector::Wake>> {
+        self.selector.waker(token)
+    }
+
+    fn hooks(&self) -> Option<selector::Hooks<'_>> {
+        Some(selector::Hooks::new(self))
+    }
+}
+
+impl<W> selector::Hook for Recorder<W> {
+    fn rearm(&self, fd: RawFd, interests: Interests) {
+        if let Some(hooks) = self.selector.hooks() {
+            hooks.rearm(fd, interests)
+        }
+    }
+
+    fn release(&self, fd: RawFd) {
+        self.state.lock().unwrap().tokens.remove(&fd);
+        if let Some(hooks) = self.selector.hooks() {
+            hooks.release(fd)
+        }
+    }
+}
+
+impl<W> AsRawFd for Recorder<W> {
+    fn as_raw_fd(&self) -> RawFd {
+        self.selector.as_raw_fd()
+    }
+}
+
+impl<W> fmt::Debug for Recorder<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Recorder").finish()
+    }
+}
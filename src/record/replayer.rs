@@ -0,0 +1,319 @@
+use crate::record::{
+    interests_bits, invalid, read_u8, read_varint, trigger_bits, DEREGISTER, ERROR, HEADER, HUP,
+    POLL, PRIORITY, READABLE, REGISTER, REREGISTER, WRITABLE,
+};
+use crate::{selector, Events, Interests, Token, Trigger};
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::io::{self, Read};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// A selector replaying a log written by a [`Recorder`].
+///
+/// Polling returns the recorded events without blocking, registrations
+/// return the recorded results, see the [module documentation]. A clone
+/// shares the state of the original, so that a clone can be used to inspect
+/// the replay after the original is passed to [`Poll::with_selector`].
+///
+/// [`Recorder`]: crate::record::Recorder
+/// [module documentation]: crate::record
+/// [`Poll::with_selector`]: crate::Poll::with_selector
+#[derive(Clone)]
+pub struct Replayer {
+    state: Arc<Mutex<State>>,
+}
+
+struct State {
+    records: VecDeque<Record>,
+    /// Time of the last replayed record.
+    elapsed: Duration,
+    /// Tokens of the file descriptors registered during the replay.
+    tokens: HashMap<RawFd, Token>,
+}
+
+struct Record {
+    tag: u8,
+    time: Duration,
+    kind: Kind,
+    /// 0 for success, see the log format.
+    result: u64,
+}
+
+enum Kind {
+    Registration {
+        token: Token,
+        /// Interests and trigger bits, if not a deregistration.
+        interests: Option<(u8, u8)>,
+    },
+    Poll {
+        events: VecDeque<(Token, u8)>,
+    },
+}
+
+impl Replayer {
+    /// Create a new replayer, reading the entire log from `reader`.
+    ///
+    /// A truncated last record, e.g. of a program that crashed while the log
+    /// was written, is ignored.
+    pub fn new<R: Read>(mut reader: R) -> io::Result<Replayer> {
+        let mut header = [0; 8];
+        reader.read_exact(&mut header)?;
+        if &header != HEADER {
+            return Err(invalid("not a mio log, or an unsupported version"));
+        }
+
+        let mut records = VecDeque::new();
+        loop {
+            let mut tag = [0];
+            if reader.read(&mut tag)? == 0 {
+                break;
+            }
+            match read_record(&mut reader, tag[0]) {
+                Ok(record) => records.push_back(record),
+                Err(ref err) if err.kind() == io::ErrorKind::UnexpectedEof => break,
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(Replayer {
+            state: Arc::new(Mutex::new(State {
+                records,
+                elapsed: Duration::from_millis(0),
+                tokens: HashMap::new(),
+            })),
+        })
+    }
+
+    /// Returns the time of the last replayed call, since the start of the
+    /// recording.
+    pub fn elapsed(&self) -> Duration {
+        self.state.lock().unwrap().elapsed
+    }
+
+    /// Returns the number of recorded calls that aren't yet replayed.
+    pub fn remaining(&self) -> usize {
+        self.state.lock().unwrap().records.len()
+    }
+
+    /// Replay the registration with `tag` of `token`.
+    fn registration(
+        &self,
+        tag: u8,
+        token: Token,
+        interests: Option<(Interests, Trigger)>,
+    ) -> io::Result<()> {
+        let interests = interests
+            .map(|(interests, trigger)| (interests_bits(interests), trigger_bits(trigger)));
+        let mut state = self.state.lock().unwrap();
+        let matches = match state.records.front() {
+            Some(&Record {
+                tag: recorded_tag,
+                kind:
+                    Kind::Registration {
+                        token: recorded_token,
+                        interests: recorded_interests,
+                    },
+                ..
+            }) => recorded_tag == tag && recorded_token == token && recorded_interests == interests,
+            _ => false,
+        };
+        if !matches {
+            return Err(state.diverged());
+        }
+        let record = state.records.pop_front().unwrap();
+        state.elapsed = record.time;
+        result(record.result)
+    }
+}
+
+impl State {
+    /// Returns the error for a replay that no longer matches the log.
+    fn diverged(&self) -> io::Error {
+        match self.records.front() {
+            Some(record) => io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "replay diverged from the log, expected a {} call recorded at {:?}",
+                    match record.tag {
+                        REGISTER => "register",
+                        REREGISTER => "reregister",
+                        DEREGISTER => "deregister",
+                        _ => "poll",
+                    },
+                    record.time
+                ),
+            ),
+            None => end_of_log(),
+        }
+    }
+}
+
+fn read_record<R: Read>(reader: &mut R, tag: u8) -> io::Result<Record> {
+    let time = Duration::from_micros(read_varint(reader)?);
+    let kind = match tag {
+        REGISTER | REREGISTER | DEREGISTER => {
+            let _fd = read_varint(reader)?;
+            let token = Token(read_varint(reader)? as usize);
+            let interests = if tag == DEREGISTER {
+                None
+            } else {
+                Some((read_u8(reader)?, read_u8(reader)?))
+            };
+            Kind::Registration { token, interests }
+        }
+        POLL => {
+            let _timeout = read_varint(reader)?;
+            Kind::Poll {
+                events: VecDeque::new(),
+            }
+        }
+        _ => return Err(invalid("unknown record in the log")),
+    };
+    let result = read_varint(reader)?;
+    let kind = match kind {
+        Kind::Poll { mut events } if result == 0 => {
+            for _ in 0..read_varint(reader)? {
+                let token = Token(read_varint(reader)? as usize);
+                events.push_back((token, read_u8(reader)?));
+            }
+            Kind::Poll { events }
+        }
+        kind => kind,
+    };
+    Ok(Record {
+        tag,
+        time,
+        kind,
+        result,
+    })
+}
+
+fn result(code: u64) -> io::Result<()> {
+    match code {
+        0 => Ok(()),
+        1 => Err(io::Error::new(io::ErrorKind::Other, "recorded error")),
+        errno => Err(io::Error::from_raw_os_error((errno - 2) as i32)),
+    }
+}
+
+fn end_of_log() -> io::Error {
+    io::Error::new(io::ErrorKind::UnexpectedEof, "end of the recorded log")
+}
+
+/// Convert the readiness `bits` of the log to `poll` flags.
+fn revents(bits: u8) -> libc::c_short {
+    let mut revents = 0;
+    if bits & READABLE != 0 {
+        revents |= libc::POLLIN;
+    }
+    if bits & WRITABLE != 0 {
+        revents |= libc::POLLOUT;
+    }
+    if bits & ERROR != 0 {
+        revents |= libc::POLLERR;
+    }
+    if bits & HUP != 0 {
+        revents |= libc::POLLHUP;
+    }
+    if bits & PRIORITY != 0 {
+        revents |= libc::POLLPRI;
+    }
+    revents
+}
+
+impl selector::Selector for Replayer {
+    fn register(
+        &self,
+        fd: RawFd,
+        token: Token,
+        interests: Interests,
+        trigger: Trigger,
+    ) -> io::Result<()> {
+        self.registration(REGISTER, token, Some((interests, trigger)))?;
+        self.state.lock().unwrap().tokens.insert(fd, token);
+        Ok(())
+    }
+
+    fn reregister(
+        &self,
+        fd: RawFd,
+        token: Token,
+        interests: Interests,
+        trigger: Trigger,
+    ) -> io::Result<()> {
+        self.registration(REREGISTER, token, Some((interests, trigger)))?;
+        self.state.lock().unwrap().tokens.insert(fd, token);
+        Ok(())
+    }
+
+    fn deregister(&self, fd: RawFd) -> io::Result<()> {
+        let token = self.state.lock().unwrap().tokens.get(&fd).cloned();
+        // Not registered during the replay, recorded with token 0.
+        self.registration(DEREGISTER, token.unwrap_or(Token(0)), None)?;
+        self.state.lock().unwrap().tokens.remove(&fd);
+        Ok(())
+    }
+
+    fn select(&self, events: &mut Events, _timeout: Option<Duration>) -> io::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let (time, code, done) = match state.records.front_mut() {
+            Some(&mut Record {
+                time,
+                result,
+                kind: Kind::Poll {
+                    events: ref mut recorded,
+                },
+                ..
+            }) => {
+                while let Some(&(token, bits)) = recorded.front() {
+                    if !events.sys().push_poll(token, revents(bits)) {
+                        break;
+                    }
+                    recorded.pop_front();
+                }
+                // Events that don't fit are returned by the next call.
+                (time, result, recorded.is_empty())
+            }
+            _ => return Err(state.diverged()),
+        };
+        if done {
+            state.records.pop_front();
+        }
+        state.elapsed = time;
+        result(code)
+    }
+
+    fn waker(&self, _token: Token) -> io::Result<Box<dyn selector::Wake>> {
+        Ok(Box::new(Waker))
+    }
+}
+
+/// Waker of the replayer, which does nothing as wake ups are returned as
+/// recorded.
+struct Waker;
+
+impl selector::Wake for Waker {
+    fn wake(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl AsRawFd for Replayer {
+    /// Returns -1, as the replayer doesn't have a file descriptor.
+    fn as_raw_fd(&self) -> RawFd {
+        -1
+    }
+}
+
+impl fmt::Debug for Replayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock().unwrap();
+        f.debug_struct("Replayer")
+            .field("elapsed", &state.elapsed)
+            .field("remaining", &state.records.len())
+            .finish()
+    }
+}
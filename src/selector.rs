@@ -0,0 +1,504 @@
+//! Selectors backing [`Poll`].
+//!
+//! A selector is the part of [`Poll`] that monitors file descriptors for
+//! readiness, e.g. epoll on Linux. [`Poll::new`] uses the selector of the
+//! platform, [`Poll::with_selector`] uses any implementation of the
+//! [`Selector`] trait. This allows the selector to be picked at runtime, or
+//! to wrap a selector, e.g. to log all registrations.
+//!
+//! The selectors provided by Mio are:
+//!
+//! | Selector  | Platforms                  | Default                       |
+//! |-----------|----------------------------|-------------------------------|
+//! | [`Epoll`] | Android, Linux and Solaris | Yes                           |
+//! | `Kqueue`  | BSDs, iOS and macOS        | Yes                           |
+//! | `Uring`   | Android and Linux          | With the `io_uring` feature   |
+//! | [`Poll`]  | All Unix platforms         | With the `force_poll` feature |
+//!
+//! # Examples
+//!
+//! Using the `poll(2)` based selector.
+//!
+//! ```
+//! # use std::error::Error;
+//! # fn try_main() -> Result<(), Box<dyn Error>> {
+//! use mio::{selector, Events, Poll};
+//! use std::time::Duration;
+//!
+//! let mut poll = Poll::with_selector(selector::Poll::new()?);
+//! let mut events = Events::with_capacity(8);
+//!
+//! poll.poll(&mut events, Some(Duration::from_millis(10)))?;
+//! assert!(events.is_empty());
+//! #     Ok(())
+//! # }
+//! #
+//! # fn main() {
+//! #     try_main().unwrap();
+//! # }
+//! ```
+//!
+//! [`Poll`]: crate::Poll
+//! [`Poll::new`]: crate::Poll::new
+//! [`Poll::with_selector`]: crate::Poll::with_selector
+
+#[cfg(feature = "fault")]
+use crate::fault;
+use crate::sys::unix;
+use crate::{Events, Interests, Token, Trigger};
+
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::time::Duration;
+use std::{fmt, io};
+
+/// A selector, used by [`Poll`] to monitor file descriptors for readiness.
+///
+/// Registrations are made by [`Evented`] handles, using [`EventedFd`], and
+/// may be changed by any thread while another thread is blocked in
+/// [`select`]. Handles are never registered with `Token(usize::MAX)`, `Poll`
+/// uses this token for its own [`waker`] and ignores events with it.
+///
+/// The file descriptor returned by the [`AsRawFd`] implementation is returned
+/// by `Poll`'s implementation, e.g. to wait for `Poll` in another event loop,
+/// it should be readable if `select` would return events. Selectors without a
+/// file descriptor, such as the simulated selector of the `sim` module and the
+/// replayer of the `record` module, return -1.
+///
+/// [`Poll`]: crate::Poll
+/// [`Evented`]: crate::event::Evented
+/// [`EventedFd`]: crate::unix::EventedFd
+/// [`select`]: Selector::select
+/// [`waker`]: Selector::waker
+pub trait Selector: AsRawFd + Send + Sync + 'static {
+    /// Register `fd` with `interests`, returning events for it with `token`.
+    ///
+    /// Returns an `AlreadyExists` error if `fd` is already registered.
+    fn register(
+        &self,
+        fd: RawFd,
+        token: Token,
+        interests: Interests,
+        trigger: Trigger,
+    ) -> io::Result<()>;
+
+    /// Replace the registration of `fd`.
+    ///
+    /// Returns a `NotFound` error if `fd` isn't registered.
+    fn reregister(
+        &self,
+        fd: RawFd,
+        token: Token,
+        interests: Interests,
+        trigger: Trigger,
+    ) -> io::Result<()>;
+
+    /// Remove the registration of `fd`.
+    ///
+    /// Returns a `NotFound` error if `fd` isn't registered.
+    fn deregister(&self, fd: RawFd) -> io::Result<()>;
+
+    /// Wait for readiness events, pushing them into `events`.
+    ///
+    /// `events` is empty when this is called, once it's full further events
+    /// must be returned by the next call. This blocks until at least one event
+    /// is returned, a [`waker`] is woken or `timeout` elapses. Like
+    /// [`Poll::poll`] this may return without any events.
+    ///
+    /// [`waker`]: Selector::waker
+    /// [`Poll::poll`]: crate::Poll::poll
+    fn select(&self, events: &mut Events, timeout: Option<Duration>) -> io::Result<()>;
+
+    /// Create a waker that wakes up a thread blocked in [`select`], which
+    /// returns a readable event with `token`. If no thread is blocked the next
+    /// call to `select` returns the event.
+    ///
+    /// This is called by [`Waker::new`], the waker is dropped with the
+    /// `Waker`.
+    ///
+    /// [`select`]: Selector::select
+    /// [`Waker::new`]: crate::Waker::new
+    fn waker(&self, token: Token) -> io::Result<Box<dyn Wake>>;
+
+    /// Returns the hooks called by Mio's handles after their I/O operations
+    /// and before they're closed, which some of Mio's selectors need, e.g.
+    /// [`Poll`] to emulate edge triggers.
+    ///
+    /// Selectors wrapping another selector must return the hooks of the
+    /// wrapped selector. The default implementation returns `None`, i.e. no
+    /// hooks.
+    fn hooks(&self) -> Option<Hooks<'_>> {
+        None
+    }
+}
+
+/// A waker created by [`Selector::waker`].
+pub trait Wake: Send + Sync + 'static {
+    /// Wake up the selector, see [`Selector::waker`].
+    fn wake(&self) -> io::Result<()>;
+}
+
+/// The hooks of a selector provided by Mio, see [`Selector::hooks`].
+///
+/// The hooks are internal to Mio and may change in any release, this type can
+/// only be obtained from the selectors provided by Mio and passed on by
+/// selectors wrapping them.
+#[derive(Clone, Copy)]
+pub struct Hooks<'a> {
+    hook: &'a dyn Hook,
+}
+
+impl<'a> Hooks<'a> {
+    pub(crate) fn new(hook: &'a dyn Hook) -> Hooks<'a> {
+        Hooks { hook }
+    }
+
+    pub(crate) fn rearm(self, fd: RawFd, interests: Interests) {
+        self.hook.rearm(fd, interests)
+    }
+
+    pub(crate) fn release(self, fd: RawFd) {
+        self.hook.release(fd)
+    }
+
+    #[cfg(feature = "fault")]
+    pub(crate) fn fault(self, fd: RawFd, operation: fault::Operation) -> Option<fault::Fault> {
+        self.hook.fault(fd, operation)
+    }
+}
+
+impl<'a> fmt::Debug for Hooks<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Hooks").finish()
+    }
+}
+
+/// The hooks implemented by Mio's selectors, called by Mio's handles through
+/// `SelectorId`.
+pub(crate) trait Hook {
+    /// Arm `interests` of `fd` again, called after an operation, i.e. a read
+    /// (`READABLE`) or a write (`WRITABLE`), returned a `WouldBlock` error or
+    /// after a successful read.
+    ///
+    /// Selectors emulating edge triggers, such as `Poll`, stop returning
+    /// events for `fd` once they're returned, until this is called.
+    fn rearm(&self, _fd: RawFd, _interests: Interests) {}
+
+    /// Remove the registration of `fd`, called when the handle is dropped,
+    /// before `fd` is closed. Handles converted into a raw file descriptor
+    /// keep their registration.
+    ///
+    /// Selectors that keep the registration of closed file descriptors, such
+    /// as `Poll`, must remove them so that they don't apply to a file opened
+    /// with the same file descriptor.
+    fn release(&self, _fd: RawFd) {}
+
+    /// Returns the fault to inject in `operation` of the handle registered
+    /// with `fd`, called before the operation. See the `fault` module.
+    #[cfg(feature = "fault")]
+    fn fault(&self, _fd: RawFd, _operation: fault::Operation) -> Option<fault::Fault> {
+        None
+    }
+}
+
+/// Allows a selector picked at runtime to be passed to [`Poll::with_selector`].
+///
+/// [`Poll::with_selector`]: crate::Poll::with_selector
+impl Selector for Box<dyn Selector> {
+    fn register(
+        &self,
+        fd: RawFd,
+        token: Token,
+        interests: Interests,
+        trigger: Trigger,
+    ) -> io::Result<()> {
+        (**self).register(fd, token, interests, trigger)
+    }
+
+    fn reregister(
+        &self,
+        fd: RawFd,
+        token: Token,
+        interests: Interests,
+        trigger: Trigger,
+    ) -> io::Result<()> {
+        (**self).reregister(fd, token, interests, trigger)
+    }
+
+    fn deregister(&self, fd: RawFd) -> io::Result<()> {
+        (**self).deregister(fd)
+    }
+
+    fn select(&self, events: &mut Events, timeout: Option<Duration>) -> io::Result<()> {
+        (**self).select(events, timeout)
+    }
+
+    fn waker(&self, token: Token) -> io::Result<Box<dyn Wake>> {
+        (**self).waker(token)
+    }
+
+    fn hooks(&self) -> Option<Hooks<'_>> {
+        (**self).hooks()
+    }
+}
+
+impl AsRawFd for Box<dyn Selector> {
+    fn as_raw_fd(&self) -> RawFd {
+        (**self).as_raw_fd()
+    }
+}
+
+/// Selector backed by [epoll].
+///
+/// [epoll]: http://man7.org/linux/man-pages/man7/epoll.7.html
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "solaris"))]
+#[derive(Debug)]
+pub struct Epoll {
+    pub(crate) sys: unix::epoll::Selector,
+}
+
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "solaris"))]
+impl Epoll {
+    /// Create a new epoll instance.
+    pub fn new() -> io::Result<Epoll> {
+        unix::epoll::Selector::new().map(|sys| Epoll { sys })
+    }
+}
+
+/// Selector backed by [kqueue].
+///
+/// [kqueue]: https://www.freebsd.org/cgi/man.cgi?query=kqueue&sektion=2
+#[cfg(any(
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+#[derive(Debug)]
+pub struct Kqueue {
+    pub(crate) sys: unix::kqueue::Selector,
+}
+
+#[cfg(any(
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+impl Kqueue {
+    /// Create a new kqueue.
+    pub fn new() -> io::Result<Kqueue> {
+        unix::kqueue::Selector::new().map(|sys| Kqueue { sys })
+    }
+}
+
+/// Selector backed by [io_uring] poll requests, requires Linux 5.13 or later.
+///
+/// Registration changes are batched and submitted by the next call to
+/// `select`. As in-flight poll requests keep the file open, file descriptors
+/// registered using [`EventedFd`], or taken from Mio's types using
+/// `into_raw_fd`, must be deregistered before they are closed.
+///
+/// [io_uring]: http://man7.org/linux/man-pages/man7/io_uring.7.html
+/// [`EventedFd`]: crate::unix::EventedFd
+#[cfg(all(feature = "io_uring", any(target_os = "linux", target_os = "android")))]
+#[derive(Debug)]
+pub struct Uring {
+    pub(crate) sys: unix::uring::Selector,
+}
+
+#[cfg(all(feature = "io_uring", any(target_os = "linux", target_os = "android")))]
+impl Uring {
+    /// Create a new io_uring instance.
+    pub fn new() -> io::Result<Uring> {
+        unix::uring::Selector::new().map(|sys| Uring { sys })
+    }
+}
+
+/// Selector backed by [poll], available on all Unix platforms.
+///
+/// `poll` is level-triggered, edge triggers are emulated: once an event is
+/// returned its interests are disarmed until the handle is read from, a write
+/// returns a [`WouldBlock`] error, or the handle is reregistered. Mio's own
+/// types do this automatically, handles registered using [`EventedFd`] must
+/// be reregistered before waiting for the next event.
+///
+/// `poll` doesn't know when a file descriptor is closed. Mio's own types
+/// remove their registration when they're dropped. File descriptors
+/// registered using `EventedFd`, or taken from Mio's types using
+/// `into_raw_fd`, must be deregistered before they're closed.
+///
+/// As `poll` has no file descriptor of its own, the first call to
+/// `as_raw_fd` creates an epoll or kqueue instance that mirrors the armed
+/// registrations and returns its file descriptor.
+///
+/// Not to be confused with [`mio::Poll`], which uses a selector.
+///
+/// [poll]: http://man7.org/linux/man-pages/man2/poll.2.html
+/// [`WouldBlock`]: std::io::ErrorKind::WouldBlock
+/// [`EventedFd`]: crate::unix::EventedFd
+/// [`mio::Poll`]: crate::Poll
+#[derive(Debug)]
+pub struct Poll {
+    pub(crate) sys: unix::poll::Selector,
+}
+
+impl Poll {
+    /// Create a new `poll(2)` based selector.
+    pub fn new() -> io::Result<Poll> {
+        unix::poll::Selector::new().map(|sys| Poll { sys })
+    }
+}
+
+/// Implements `Selector` and `AsRawFd` by calling the methods of the `sys`
+/// field, creating wakers of the given type. With `with_hooks` the `Hook`
+/// implementation of `sys` is returned as the hooks of the selector.
+macro_rules! impl_selector {
+    ($( #[$meta: meta] )* $name: ident, $waker: ty) => {
+        impl_selector!($( #[$meta] )* $name, $waker, {});
+    };
+    ($( #[$meta: meta] )* $name: ident, $waker: ty, with_hooks) => {
+        impl_selector!($( #[$meta] )* $name, $waker, {
+            fn hooks(&self) -> Option<Hooks<'_>> {
+                Some(Hooks::new(&self.sys))
+            }
+        });
+    };
+    ($( #[$meta: meta] )* $name: ident, $waker: ty, { $( $hooks: tt )* }) => {
+        $( #[$meta] )*
+        impl Selector for $name {
+            fn register(
+                &self,
+                fd: RawFd,
+                token: Token,
+                interests: Interests,
+                trigger: Trigger,
+            ) -> io::Result<()> {
+                self.sys.register(fd, token, interests, trigger)
+            }
+
+            fn reregister(
+                &self,
+                fd: RawFd,
+                token: Token,
+                interests: Interests,
+                trigger: Trigger,
+            ) -> io::Result<()> {
+                self.sys.reregister(fd, token, interests, trigger)
+            }
+
+            fn deregister(&self, fd: RawFd) -> io::Result<()> {
+                self.sys.deregister(fd)
+            }
+
+            fn select(&self, events: &mut Events, timeout: Option<Duration>) -> io::Result<()> {
+                self.sys.select(events.sys(), timeout)
+            }
+
+            fn waker(&self, token: Token) -> io::Result<Box<dyn Wake>> {
+                <$waker>::new(&self.sys, token).map(|waker| Box::new(waker) as Box<dyn Wake>)
+            }
+
+            $( $hooks )*
+        }
+
+        $( #[$meta] )*
+        impl AsRawFd for $name {
+            fn as_raw_fd(&self) -> RawFd {
+                self.sys.as_raw_fd()
+            }
+        }
+    };
+}
+
+impl_selector!(
+    #[cfg(any(target_os = "linux", target_os = "android", target_os = "solaris"))]
+    Epoll,
+    unix::epoll::Waker
+);
+impl_selector!(
+    #[cfg(any(
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "macos",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    Kqueue,
+    unix::kqueue::Waker
+);
+impl_selector!(
+    #[cfg(all(feature = "io_uring", any(target_os = "linux", target_os = "android")))]
+    Uring,
+    unix::uring::Waker,
+    with_hooks
+);
+impl_selector!(Poll, unix::poll::Waker, with_hooks);
+
+/// The default selector, see the module documentation, used by `Poll::new`
+/// without dynamic dispatch.
+#[cfg(feature = "force_poll")]
+pub(crate) type DefaultSelector = Poll;
+#[cfg(feature = "force_poll")]
+pub(crate) type DefaultWaker = unix::poll::Waker;
+
+#[cfg(all(
+    not(feature = "force_poll"),
+    feature = "io_uring",
+    any(target_os = "linux", target_os = "android")
+))]
+pub(crate) type DefaultSelector = Uring;
+#[cfg(all(
+    not(feature = "force_poll"),
+    feature = "io_uring",
+    any(target_os = "linux", target_os = "android")
+))]
+pub(crate) type DefaultWaker = unix::uring::Waker;
+
+#[cfg(all(
+    not(feature = "force_poll"),
+    not(all(feature = "io_uring", any(target_os = "linux", target_os = "android"))),
+    any(target_os = "linux", target_os = "android", target_os = "solaris")
+))]
+pub(crate) type DefaultSelector = Epoll;
+#[cfg(all(
+    not(feature = "force_poll"),
+    not(all(feature = "io_uring", any(target_os = "linux", target_os = "android"))),
+    any(target_os = "linux", target_os = "android", target_os = "solaris")
+))]
+pub(crate) type DefaultWaker = unix::epoll::Waker;
+
+#[cfg(all(
+    not(feature = "force_poll"),
+    any(
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "macos",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    )
+))]
+pub(crate) type DefaultSelector = Kqueue;
+#[cfg(all(
+    not(feature = "force_poll"),
+    any(
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "macos",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    )
+))]
+pub(crate) type DefaultWaker = unix::kqueue::Waker;
+
+/// Create the default selector as a trait object, for selectors wrapping it.
+#[cfg(any(feature = "fault", feature = "record"))]
+pub(crate) fn default() -> io::Result<Box<dyn Selector>> {
+    DefaultSelector::new().map(|selector| Box::new(selector) as Box<dyn Selector>)
+}
@@ -0,0 +1,90 @@
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Virtual clock of a simulation.
+///
+/// The clock starts at zero and only moves forward when it's advanced, either
+/// by hand using [`advance`], or by polling a simulated [`Selector`] with a
+/// timeout. Clones share the same time.
+///
+/// [`advance`]: Clock::advance
+/// [`Selector`]: crate::sim::Selector
+///
+/// # Examples
+///
+/// ```
+/// use mio::sim::Clock;
+/// use std::time::Duration;
+///
+/// let clock = Clock::new();
+/// let start = clock.now();
+///
+/// clock.advance(Duration::from_millis(10));
+/// assert_eq!(clock.elapsed(), Duration::from_millis(10));
+/// assert_eq!(clock.now() - start, Duration::from_millis(10));
+/// ```
+#[derive(Clone)]
+pub struct Clock {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    /// The `Instant` of time zero, see `Clock::now`.
+    start: Instant,
+    elapsed: Mutex<Duration>,
+}
+
+impl Clock {
+    /// Create a new clock, starting at zero.
+    pub fn new() -> Clock {
+        Clock {
+            inner: Arc::new(Inner {
+                start: Instant::now(),
+                elapsed: Mutex::new(Duration::from_secs(0)),
+            }),
+        }
+    }
+
+    /// Returns the time elapsed since the clock started.
+    pub fn elapsed(&self) -> Duration {
+        *self.inner.elapsed.lock().unwrap()
+    }
+
+    /// Returns the current time as an `Instant`.
+    ///
+    /// This is the `Instant` at which the clock was created plus the virtual
+    /// time elapsed since, e.g. to be passed to [`Wheel::expired_at`].
+    ///
+    /// [`Wheel::expired_at`]: crate::time::Wheel::expired_at
+    pub fn now(&self) -> Instant {
+        self.inner.start + self.elapsed()
+    }
+
+    /// Move the clock forward by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        *self.inner.elapsed.lock().unwrap() += duration;
+    }
+
+    /// Move the clock forward to `elapsed`, if it's not already past it.
+    pub(crate) fn advance_to(&self, elapsed: Duration) {
+        let mut current = self.inner.elapsed.lock().unwrap();
+        if elapsed > *current {
+            *current = elapsed;
+        }
+    }
+}
+
+impl Default for Clock {
+    fn default() -> Clock {
+        Clock::new()
+    }
+}
+
+impl fmt::Debug for Clock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Clock")
+            .field("elapsed", &self.elapsed())
+            .finish()
+    }
+}
@@ -0,0 +1,114 @@
+//! Deterministic simulation of [`Poll`], for tests (requires the `sim`
+//! feature).
+//!
+//! The [`Selector`] in this module doesn't use the kernel: readiness events
+//! are scripted per [`Token`], e.g. "token 3 becomes readable at t=5ms", and
+//! time is kept by a virtual [`Clock`]. This makes the results of
+//! [`Poll::poll`] fully reproducible, without sleeping.
+//!
+//! Time only passes when the clock is advanced by hand, using
+//! [`Clock::advance`], or when [`Poll::poll`] is called with a timeout, see
+//! [`Selector`].
+//!
+//! [`TcpStream`], [`TcpListener`] and [`UdpSocket`] are simulated equivalents
+//! of the types in [`mio::net`], connected over an in-process [`Network`]
+//! rather than the kernel. The network can add latency and inject faults,
+//! such as packet loss, partial writes and connection resets, to test error
+//! paths that are hard to trigger using real sockets.
+//!
+//! # Examples
+//!
+//! ```
+//! # use std::error::Error;
+//! # fn try_main() -> Result<(), Box<dyn Error>> {
+//! use mio::sim::{Clock, Selector};
+//! use mio::{Events, Interests, Poll, Token};
+//! use std::time::Duration;
+//!
+//! let clock = Clock::new();
+//! let selector = Selector::new(&clock);
+//! // Token 3 becomes readable at t=5ms.
+//! selector.schedule(Duration::from_millis(5), Token(3), Interests::READABLE);
+//!
+//! let mut poll = Poll::with_selector(selector.clone());
+//! let mut events = Events::with_capacity(8);
+//!
+//! // Nothing is ready yet.
+//! poll.poll(&mut events, Some(Duration::from_millis(0)))?;
+//! assert!(events.is_empty());
+//!
+//! // Polling moves the clock to the scripted event, rather than sleeping.
+//! poll.poll(&mut events, Some(Duration::from_secs(1)))?;
+//! let event = events.iter().next().unwrap();
+//! assert_eq!(event.token(), Token(3));
+//! assert!(event.is_readable());
+//! assert_eq!(clock.elapsed(), Duration::from_millis(5));
+//! #     Ok(())
+//! # }
+//! #
+//! # fn main() {
+//! #     try_main().unwrap();
+//! # }
+//! ```
+//!
+//! Using the simulated network:
+//!
+//! ```
+//! # use std::error::Error;
+//! # fn try_main() -> Result<(), Box<dyn Error>> {
+//! use mio::sim::{Clock, Network, Selector, TcpListener, TcpStream};
+//! use mio::{Events, Interests, Poll, Token};
+//! use std::io::{Read, Write};
+//! use std::time::Duration;
+//!
+//! let clock = Clock::new();
+//! let selector = Selector::new(&clock);
+//! let network = Network::new(&selector, 0);
+//! network.set_latency(Duration::from_millis(10));
+//!
+//! let mut poll = Poll::with_selector(selector);
+//! let mut events = Events::with_capacity(8);
+//!
+//! let addr = "10.0.0.1:80".parse()?;
+//! let listener = TcpListener::bind(&network, addr)?;
+//! poll.registry().register(&listener, Token(0), Interests::READABLE)?;
+//! let mut client = TcpStream::connect(&network, addr)?;
+//! poll.registry().register(&client, Token(1), Interests::WRITABLE)?;
+//!
+//! // The connection request arrives after 10ms.
+//! poll.poll(&mut events, None)?;
+//! let (mut server, _) = listener.accept()?;
+//! poll.registry().register(&server, Token(2), Interests::READABLE)?;
+//!
+//! // The client is connected after a round trip.
+//! poll.poll(&mut events, None)?;
+//! client.write_all(b"hello")?;
+//!
+//! poll.poll(&mut events, None)?;
+//! let mut buf = [0; 8];
+//! assert_eq!(server.read(&mut buf)?, 5);
+//! assert_eq!(clock.elapsed(), Duration::from_millis(30));
+//! #     Ok(())
+//! # }
+//! #
+//! # fn main() {
+//! #     try_main().unwrap();
+//! # }
+//! ```
+//!
+//! [`Poll`]: crate::Poll
+//! [`Poll::poll`]: crate::Poll::poll
+//! [`Token`]: crate::Token
+//! [`mio::net`]: crate::net
+
+mod clock;
+mod net;
+mod selector;
+mod tcp;
+mod udp;
+
+pub use self::clock::Clock;
+pub use self::net::Network;
+pub use self::selector::Selector;
+pub use self::tcp::{TcpListener, TcpStream};
+pub use self::udp::UdpSocket;
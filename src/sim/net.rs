@@ -0,0 +1,824 @@
+use crate::event::SetReadiness;
+use crate::rng::Rng;
+use crate::sim::Selector;
+use crate::Interests;
+
+use std::collections::{HashMap, VecDeque};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr};
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+use std::time::Duration;
+use std::{cmp, fmt, io};
+
+/// Time after which a lost stream segment is retransmitted.
+const RETRANSMIT_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// First port used for sockets bound to port 0 and outgoing connections.
+const EPHEMERAL_PORT: u16 = 49152;
+
+/// Largest payload of a UDP datagram.
+const MAX_DATAGRAM: usize = 65507;
+
+/// In-process network connecting the simulated [`TcpStream`],
+/// [`TcpListener`] and [`UdpSocket`].
+///
+/// Data sent over the network is delivered by the [`Selector`] it's created
+/// with, once its [`Clock`] reaches the time of arrival. Readiness events are
+/// returned through the normal [`Registry`], like those of the types in
+/// [`mio::net`].
+///
+/// By default data arrives immediately (at the current time) and nothing goes
+/// wrong. The network can be configured to add latency and to inject faults:
+///
+/// * [`set_latency`]: the time data takes to arrive.
+/// * [`set_loss`]: the probability a datagram or stream segment is lost. Lost
+///   datagrams are dropped, lost stream segments are retransmitted after
+///   200ms, delaying all data sent after them.
+/// * [`set_reorder`]: the probability a datagram is delayed, so that it
+///   arrives after datagrams sent later.
+/// * [`set_max_write`]: the maximum number of bytes accepted by a single
+///   write to a stream, to test partial writes.
+/// * [`set_buffer_size`]: the number of bytes a stream buffers, writes return
+///   `WouldBlock` once the buffer of the receiving end is full.
+/// * [`set_reset`]: the probability a stream segment resets the connection.
+///
+/// Connections can also be reset explicitly using [`reset`]. The faults are
+/// picked by a random number generator using the `seed`, so a simulation
+/// using the same seed makes the same choices.
+///
+/// Addresses aren't bound to interfaces: any address can be bound and
+/// connected to, a socket bound to an unspecified address (e.g. `0.0.0.0`)
+/// receives data for all addresses with the same port.
+///
+/// [`TcpStream`]: crate::sim::TcpStream
+/// [`TcpListener`]: crate::sim::TcpListener
+/// [`UdpSocket`]: crate::sim::UdpSocket
+/// [`Clock`]: crate::sim::Clock
+/// [`Registry`]: crate::Registry
+/// [`mio::net`]: crate::net
+/// [`set_latency`]: Network::set_latency
+/// [`set_loss`]: Network::set_loss
+/// [`set_reorder`]: Network::set_reorder
+/// [`set_max_write`]: Network::set_max_write
+/// [`set_buffer_size`]: Network::set_buffer_size
+/// [`set_reset`]: Network::set_reset
+/// [`reset`]: Network::reset
+#[derive(Clone)]
+pub struct Network {
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    selector: Selector,
+    state: Mutex<State>,
+}
+
+struct State {
+    rng: Rng,
+    latency: Duration,
+    loss: f64,
+    reorder: f64,
+    reorder_delay: Duration,
+    reset: f64,
+    max_write: Option<usize>,
+    buffer_size: usize,
+    next_port: u16,
+    /// Ids of listeners, streams and sockets are never reused, so that data
+    /// in flight never arrives at the wrong end.
+    next_id: u64,
+    listeners: HashMap<u64, Listener>,
+    listener_addrs: HashMap<SocketAddr, u64>,
+    streams: HashMap<u64, Stream>,
+    sockets: HashMap<u64, Socket>,
+    socket_addrs: HashMap<SocketAddr, u64>,
+}
+
+struct Listener {
+    addr: SocketAddr,
+    /// Connected streams that aren't yet accepted.
+    backlog: VecDeque<u64>,
+    readiness: Readiness,
+}
+
+struct Stream {
+    local: SocketAddr,
+    peer: SocketAddr,
+    /// The other end of the connection, once connected.
+    peer_id: Option<u64>,
+    /// Received data that isn't yet read.
+    recv: VecDeque<u8>,
+    /// The peer shut down writing.
+    recv_eof: bool,
+    read_closed: bool,
+    write_closed: bool,
+    error: Option<io::ErrorKind>,
+    /// Number of bytes sent that haven't yet arrived.
+    in_flight: usize,
+    /// Time of arrival of the last segment sent, as stream segments arrive in
+    /// order.
+    last_arrival: Duration,
+    /// `None` for streams in the backlog of a listener.
+    readiness: Option<Readiness>,
+}
+
+struct Socket {
+    addr: SocketAddr,
+    recv: VecDeque<(Vec<u8>, SocketAddr)>,
+    /// Number of bytes in `recv`.
+    recv_len: usize,
+    readiness: Readiness,
+}
+
+/// The readiness of a simulated type, as last set.
+struct Readiness {
+    set_readiness: SetReadiness,
+    current: Option<Interests>,
+}
+
+impl Network {
+    /// Create a new network, delivering data using `selector`. The faults
+    /// injected are picked using `seed`.
+    pub fn new(selector: &Selector, seed: u64) -> Network {
+        Network {
+            shared: Arc::new(Shared {
+                selector: selector.clone(),
+                state: Mutex::new(State {
+                    rng: Rng::new(seed),
+                    latency: Duration::from_millis(0),
+                    loss: 0.0,
+                    reorder: 0.0,
+                    reorder_delay: Duration::from_millis(0),
+                    reset: 0.0,
+                    max_write: None,
+                    buffer_size: 64 * 1024,
+                    next_port: EPHEMERAL_PORT,
+                    next_id: 0,
+                    listeners: HashMap::new(),
+                    listener_addrs: HashMap::new(),
+                    streams: HashMap::new(),
+                    sockets: HashMap::new(),
+                    socket_addrs: HashMap::new(),
+                }),
+            }),
+        }
+    }
+
+    /// Returns the selector delivering the data.
+    pub fn selector(&self) -> &Selector {
+        &self.shared.selector
+    }
+
+    /// Set the time data takes to arrive, defaults to zero.
+    pub fn set_latency(&self, latency: Duration) {
+        self.state().latency = latency;
+    }
+
+    /// Set the probability, between 0.0 and 1.0, that a datagram or stream
+    /// segment is lost, defaults to zero.
+    ///
+    /// Lost datagrams are dropped. Lost stream segments are retransmitted
+    /// after 200ms, the data sent after them arrives after the retransmitted
+    /// segment.
+    pub fn set_loss(&self, probability: f64) {
+        self.state().loss = probability;
+    }
+
+    /// Set the probability, between 0.0 and 1.0, that a datagram is delayed by
+    /// an additional `delay`, defaults to zero.
+    ///
+    /// Stream segments are never reordered.
+    pub fn set_reorder(&self, probability: f64, delay: Duration) {
+        let mut state = self.state();
+        state.reorder = probability;
+        state.reorder_delay = delay;
+    }
+
+    /// Set the maximum number of bytes accepted by a single write to a
+    /// stream, `None` (the default) accepts as many bytes as fit in the
+    /// buffer.
+    pub fn set_max_write(&self, max: Option<usize>) {
+        self.state().max_write = max;
+    }
+
+    /// Set the number of bytes buffered by a stream or socket, defaults to
+    /// 64 KiB.
+    ///
+    /// Writes to a stream return `WouldBlock` once the data in flight and the
+    /// data buffered by the receiving end reach this size, the stream becomes
+    /// writable again once the receiving end reads the data. Datagrams that
+    /// don't fit in the buffer of the receiving socket are dropped.
+    pub fn set_buffer_size(&self, size: usize) {
+        self.state().buffer_size = size;
+    }
+
+    /// Set the probability, between 0.0 and 1.0, that a stream segment resets
+    /// the connection rather than arriving, defaults to zero.
+    pub fn set_reset(&self, probability: f64) {
+        self.state().reset = probability;
+    }
+
+    /// Reset all connections with either end bound to `addr`.
+    ///
+    /// Both ends of the connection return a `ConnectionReset` error from then
+    /// on, the data in their buffers is lost.
+    pub fn reset(&self, addr: SocketAddr) {
+        let mut state = self.state();
+        let ids: Vec<u64> = state
+            .streams
+            .iter()
+            .filter(|&(_, stream)| stream.local == addr || stream.peer == addr)
+            .map(|(&id, _)| id)
+            .collect();
+        for id in ids {
+            state.fail(id, io::ErrorKind::ConnectionReset);
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.shared.state.lock().unwrap()
+    }
+
+    fn now(&self) -> Duration {
+        self.shared.selector.clock().elapsed()
+    }
+
+    /// Call `f` once the clock reaches `at`, if the network still exists.
+    fn schedule<F>(&self, at: Duration, f: F)
+    where
+        F: FnOnce(&Network, &mut State) + Send + 'static,
+    {
+        let shared: Weak<Shared> = Arc::downgrade(&self.shared);
+        self.shared.selector.schedule_call(at, move || {
+            if let Some(shared) = shared.upgrade() {
+                let network = Network { shared };
+                let mut state = network.state();
+                f(&network, &mut state);
+            }
+        })
+    }
+
+    /* TCP listeners. */
+
+    pub(crate) fn bind_listener(
+        &self,
+        addr: SocketAddr,
+        set_readiness: SetReadiness,
+    ) -> io::Result<u64> {
+        let mut state = self.state();
+        let state = &mut *state;
+        let addr = if addr.port() == 0 {
+            let listener_addrs = &state.listener_addrs;
+            let port = next_port(&mut state.next_port, |port| {
+                !listener_addrs.contains_key(&SocketAddr::new(addr.ip(), port))
+            });
+            SocketAddr::new(addr.ip(), port)
+        } else if state.listener_addrs.contains_key(&addr) {
+            return Err(io::ErrorKind::AddrInUse.into());
+        } else {
+            addr
+        };
+        let id = state.next_id();
+        state.listener_addrs.insert(addr, id);
+        state.listeners.insert(
+            id,
+            Listener {
+                addr,
+                backlog: VecDeque::new(),
+                readiness: Readiness::new(set_readiness),
+            },
+        );
+        Ok(id)
+    }
+
+    pub(crate) fn accept(
+        &self,
+        id: u64,
+        set_readiness: SetReadiness,
+    ) -> io::Result<(u64, SocketAddr)> {
+        let mut state = self.state();
+        let stream_id = match state.listeners.get_mut(&id).unwrap().backlog.pop_front() {
+            Some(stream_id) => stream_id,
+            None => return Err(io::ErrorKind::WouldBlock.into()),
+        };
+        state.update_listener(id, false);
+
+        let stream = state.streams.get_mut(&stream_id).unwrap();
+        stream.readiness = Some(Readiness::new(set_readiness));
+        let peer = stream.peer;
+        state.update_stream(stream_id, true);
+        Ok((stream_id, peer))
+    }
+
+    pub(crate) fn listener_addr(&self, id: u64) -> SocketAddr {
+        self.state().listeners[&id].addr
+    }
+
+    pub(crate) fn close_listener(&self, id: u64) {
+        let mut state = self.state();
+        let listener = state.listeners.remove(&id).unwrap();
+        state.listener_addrs.remove(&listener.addr);
+        // Connections that are never accepted are reset.
+        for stream_id in listener.backlog {
+            let stream = state.streams.remove(&stream_id).unwrap();
+            state.send_reset(self, stream.peer_id.unwrap());
+        }
+    }
+
+    /* TCP streams. */
+
+    pub(crate) fn connect(&self, addr: SocketAddr, set_readiness: SetReadiness) -> io::Result<u64> {
+        let mut state = self.state();
+        let port = next_port(&mut state.next_port, |_| true);
+        let local = SocketAddr::new(local_ip(addr), port);
+        let id = state.next_id();
+        let mut stream = Stream::new(local, addr, None);
+        stream.readiness = Some(Readiness::new(set_readiness));
+        state.streams.insert(id, stream);
+
+        let at = self.now() + state.latency;
+        self.schedule(at, move |network, state| state.syn(network, id, addr));
+        Ok(id)
+    }
+
+    pub(crate) fn stream_addrs(&self, id: u64) -> (SocketAddr, Option<SocketAddr>) {
+        let state = self.state();
+        let stream = &state.streams[&id];
+        (stream.local, stream.peer_id.map(|_| stream.peer))
+    }
+
+    pub(crate) fn read(&self, id: u64, buf: &mut [u8]) -> io::Result<usize> {
+        let mut state = self.state();
+        let result = state.read(id, buf);
+        state.update_stream(id, false);
+        result
+    }
+
+    pub(crate) fn write(&self, id: u64, buf: &[u8]) -> io::Result<usize> {
+        let mut state = self.state();
+        let result = state.write(self, id, buf);
+        state.update_stream(id, false);
+        result
+    }
+
+    pub(crate) fn shutdown(&self, id: u64, how: Shutdown) -> io::Result<()> {
+        let mut state = self.state();
+        let result = state.shutdown(self, id, how);
+        state.update_stream(id, false);
+        result
+    }
+
+    pub(crate) fn close_stream(&self, id: u64) {
+        self.state().close_stream(self, id)
+    }
+
+    /* UDP sockets. */
+
+    pub(crate) fn bind_socket(
+        &self,
+        addr: SocketAddr,
+        set_readiness: SetReadiness,
+    ) -> io::Result<u64> {
+        let mut state = self.state();
+        let state = &mut *state;
+        let addr = if addr.port() == 0 {
+            let socket_addrs = &state.socket_addrs;
+            let port = next_port(&mut state.next_port, |port| {
+                !socket_addrs.contains_key(&SocketAddr::new(addr.ip(), port))
+            });
+            SocketAddr::new(addr.ip(), port)
+        } else if state.socket_addrs.contains_key(&addr) {
+            return Err(io::ErrorKind::AddrInUse.into());
+        } else {
+            addr
+        };
+        let id = state.next_id();
+        state.socket_addrs.insert(addr, id);
+        state.sockets.insert(
+            id,
+            Socket {
+                addr,
+                recv: VecDeque::new(),
+                recv_len: 0,
+                readiness: Readiness::new(set_readiness),
+            },
+        );
+        // Sending never blocks.
+        state.update_socket(id, true);
+        Ok(id)
+    }
+
+    pub(crate) fn socket_addr(&self, id: u64) -> SocketAddr {
+        self.state().sockets[&id].addr
+    }
+
+    pub(crate) fn send_to(&self, id: u64, buf: &[u8], target: SocketAddr) -> io::Result<usize> {
+        if buf.len() > MAX_DATAGRAM {
+            return Err(io::Error::from_raw_os_error(libc::EMSGSIZE));
+        }
+        let mut state = self.state();
+        let state = &mut *state;
+        if state.rng.chance(state.loss) {
+            return Ok(buf.len());
+        }
+        let mut at = self.now() + state.latency;
+        if state.rng.chance(state.reorder) {
+            at += state.reorder_delay;
+        }
+        let source = state.sockets[&id].addr;
+        let data = buf.to_vec();
+        self.schedule(at, move |_, state| state.datagram(data, source, target));
+        Ok(buf.len())
+    }
+
+    pub(crate) fn recv_from(&self, id: u64, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        let mut state = self.state();
+        let result = {
+            let socket = state.sockets.get_mut(&id).unwrap();
+            match socket.recv.pop_front() {
+                Some((data, source)) => {
+                    socket.recv_len -= data.len();
+                    // Like the kernel the remainder of the datagram is lost.
+                    let n = cmp::min(buf.len(), data.len());
+                    buf[..n].copy_from_slice(&data[..n]);
+                    Ok((n, source))
+                }
+                None => Err(io::ErrorKind::WouldBlock.into()),
+            }
+        };
+        state.update_socket(id, false);
+        result
+    }
+
+    pub(crate) fn close_socket(&self, id: u64) {
+        let mut state = self.state();
+        let socket = state.sockets.remove(&id).unwrap();
+        state.socket_addrs.remove(&socket.addr);
+    }
+}
+
+impl fmt::Debug for Network {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state();
+        f.debug_struct("Network")
+            .field("latency", &state.latency)
+            .field("loss", &state.loss)
+            .field("reorder", &state.reorder)
+            .field("reorder_delay", &state.reorder_delay)
+            .field("reset", &state.reset)
+            .field("max_write", &state.max_write)
+            .field("buffer_size", &state.buffer_size)
+            .finish()
+    }
+}
+
+impl State {
+    fn next_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    /// Returns the id of the listener or socket in `addrs` receiving data for
+    /// `addr`.
+    fn lookup(addrs: &HashMap<SocketAddr, u64>, addr: SocketAddr) -> Option<u64> {
+        addrs
+            .get(&addr)
+            .or_else(|| addrs.get(&SocketAddr::new(unspecified(addr.ip()), addr.port())))
+            .cloned()
+    }
+
+    /// A connection request of stream `id` arrives at `addr`.
+    fn syn(&mut self, network: &Network, id: u64, addr: SocketAddr) {
+        let local = match self.streams.get(&id) {
+            Some(stream) => stream.local,
+            // Closed before the request arrived.
+            None => return,
+        };
+        let listener_id = match State::lookup(&self.listener_addrs, addr) {
+            Some(listener_id) => listener_id,
+            None => {
+                self.fail(id, io::ErrorKind::ConnectionRefused);
+                return;
+            }
+        };
+        let server_id = self.next_id();
+        self.streams
+            .insert(server_id, Stream::new(addr, local, Some(id)));
+        self.listeners
+            .get_mut(&listener_id)
+            .unwrap()
+            .backlog
+            .push_back(server_id);
+        self.update_listener(listener_id, true);
+
+        let at = network.now() + self.latency;
+        network.schedule(at, move |_, state| {
+            if !state.streams.contains_key(&id) {
+                return;
+            }
+            if state.streams.contains_key(&server_id) {
+                state.streams.get_mut(&id).unwrap().peer_id = Some(server_id);
+                state.update_stream(id, true);
+            } else {
+                // The listener was closed in the meantime.
+                state.fail(id, io::ErrorKind::ConnectionReset);
+            }
+        });
+    }
+
+    fn read(&mut self, id: u64, buf: &mut [u8]) -> io::Result<usize> {
+        let (n, peer_id) = {
+            let stream = self.streams.get_mut(&id).unwrap();
+            if let Some(kind) = stream.error {
+                return Err(kind.into());
+            } else if stream.read_closed {
+                return Ok(0);
+            } else if stream.recv.is_empty() {
+                if stream.recv_eof || buf.is_empty() {
+                    return Ok(0);
+                }
+                return Err(io::ErrorKind::WouldBlock.into());
+            }
+            let n = cmp::min(buf.len(), stream.recv.len());
+            for (dst, src) in buf.iter_mut().zip(stream.recv.drain(..n)) {
+                *dst = src;
+            }
+            (n, stream.peer_id)
+        };
+        // The freed space may make the peer writable again.
+        if let Some(peer_id) = peer_id {
+            self.update_stream(peer_id, true);
+        }
+        Ok(n)
+    }
+
+    fn write(&mut self, network: &Network, id: u64, buf: &[u8]) -> io::Result<usize> {
+        let peer_id = {
+            let stream = &self.streams[&id];
+            if let Some(kind) = stream.error {
+                return Err(kind.into());
+            } else if stream.write_closed {
+                return Err(io::ErrorKind::BrokenPipe.into());
+            }
+            match stream.peer_id {
+                Some(peer_id) => peer_id,
+                // Not yet connected.
+                None => return Err(io::ErrorKind::WouldBlock.into()),
+            }
+        };
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let space = self.space(id);
+        let n = cmp::min(
+            cmp::min(buf.len(), space),
+            self.max_write.unwrap_or(usize::max_value()),
+        );
+        if n == 0 {
+            return Err(io::ErrorKind::WouldBlock.into());
+        }
+
+        let now = network.now();
+        let lost = self.rng.chance(self.loss);
+        let reset = self.rng.chance(self.reset);
+        let at = self.arrival(id, now, lost);
+        let stream = self.streams.get_mut(&id).unwrap();
+        stream.in_flight += n;
+        let data = buf[..n].to_vec();
+        network.schedule(at, move |network, state| {
+            if reset {
+                state.fail(id, io::ErrorKind::ConnectionReset);
+                state.fail(peer_id, io::ErrorKind::ConnectionReset);
+            } else {
+                state.segment(network, id, peer_id, Segment::Data(data));
+            }
+        });
+        Ok(n)
+    }
+
+    fn shutdown(&mut self, network: &Network, id: u64, how: Shutdown) -> io::Result<()> {
+        let stream = self.streams.get_mut(&id).unwrap();
+        let peer_id = match stream.peer_id {
+            Some(peer_id) => peer_id,
+            None => return Err(io::ErrorKind::NotConnected.into()),
+        };
+        if how != Shutdown::Write {
+            stream.read_closed = true;
+        }
+        if how != Shutdown::Read && !stream.write_closed && stream.error.is_none() {
+            stream.write_closed = true;
+            let at = self.arrival(id, network.now(), false);
+            network.schedule(at, move |network, state| {
+                state.segment(network, id, peer_id, Segment::Fin)
+            });
+        }
+        Ok(())
+    }
+
+    /// Close stream `id`, dropped by the user.
+    fn close_stream(&mut self, network: &Network, id: u64) {
+        let _ = self.shutdown(network, id, Shutdown::Both);
+        let stream = self.streams.remove(&id).unwrap();
+        // Data that is never read resets the connection, like the kernel does.
+        if let (Some(peer_id), false) = (stream.peer_id, stream.recv.is_empty()) {
+            self.send_reset(network, peer_id);
+        }
+    }
+
+    /// Returns the time of arrival of the next segment of stream `id`.
+    fn arrival(&mut self, id: u64, now: Duration, lost: bool) -> Duration {
+        let mut at = now + self.latency;
+        if lost {
+            at += RETRANSMIT_TIMEOUT;
+        }
+        let stream = self.streams.get_mut(&id).unwrap();
+        at = cmp::max(at, stream.last_arrival);
+        stream.last_arrival = at;
+        at
+    }
+
+    /// A segment sent by stream `from` arrives at stream `to`.
+    fn segment(&mut self, network: &Network, from: u64, to: u64, segment: Segment) {
+        if let Segment::Data(ref data) = segment {
+            if let Some(stream) = self.streams.get_mut(&from) {
+                stream.in_flight -= data.len();
+            }
+        }
+        match self.streams.get_mut(&to) {
+            Some(ref mut stream) if stream.error.is_none() => match segment {
+                Segment::Data(data) => stream.recv.extend(data),
+                Segment::Fin => stream.recv_eof = true,
+            },
+            Some(_) => return,
+            None => {
+                if let Segment::Data(_) = segment {
+                    // The peer is closed.
+                    self.send_reset(network, from);
+                }
+                return;
+            }
+        }
+        self.update_stream(to, true);
+    }
+
+    /// Reset stream `id` after the latency.
+    fn send_reset(&mut self, network: &Network, id: u64) {
+        let at = network.now() + self.latency;
+        network.schedule(at, move |_, state| {
+            if state.streams.contains_key(&id) {
+                state.fail(id, io::ErrorKind::ConnectionReset)
+            }
+        });
+    }
+
+    /// Fail stream `id` with an error of `kind`.
+    fn fail(&mut self, id: u64, kind: io::ErrorKind) {
+        if let Some(stream) = self.streams.get_mut(&id) {
+            stream.error = Some(kind);
+            stream.recv.clear();
+            self.update_stream(id, true);
+        }
+    }
+
+    /// Returns the number of bytes that stream `id` can write.
+    fn space(&self, id: u64) -> usize {
+        let stream = &self.streams[&id];
+        let buffered = stream
+            .peer_id
+            .and_then(|peer_id| self.streams.get(&peer_id))
+            .map_or(0, |peer| peer.recv.len());
+        self.buffer_size.saturating_sub(stream.in_flight + buffered)
+    }
+
+    /// A datagram sent by `source` arrives at `target`.
+    fn datagram(&mut self, data: Vec<u8>, source: SocketAddr, target: SocketAddr) {
+        let id = match State::lookup(&self.socket_addrs, target) {
+            Some(id) => id,
+            None => return,
+        };
+        let socket = self.sockets.get_mut(&id).unwrap();
+        if socket.recv_len + data.len() > self.buffer_size {
+            return;
+        }
+        socket.recv_len += data.len();
+        socket.recv.push_back((data, source));
+        self.update_socket(id, true);
+    }
+
+    /// Update the readiness of listener `id`. If `notify` is true an event is
+    /// returned even if the readiness didn't change, e.g. for new data.
+    fn update_listener(&mut self, id: u64, notify: bool) {
+        let listener = self.listeners.get_mut(&id).unwrap();
+        let readiness = if listener.backlog.is_empty() {
+            None
+        } else {
+            Some(Interests::READABLE)
+        };
+        listener.readiness.set(readiness, notify);
+    }
+
+    /// Update the readiness of stream `id`, see `update_listener`.
+    fn update_stream(&mut self, id: u64, notify: bool) {
+        let space = match self.streams.get(&id) {
+            Some(_) => self.space(id),
+            None => return,
+        };
+        let stream = self.streams.get_mut(&id).unwrap();
+        let failed = stream.error.is_some();
+        let readable = failed || !stream.recv.is_empty() || stream.recv_eof || stream.read_closed;
+        let writable = failed || (stream.peer_id.is_some() && !stream.write_closed && space > 0);
+        let readiness = match (readable, writable) {
+            (true, true) => Some(Interests::READABLE | Interests::WRITABLE),
+            (true, false) => Some(Interests::READABLE),
+            (false, true) => Some(Interests::WRITABLE),
+            (false, false) => None,
+        };
+        if let Some(ref mut current) = stream.readiness {
+            current.set(readiness, notify);
+        }
+    }
+
+    /// Update the readiness of socket `id`, see `update_listener`.
+    fn update_socket(&mut self, id: u64, notify: bool) {
+        let socket = self.sockets.get_mut(&id).unwrap();
+        let readiness = if socket.recv.is_empty() {
+            Some(Interests::WRITABLE)
+        } else {
+            Some(Interests::READABLE | Interests::WRITABLE)
+        };
+        socket.readiness.set(readiness, notify);
+    }
+}
+
+enum Segment {
+    Data(Vec<u8>),
+    Fin,
+}
+
+impl Stream {
+    fn new(local: SocketAddr, peer: SocketAddr, peer_id: Option<u64>) -> Stream {
+        Stream {
+            local,
+            peer,
+            peer_id,
+            recv: VecDeque::new(),
+            recv_eof: false,
+            read_closed: false,
+            write_closed: false,
+            error: None,
+            in_flight: 0,
+            last_arrival: Duration::from_millis(0),
+            readiness: None,
+        }
+    }
+}
+
+impl Readiness {
+    fn new(set_readiness: SetReadiness) -> Readiness {
+        Readiness {
+            set_readiness,
+            current: None,
+        }
+    }
+
+    fn set(&mut self, readiness: Option<Interests>, notify: bool) {
+        if notify || readiness != self.current {
+            self.current = readiness;
+            // Only fails if the `Poll` instance is gone, in which case there
+            // is nobody to notify.
+            let _ = self.set_readiness.set_readiness(readiness);
+        }
+    }
+}
+
+/// Returns the next ephemeral port for which `free` returns true.
+fn next_port<F>(next: &mut u16, free: F) -> u16
+where
+    F: Fn(u16) -> bool,
+{
+    loop {
+        let port = *next;
+        *next = if port == u16::max_value() {
+            EPHEMERAL_PORT
+        } else {
+            port + 1
+        };
+        if free(port) {
+            return port;
+        }
+    }
+}
+
+/// Returns the local address used to connect to `addr`.
+fn local_ip(addr: SocketAddr) -> IpAddr {
+    match addr {
+        SocketAddr::V4(_) => IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
+        SocketAddr::V6(_) => IpAddr::V6(Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 1)),
+    }
+}
+
+fn unspecified(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)),
+        IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 0)),
+    }
+}
@@ -0,0 +1,300 @@
+use crate::sim::Clock;
+use crate::{selector, Events, Interests, Token, Trigger};
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::Duration;
+use std::{fmt, io};
+
+/// Simulated selector, returning scripted readiness events.
+///
+/// Readiness events are scripted per token using [`schedule`], they're
+/// returned once the [`Clock`] reaches their time, in the order in which they
+/// were scheduled. Calls to [`Waker::wake`] and [`SetReadiness`] are returned
+/// as usual.
+///
+/// Polling never sleeps. If no events are ready, `Poll::poll` moves the clock
+/// forward to the next scheduled event, but no further than its timeout. Only
+/// if no events are scheduled and there is no timeout it blocks, until another
+/// thread schedules an event or wakes it up.
+///
+/// Registrations of file descriptors are tracked, returning the same errors as
+/// other selectors, but they never return events. A clone shares the state of
+/// the original, so that a clone can be used to script events after the
+/// original is passed to [`Poll::with_selector`].
+///
+/// [`schedule`]: Selector::schedule
+/// [`Waker::wake`]: crate::Waker::wake
+/// [`SetReadiness`]: crate::event::SetReadiness
+/// [`Poll::with_selector`]: crate::Poll::with_selector
+#[derive(Clone)]
+pub struct Selector {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    clock: Clock,
+    state: Mutex<State>,
+    /// Notified when events are scheduled or woken, see `Selector::select`.
+    condvar: Condvar,
+}
+
+struct State {
+    registrations: HashMap<RawFd, Registration>,
+    /// Keyed by their time and the order in which they were scheduled.
+    scheduled: BTreeMap<(Duration, u64), Scheduled>,
+    next_seq: u64,
+    /// Events that are ready, but not yet returned.
+    ready: VecDeque<(Token, Interests)>,
+}
+
+enum Scheduled {
+    Event(Token, Interests),
+    /// Called by `select`, used by the simulated network to deliver data.
+    Call(Box<dyn FnOnce() + Send>),
+}
+
+struct Registration {
+    token: Token,
+    interests: Interests,
+    trigger: Trigger,
+}
+
+impl Selector {
+    /// Create a new simulated selector using `clock`.
+    pub fn new(clock: &Clock) -> Selector {
+        Selector {
+            inner: Arc::new(Inner {
+                clock: clock.clone(),
+                state: Mutex::new(State {
+                    registrations: HashMap::new(),
+                    scheduled: BTreeMap::new(),
+                    next_seq: 0,
+                    ready: VecDeque::new(),
+                }),
+                condvar: Condvar::new(),
+            }),
+        }
+    }
+
+    /// Returns the clock of the selector.
+    pub fn clock(&self) -> &Clock {
+        &self.inner.clock
+    }
+
+    /// Schedule an event with `token` and `readiness`, returned once the
+    /// clock reaches `at` (see [`Clock::elapsed`]).
+    ///
+    /// Events scheduled in the past are returned by the next poll.
+    pub fn schedule(&self, at: Duration, token: Token, readiness: Interests) {
+        self.insert(at, Scheduled::Event(token, readiness))
+    }
+
+    /// Schedule a call to `f`, made by `Poll::poll` once the clock reaches
+    /// `at`.
+    pub(crate) fn schedule_call<F>(&self, at: Duration, f: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.insert(at, Scheduled::Call(Box::new(f)))
+    }
+
+    fn insert(&self, at: Duration, scheduled: Scheduled) {
+        let mut state = self.state();
+        let seq = state.next_seq;
+        state.next_seq += 1;
+        state.scheduled.insert((at, seq), scheduled);
+        self.inner.condvar.notify_all();
+    }
+
+    /// Schedule an event with `token` and `readiness` at the current time, see
+    /// [`schedule`].
+    ///
+    /// [`schedule`]: Selector::schedule
+    pub fn set_ready(&self, token: Token, readiness: Interests) {
+        self.schedule(self.inner.clock.elapsed(), token, readiness)
+    }
+
+    /// Returns the token, interests and trigger with which `fd` is
+    /// registered, if it's registered.
+    pub fn registration(&self, fd: RawFd) -> Option<(Token, Interests, Trigger)> {
+        self.state().registrations.get(&fd).map(|registration| {
+            (
+                registration.token,
+                registration.interests,
+                registration.trigger,
+            )
+        })
+    }
+
+    /// Returns the number of scheduled events that aren't yet returned,
+    /// including data in flight on a simulated [`Network`].
+    ///
+    /// [`Network`]: crate::sim::Network
+    pub fn pending(&self) -> usize {
+        let state = self.state();
+        state.scheduled.len() + state.ready.len()
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.inner.state.lock().unwrap()
+    }
+}
+
+impl State {
+    /// Move the scheduled events up to `now` to the ready queue, returning
+    /// the calls that are due.
+    fn expire(&mut self, now: Duration) -> Vec<Box<dyn FnOnce() + Send>> {
+        let mut calls = Vec::new();
+        while let Some(&key) = self.scheduled.keys().next() {
+            if key.0 > now {
+                break;
+            }
+            match self.scheduled.remove(&key).unwrap() {
+                Scheduled::Event(token, readiness) => self.ready.push_back((token, readiness)),
+                Scheduled::Call(f) => calls.push(f),
+            }
+        }
+        calls
+    }
+
+    fn next_scheduled(&self) -> Option<Duration> {
+        self.scheduled.keys().next().map(|&(at, _)| at)
+    }
+}
+
+impl selector::Selector for Selector {
+    fn register(
+        &self,
+        fd: RawFd,
+        token: Token,
+        interests: Interests,
+        trigger: Trigger,
+    ) -> io::Result<()> {
+        let mut state = self.state();
+        if state.registrations.contains_key(&fd) {
+            return Err(io::Error::from_raw_os_error(libc::EEXIST));
+        }
+        state.registrations.insert(
+            fd,
+            Registration {
+                token,
+                interests,
+                trigger,
+            },
+        );
+        Ok(())
+    }
+
+    fn reregister(
+        &self,
+        fd: RawFd,
+        token: Token,
+        interests: Interests,
+        trigger: Trigger,
+    ) -> io::Result<()> {
+        match self.state().registrations.get_mut(&fd) {
+            Some(registration) => {
+                *registration = Registration {
+                    token,
+                    interests,
+                    trigger,
+                };
+                Ok(())
+            }
+            None => Err(io::Error::from_raw_os_error(libc::ENOENT)),
+        }
+    }
+
+    fn deregister(&self, fd: RawFd) -> io::Result<()> {
+        match self.state().registrations.remove(&fd) {
+            Some(_) => Ok(()),
+            None => Err(io::Error::from_raw_os_error(libc::ENOENT)),
+        }
+    }
+
+    fn select(&self, events: &mut Events, timeout: Option<Duration>) -> io::Result<()> {
+        let clock = &self.inner.clock;
+        let deadline = timeout.map(|timeout| clock.elapsed() + timeout);
+        let mut state = self.state();
+        loop {
+            let calls = state.expire(clock.elapsed());
+            if !calls.is_empty() {
+                // The calls may schedule events or wake us up.
+                drop(state);
+                for call in calls {
+                    call();
+                }
+                state = self.state();
+                continue;
+            }
+
+            if !state.ready.is_empty() {
+                while let Some(&(token, readiness)) = state.ready.front() {
+                    if !events.push(token, readiness) {
+                        break;
+                    }
+                    state.ready.pop_front();
+                }
+                return Ok(());
+            }
+
+            match (state.next_scheduled(), deadline) {
+                (Some(at), Some(deadline)) if at <= deadline => clock.advance_to(at),
+                (Some(at), None) => clock.advance_to(at),
+                (_, Some(deadline)) => {
+                    clock.advance_to(deadline);
+                    return Ok(());
+                }
+                // Nothing will ever be returned, unless another thread
+                // schedules an event or wakes us up.
+                (None, None) => state = self.inner.condvar.wait(state).unwrap(),
+            }
+        }
+    }
+
+    fn waker(&self, token: Token) -> io::Result<Box<dyn selector::Wake>> {
+        Ok(Box::new(Waker {
+            selector: self.clone(),
+            token,
+        }))
+    }
+}
+
+/// Waker of the simulated selector, readying an event with its token.
+struct Waker {
+    selector: Selector,
+    token: Token,
+}
+
+impl selector::Wake for Waker {
+    fn wake(&self) -> io::Result<()> {
+        let mut state = self.selector.state();
+        let event = (self.token, Interests::READABLE);
+        if !state.ready.contains(&event) {
+            state.ready.push_back(event);
+        }
+        self.selector.inner.condvar.notify_all();
+        Ok(())
+    }
+}
+
+impl AsRawFd for Selector {
+    /// Returns -1, as the simulated selector doesn't have a file descriptor.
+    fn as_raw_fd(&self) -> RawFd {
+        -1
+    }
+}
+
+impl fmt::Debug for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state();
+        f.debug_struct("Selector")
+            .field("clock", &self.inner.clock)
+            .field("registrations", &state.registrations.len())
+            .field("scheduled", &state.scheduled.len())
+            .field("ready", &state.ready.len())
+            .finish()
+    }
+}
@@ -0,0 +1,199 @@
+use crate::event::{Evented, Registration};
+use crate::sim::Network;
+use crate::{Interests, Registry, Token};
+
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, SocketAddr};
+
+/// A simulated TCP stream, connected over a [`Network`].
+///
+/// The simulated equivalent of [`mio::net::TcpStream`], it doesn't use the
+/// kernel. Readiness events are returned like those of `mio::net::TcpStream`:
+/// the stream becomes writable once it's connected, and readable once data
+/// arrives, the peer shuts down or the connection fails.
+///
+/// [`mio::net::TcpStream`]: crate::net::TcpStream
+pub struct TcpStream {
+    network: Network,
+    id: u64,
+    registration: Registration,
+}
+
+/// A simulated TCP listener, accepting connections over a [`Network`].
+///
+/// The simulated equivalent of [`mio::net::TcpListener`], it becomes readable
+/// once a connection is ready to be accepted.
+///
+/// [`mio::net::TcpListener`]: crate::net::TcpListener
+pub struct TcpListener {
+    network: Network,
+    id: u64,
+    registration: Registration,
+}
+
+impl TcpStream {
+    /// Connect to `addr` over `network`.
+    ///
+    /// The connection is established after a round trip, or fails with a
+    /// `ConnectionRefused` error if nothing listens on `addr`.
+    pub fn connect(network: &Network, addr: SocketAddr) -> io::Result<TcpStream> {
+        let (registration, set_readiness) = Registration::new();
+        let id = network.connect(addr, set_readiness)?;
+        Ok(TcpStream {
+            network: network.clone(),
+            id,
+            registration,
+        })
+    }
+
+    /// Returns the socket address of the remote peer of this TCP connection.
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.network
+            .stream_addrs(self.id)
+            .1
+            .ok_or_else(|| io::ErrorKind::NotConnected.into())
+    }
+
+    /// Returns the socket address of the local half of this TCP connection.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        Ok(self.network.stream_addrs(self.id).0)
+    }
+
+    /// Shuts down the read, write, or both halves of this connection.
+    pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+        self.network.shutdown(self.id, how)
+    }
+}
+
+impl Read for TcpStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        (&*self).read(buf)
+    }
+}
+
+impl<'a> Read for &'a TcpStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.network.read(self.id, buf)
+    }
+}
+
+impl Write for TcpStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (&*self).write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        (&*self).flush()
+    }
+}
+
+impl<'a> Write for &'a TcpStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.network.write(self.id, buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Evented for TcpStream {
+    fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        self.registration.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &self,
+        registry: &Registry,
+        token: Token,
+        interests: Interests,
+    ) -> io::Result<()> {
+        self.registration.reregister(registry, token, interests)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.registration.deregister(registry)
+    }
+}
+
+impl Drop for TcpStream {
+    fn drop(&mut self) {
+        self.network.close_stream(self.id);
+    }
+}
+
+impl fmt::Debug for TcpStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (local, peer) = self.network.stream_addrs(self.id);
+        f.debug_struct("TcpStream")
+            .field("local", &local)
+            .field("peer", &peer)
+            .finish()
+    }
+}
+
+impl TcpListener {
+    /// Bind a listener to `addr` on `network`.
+    ///
+    /// If the port of `addr` is 0 an unused port is picked.
+    pub fn bind(network: &Network, addr: SocketAddr) -> io::Result<TcpListener> {
+        let (registration, set_readiness) = Registration::new();
+        let id = network.bind_listener(addr, set_readiness)?;
+        Ok(TcpListener {
+            network: network.clone(),
+            id,
+            registration,
+        })
+    }
+
+    /// Accept a new connection, returning `WouldBlock` if none is ready.
+    pub fn accept(&self) -> io::Result<(TcpStream, SocketAddr)> {
+        let (registration, set_readiness) = Registration::new();
+        let (id, addr) = self.network.accept(self.id, set_readiness)?;
+        let stream = TcpStream {
+            network: self.network.clone(),
+            id,
+            registration,
+        };
+        Ok((stream, addr))
+    }
+
+    /// Returns the local socket address of this listener.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        Ok(self.network.listener_addr(self.id))
+    }
+}
+
+impl Evented for TcpListener {
+    fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        self.registration.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &self,
+        registry: &Registry,
+        token: Token,
+        interests: Interests,
+    ) -> io::Result<()> {
+        self.registration.reregister(registry, token, interests)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.registration.deregister(registry)
+    }
+}
+
+impl Drop for TcpListener {
+    fn drop(&mut self) {
+        self.network.close_listener(self.id);
+    }
+}
+
+impl fmt::Debug for TcpListener {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TcpListener")
+            .field("local", &self.network.listener_addr(self.id))
+            .finish()
+    }
+}
@@ -0,0 +1,89 @@
+use crate::event::{Evented, Registration};
+use crate::sim::Network;
+use crate::{Interests, Registry, Token};
+
+use std::fmt;
+use std::io;
+use std::net::SocketAddr;
+
+/// A simulated UDP socket, sending datagrams over a [`Network`].
+///
+/// The simulated equivalent of [`mio::net::UdpSocket`], it doesn't use the
+/// kernel. The socket is always writable, as sending never blocks, and
+/// becomes readable once a datagram arrives.
+///
+/// [`mio::net::UdpSocket`]: crate::net::UdpSocket
+pub struct UdpSocket {
+    network: Network,
+    id: u64,
+    registration: Registration,
+}
+
+impl UdpSocket {
+    /// Bind a socket to `addr` on `network`.
+    ///
+    /// If the port of `addr` is 0 an unused port is picked.
+    pub fn bind(network: &Network, addr: SocketAddr) -> io::Result<UdpSocket> {
+        let (registration, set_readiness) = Registration::new();
+        let id = network.bind_socket(addr, set_readiness)?;
+        Ok(UdpSocket {
+            network: network.clone(),
+            id,
+            registration,
+        })
+    }
+
+    /// Returns the socket address that this socket was bound to.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        Ok(self.network.socket_addr(self.id))
+    }
+
+    /// Sends a datagram to `target`, returning the number of bytes sent.
+    ///
+    /// Like with a real UDP socket the datagram may never arrive.
+    pub fn send_to(&self, buf: &[u8], target: SocketAddr) -> io::Result<usize> {
+        self.network.send_to(self.id, buf, target)
+    }
+
+    /// Receives a datagram, returning the number of bytes read and the
+    /// address it came from, or `WouldBlock` if no datagram arrived.
+    ///
+    /// If `buf` is too small to hold the datagram the excess bytes are
+    /// discarded.
+    pub fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        self.network.recv_from(self.id, buf)
+    }
+}
+
+impl Evented for UdpSocket {
+    fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        self.registration.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &self,
+        registry: &Registry,
+        token: Token,
+        interests: Interests,
+    ) -> io::Result<()> {
+        self.registration.reregister(registry, token, interests)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.registration.deregister(registry)
+    }
+}
+
+impl Drop for UdpSocket {
+    fn drop(&mut self) {
+        self.network.close_socket(self.id);
+    }
+}
+
+impl fmt::Debug for UdpSocket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UdpSocket")
+            .field("local", &self.network.socket_addr(self.id))
+            .finish()
+    }
+}
@@ -10,17 +10,21 @@
 
 #[cfg(unix)]
 pub use self::unix::{
-    pipe, set_nonblock, Event, EventedFd, Events, Io, Selector, SysEvent, TcpListener, TcpStream,
-    UdpSocket, Waker,
+    pipe, set_nonblock, Event, EventedFd, Events, Io, Registration, Selector, SetReadiness,
+    SignalPipe, SysEvent, TcpListener, TcpStream, UdpSocket, UnixDatagram, UnixListener,
+    UnixSeqpacket, UnixSeqpacketListener, UnixStream, Waker,
 };
 
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub use self::unix::{Inotify, Process, Signals, Timer};
+
 #[cfg(unix)]
 pub mod unix;
 
 #[cfg(windows)]
 pub use self::windows::{
     Binding, Event, Events, Overlapped, Selector, SysEvent, TcpListener, TcpStream, UdpSocket,
-    Waker,
+    UserRegistration as Registration, UserSetReadiness as SetReadiness, Waker,
 };
 
 #[cfg(windows)]
@@ -1,26 +1,41 @@
 use crate::sys::unix::cvt;
 use crate::sys::unix::io::set_cloexec;
-use crate::{Interests, Token};
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use crate::sys::unix::timerfd::{self, Timer};
+use crate::{Interests, Token, Trigger};
 
 use libc::{self, c_int};
-use libc::{EPOLLET, EPOLLIN, EPOLLOUT};
+use libc::{EPOLLET, EPOLLIN, EPOLLONESHOT, EPOLLOUT};
 use std::os::unix::io::AsRawFd;
 use std::os::unix::io::RawFd;
-use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use std::ptr;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use std::sync::Mutex;
 use std::time::Duration;
 use std::{cmp, i32, io};
 
-/// Each Selector has a globally unique(ish) ID associated with it. This ID
-/// gets tracked by `TcpStream`, `TcpListener`, etc... when they are first
-/// registered with the `Selector`. If a type that is previously associated with
-/// a `Selector` attempts to register itself with a different `Selector`, the
-/// operation will return with an error. This matches windows behavior.
-static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+/// Token of the timer used by `wait_timer`. This is the token reserved by
+/// `Poll`, which ignores its events.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const TIMER: Token = Token(usize::MAX);
+
+/// Set once `epoll_pwait2` returned `ENOSYS`, i.e. the C library supports it
+/// but the kernel doesn't.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+static NO_EPOLL_PWAIT2: AtomicBool = AtomicBool::new(false);
+
+pub use crate::sys::unix::waker::Waker;
 
 #[derive(Debug)]
 pub struct Selector {
-    id: usize,
     epfd: RawFd,
+    /// Timer used to wait for timeouts with sub-millisecond precision if
+    /// `epoll_pwait2` isn't available, created on first use.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    timer: Mutex<Option<Timer>>,
 }
 
 impl Selector {
@@ -41,54 +56,130 @@ impl Selector {
             }
         };
 
-        // offset by 1 to avoid choosing 0 as the id of a selector
-        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed) + 1;
-
-        Ok(Selector { id: id, epfd: epfd })
+        Ok(Selector {
+            epfd: epfd,
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            timer: Mutex::new(None),
+        })
     }
 
-    pub fn id(&self) -> usize {
-        self.id
+    /// Wait for events from the OS
+    pub fn select(&self, evts: &mut Events, timeout: Option<Duration>) -> io::Result<()> {
+        evts.clear();
+        let cnt = match timeout {
+            // `epoll_wait` only supports millisecond precision, so we need to
+            // take another route for anything more precise.
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            Some(to) if to.subsec_nanos() % NANOS_PER_MILLI != 0 => self.wait_precise(evts, to)?,
+            _ => self.wait(evts, timeout)?,
+        };
+        unsafe {
+            evts.events.set_len(cnt);
+        }
+        Ok(())
     }
 
-    /// Wait for events from the OS
-    pub fn select(
-        &self,
-        evts: &mut Events,
-        waker: Token,
-        timeout: Option<Duration>,
-    ) -> io::Result<bool> {
+    /// Wait for events using `epoll_wait`, rounding `timeout` up to whole
+    /// milliseconds. Returns the number of events written into `evts`.
+    fn wait(&self, evts: &mut Events, timeout: Option<Duration>) -> io::Result<usize> {
         let timeout_ms = timeout
             .map(|to| cmp::min(millis(to), i32::MAX as u64) as i32)
             .unwrap_or(-1);
 
         // Wait for epoll events for at most timeout_ms milliseconds
-        evts.clear();
         unsafe {
-            let cnt = cvt(libc::epoll_wait(
+            cvt(libc::epoll_wait(
                 self.epfd,
                 evts.events.as_mut_ptr(),
                 evts.events.capacity() as i32,
                 timeout_ms,
-            ))?;
-            let cnt = cnt as usize;
-            evts.events.set_len(cnt);
+            ))
+            .map(|cnt| cnt as usize)
+        }
+    }
 
-            for i in 0..cnt {
-                if evts.events[i].u64 as usize == waker.into() {
-                    evts.events.remove(i);
-                    return Ok(true);
+    /// Wait for events with nanosecond precision using `epoll_pwait2` (Linux
+    /// 5.11+), falling back to a timerfd if it's not available.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn wait_precise(&self, evts: &mut Events, timeout: Duration) -> io::Result<usize> {
+        dlsym!(fn epoll_pwait2(
+            c_int,
+            *mut libc::epoll_event,
+            c_int,
+            *const libc::timespec,
+            *const libc::sigset_t
+        ) -> c_int);
+
+        if !NO_EPOLL_PWAIT2.load(Ordering::Relaxed) {
+            if let Some(epoll_pwait2_fn) = epoll_pwait2.get() {
+                let timeout = timerfd::timespec(timeout);
+                let res = unsafe {
+                    cvt(epoll_pwait2_fn(
+                        self.epfd,
+                        evts.events.as_mut_ptr(),
+                        evts.events.capacity() as i32,
+                        &timeout,
+                        ptr::null(),
+                    ))
+                };
+                match res {
+                    Ok(cnt) => return Ok(cnt as usize),
+                    Err(ref err) if err.raw_os_error() == Some(libc::ENOSYS) => {
+                        NO_EPOLL_PWAIT2.store(true, Ordering::Relaxed);
+                    }
+                    Err(err) => return Err(err),
                 }
             }
         }
 
-        Ok(false)
+        self.wait_timer(evts, timeout)
+    }
+
+    /// Wait for events, using a timerfd registered with the `TIMER` token to
+    /// wake up after `timeout`.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn wait_timer(&self, evts: &mut Events, timeout: Duration) -> io::Result<usize> {
+        let mut timer = self.timer.lock().unwrap();
+        if timer.is_none() {
+            let new_timer = Timer::new(libc::CLOCK_MONOTONIC)?;
+            self.register(
+                new_timer.as_raw_fd(),
+                TIMER,
+                Interests::READABLE,
+                Trigger::Edge,
+            )?;
+            *timer = Some(new_timer);
+        }
+        let timer = timer.as_ref().unwrap();
+
+        // `select` only calls us for timeouts with sub-millisecond precision,
+        // which are never zero (which would disarm the timer).
+        timer.set(timeout, Duration::from_secs(0))?;
+        // The timer will expire before the timeout, which is rounded up, but
+        // we use it as a safety net anyway.
+        let res = self.wait(evts, Some(timeout));
+
+        // Disarm the timer and reset the expiration count, this ensures the
+        // timer doesn't wake up a later call.
+        timer.set(Duration::from_secs(0), Duration::from_secs(0))?;
+        match timer.read() {
+            Ok(_) => {}
+            Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {}
+            Err(err) => return Err(err),
+        }
+        res
     }
 
     /// Register event interests for the given IO handle with the OS
-    pub fn register(&self, fd: RawFd, token: Token, interests: Interests) -> io::Result<()> {
+    pub fn register(
+        &self,
+        fd: RawFd,
+        token: Token,
+        interests: Interests,
+        trigger: Trigger,
+    ) -> io::Result<()> {
         let mut info = libc::epoll_event {
-            events: interests_to_epoll(interests),
+            events: interests_to_epoll(interests, trigger),
             u64: usize::from(token) as u64,
         };
 
@@ -104,9 +195,15 @@ impl Selector {
     }
 
     /// Register event interests for the given IO handle with the OS
-    pub fn reregister(&self, fd: RawFd, token: Token, interests: Interests) -> io::Result<()> {
+    pub fn reregister(
+        &self,
+        fd: RawFd,
+        token: Token,
+        interests: Interests,
+        trigger: Trigger,
+    ) -> io::Result<()> {
         let mut info = libc::epoll_event {
-            events: interests_to_epoll(interests),
+            events: interests_to_epoll(interests, trigger),
             u64: usize::from(token) as u64,
         };
 
@@ -140,8 +237,12 @@ impl Selector {
     }
 }
 
-fn interests_to_epoll(interests: Interests) -> u32 {
-    let mut kind = EPOLLET;
+fn interests_to_epoll(interests: Interests, trigger: Trigger) -> u32 {
+    let mut kind = match trigger {
+        Trigger::Edge => EPOLLET,
+        Trigger::Level => 0,
+        Trigger::Oneshot => EPOLLONESHOT,
+    };
 
     if interests.is_readable() {
         kind |= EPOLLIN;
@@ -249,6 +350,47 @@ impl Events {
             self.events.set_len(0);
         }
     }
+
+    /// Add a user space readiness event, returns `false` if there is no room
+    /// for it.
+    pub fn push(&mut self, token: Token, readiness: Interests) -> bool {
+        let mut kind = 0;
+        if readiness.is_readable() {
+            kind |= EPOLLIN;
+        }
+        if readiness.is_writable() {
+            kind |= EPOLLOUT;
+        }
+        self.push_raw(token, kind as u32)
+    }
+
+    /// Add an event with `events` in the epoll format (which matches the
+    /// `poll` format), returns `false` if there is no room for it.
+    pub fn push_raw(&mut self, token: Token, events: u32) -> bool {
+        if self.events.len() == self.events.capacity() {
+            return false;
+        }
+        self.events.push(libc::epoll_event {
+            events,
+            u64: usize::from(token) as u64,
+        });
+        true
+    }
+
+    /// Add an event with `revents` returned by `poll`, returns `false` if
+    /// there is no room for it.
+    pub fn push_poll(&mut self, token: Token, revents: libc::c_short) -> bool {
+        // The epoll flags match those of `poll`.
+        self.push_raw(token, revents as u16 as u32)
+    }
+
+    /// Remove the events for which `f` returns `false`.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(Token) -> bool,
+    {
+        self.events.retain(|event| f(Token(event.u64 as usize)))
+    }
 }
 
 const NANOS_PER_MILLI: u32 = 1_000_000;
@@ -267,3 +409,38 @@ pub fn millis(duration: Duration) -> u64 {
         .saturating_mul(MILLIS_PER_SEC)
         .saturating_add(millis as u64)
 }
+
+#[test]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn wait_timer_sub_millisecond() {
+    use std::time::Instant;
+
+    let selector = Selector::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    // A rounded up timeout takes at least a millisecond every time, a loaded
+    // machine only delays some of the tries.
+    let timeout = Duration::from_micros(100);
+    let mut fastest = Duration::from_secs(1);
+    for _ in 0..50 {
+        let start = Instant::now();
+        selector.wait_timer(&mut events, timeout).unwrap();
+        let elapsed = start.elapsed();
+        assert!(elapsed >= timeout, "returned early: {:?}", elapsed);
+        fastest = cmp::min(fastest, elapsed);
+        if fastest < Duration::from_millis(1) {
+            break;
+        }
+    }
+    assert!(
+        fastest < Duration::from_millis(1),
+        "rounded up to a millisecond: {:?}",
+        fastest
+    );
+
+    // The timer must not wake up later calls.
+    selector
+        .select(&mut events, Some(Duration::from_millis(10)))
+        .unwrap();
+    assert!(events.is_empty());
+}
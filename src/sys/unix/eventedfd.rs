@@ -99,7 +99,7 @@ pub struct EventedFd<'a>(pub &'a RawFd);
 
 impl<'a> Evented for EventedFd<'a> {
     fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
-        poll::selector(registry).register(*self.0, token, interests)
+        poll::selector(registry).register(*self.0, token, interests, poll::trigger(registry))
     }
 
     fn reregister(
@@ -108,7 +108,7 @@ impl<'a> Evented for EventedFd<'a> {
         token: Token,
         interests: Interests,
     ) -> io::Result<()> {
-        poll::selector(registry).reregister(*self.0, token, interests)
+        poll::selector(registry).reregister(*self.0, token, interests, poll::trigger(registry))
     }
 
     fn deregister(&self, registry: &Registry) -> io::Result<()> {
@@ -0,0 +1,100 @@
+use crate::event::Evented;
+use crate::sys::unix::cvt;
+use crate::unix::EventedFd;
+use crate::{Interests, Registry, Token};
+
+use libc::{self, c_int};
+use std::ffi::CString;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use std::path::Path;
+
+/// Filesystem watcher backed by `inotify`.
+pub struct Inotify {
+    fd: File,
+}
+
+impl Inotify {
+    pub fn new() -> io::Result<Inotify> {
+        let fd = unsafe { cvt(libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC))? };
+        Ok(Inotify {
+            fd: unsafe { File::from_raw_fd(fd) },
+        })
+    }
+
+    pub fn add_watch(&self, path: &Path, mask: u32) -> io::Result<c_int> {
+        let path = CString::new(path.as_os_str().as_bytes()).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "paths may not contain interior null bytes",
+            )
+        })?;
+        unsafe {
+            cvt(libc::inotify_add_watch(
+                self.as_raw_fd(),
+                path.as_ptr(),
+                mask,
+            ))
+        }
+    }
+
+    pub fn rm_watch(&self, wd: c_int) -> io::Result<()> {
+        unsafe { cvt(libc::inotify_rm_watch(self.as_raw_fd(), wd)).map(|_| ()) }
+    }
+
+    /// Read as many whole events as fit into `buf`, returns a `WouldBlock`
+    /// error if no event is queued.
+    pub fn read(&self, buf: &mut [u8]) -> io::Result<usize> {
+        match (&self.fd).read(buf) {
+            // The kernel never splits an event over multiple reads, if the
+            // next event doesn't fit in `buf` the read fails instead.
+            Err(ref err) if err.raw_os_error() == Some(libc::EINVAL) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "buffer too small for the next inotify event",
+            )),
+            res => res,
+        }
+    }
+}
+
+impl Evented for Inotify {
+    fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).register(registry, token, interests)
+    }
+
+    fn reregister(
+        &self,
+        registry: &Registry,
+        token: Token,
+        interests: Interests,
+    ) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).reregister(registry, token, interests)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).deregister(registry)
+    }
+}
+
+impl fmt::Debug for Inotify {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Inotify")
+            .field("fd", &self.fd.as_raw_fd())
+            .finish()
+    }
+}
+
+impl IntoRawFd for Inotify {
+    fn into_raw_fd(self) -> RawFd {
+        self.fd.into_raw_fd()
+    }
+}
+
+impl AsRawFd for Inotify {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
@@ -1,8 +1,10 @@
 use crate::event::Evented;
 use crate::sys::unix::cvt;
+use crate::sys::unix::uio::VecIo;
 use crate::unix::EventedFd;
 use crate::{Interests, Registry, Token};
 
+use iovec::IoVec;
 use libc;
 use std::fs::File;
 use std::io::{self, Read, Write};
@@ -41,6 +43,27 @@ impl Io {
             fd: self.fd.try_clone()?,
         })
     }
+
+    /// Set or clear the `O_NONBLOCK` flag of the FD
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        unsafe {
+            let flags = cvt(libc::fcntl(self.as_raw_fd(), libc::F_GETFL))?;
+            let flags = if nonblocking {
+                flags | libc::O_NONBLOCK
+            } else {
+                flags & !libc::O_NONBLOCK
+            };
+            cvt(libc::fcntl(self.as_raw_fd(), libc::F_SETFL, flags)).map(|_| ())
+        }
+    }
+
+    pub fn readv(&self, bufs: &mut [&mut IoVec]) -> io::Result<usize> {
+        VecIo::readv(self, bufs)
+    }
+
+    pub fn writev(&self, bufs: &[&IoVec]) -> io::Result<usize> {
+        VecIo::writev(self, bufs)
+    }
 }
 
 impl FromRawFd for Io {
@@ -1,6 +1,6 @@
 use crate::sys::unix::cvt;
 use crate::sys::unix::io::set_cloexec;
-use crate::{Interests, Token};
+use crate::{Interests, Token, Trigger};
 
 use libc::{self, time_t};
 use log::trace;
@@ -9,17 +9,9 @@ use std::io;
 use std::os::raw::{c_int, c_short};
 use std::os::unix::io::AsRawFd;
 use std::os::unix::io::RawFd;
-use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 use std::{cmp, fmt, ptr};
 
-/// Each Selector has a globally unique(ish) ID associated with it. This ID
-/// gets tracked by `TcpStream`, `TcpListener`, etc... when they are first
-/// registered with the `Selector`. If a type that is previously associated with
-/// a `Selector` attempts to register itself with a different `Selector`, the
-/// operation will return with an error. This matches windows behavior.
-static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
-
 #[cfg(not(target_os = "netbsd"))]
 type Filter = c_short;
 #[cfg(not(target_os = "netbsd"))]
@@ -47,31 +39,21 @@ macro_rules! kevent {
     };
 }
 
+pub use crate::sys::unix::waker::Waker;
+
 pub struct Selector {
-    id: usize,
     kq: RawFd,
 }
 
 impl Selector {
     pub fn new() -> io::Result<Selector> {
-        // offset by 1 to avoid choosing 0 as the id of a selector
-        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed) + 1;
         let kq = unsafe { cvt(libc::kqueue())? };
         drop(set_cloexec(kq));
 
-        Ok(Selector { id, kq })
-    }
-
-    pub fn id(&self) -> usize {
-        self.id
+        Ok(Selector { kq })
     }
 
-    pub fn select(
-        &self,
-        evts: &mut Events,
-        _waker: Token,
-        timeout: Option<Duration>,
-    ) -> io::Result<bool> {
+    pub fn select(&self, evts: &mut Events, timeout: Option<Duration>) -> io::Result<()> {
         let timeout = timeout.map(|to| libc::timespec {
             tv_sec: cmp::min(to.as_secs(), time_t::max_value() as u64) as time_t,
             // `Duration::subsec_nanos` is guaranteed to be less than one
@@ -86,24 +68,43 @@ impl Selector {
             .unwrap_or(ptr::null_mut());
 
         evts.clear();
-        unsafe {
-            let cnt = cvt(libc::kevent(
+        let cnt = unsafe {
+            cvt(libc::kevent(
                 self.kq,
                 ptr::null(),
                 0,
                 evts.events.as_mut_ptr(),
                 evts.events.capacity() as Count,
                 timeout,
-            ))?;
-            evts.events.set_len(cnt as usize);
-            Ok(true)
+            ))? as usize
+        };
+        unsafe {
+            evts.events.set_len(cnt);
         }
+        Ok(())
     }
 
-    pub fn register(&self, fd: RawFd, token: Token, interests: Interests) -> io::Result<()> {
-        trace!("registering; token={:?}; interests={:?}", token, interests);
+    pub fn register(
+        &self,
+        fd: RawFd,
+        token: Token,
+        interests: Interests,
+        trigger: Trigger,
+    ) -> io::Result<()> {
+        trace!(
+            "registering; token={:?}; interests={:?}; trigger={:?}",
+            token,
+            interests,
+            trigger
+        );
 
-        let flags = libc::EV_CLEAR | libc::EV_RECEIPT;
+        let flags = match trigger {
+            Trigger::Edge => libc::EV_CLEAR | libc::EV_RECEIPT,
+            Trigger::Level => libc::EV_RECEIPT,
+            // Explicitly enable the event to rearm a registration that was
+            // disabled by `EV_DISPATCH`.
+            Trigger::Oneshot => libc::EV_DISPATCH | libc::EV_ENABLE | libc::EV_RECEIPT,
+        };
 
         unsafe {
             let r = if interests.is_readable() {
@@ -173,10 +174,16 @@ impl Selector {
         }
     }
 
-    pub fn reregister(&self, fd: RawFd, token: Token, interests: Interests) -> io::Result<()> {
+    pub fn reregister(
+        &self,
+        fd: RawFd,
+        token: Token,
+        interests: Interests,
+        trigger: Trigger,
+    ) -> io::Result<()> {
         // Just need to call register here since EV_ADD is a mod if already
         // registered
-        self.register(fd, token, interests)
+        self.register(fd, token, interests, trigger)
     }
 
     pub fn deregister(&self, fd: RawFd) -> io::Result<()> {
@@ -223,8 +230,10 @@ impl Selector {
     #[cfg(any(target_os = "freebsd", target_os = "ios", target_os = "macos"))]
     pub fn setup_waker(&self, token: Token) -> io::Result<()> {
         // First attempt to accept user space notifications.
+        // The token is used as identifier, so that multiple wakers (e.g. the
+        // one of the readiness queue) don't overwrite each other.
         let mut kevent = kevent!(
-            0,
+            token.0,
             libc::EVFILT_USER,
             libc::EV_ADD | libc::EV_CLEAR | libc::EV_RECEIPT,
             token.0
@@ -256,10 +265,7 @@ impl Selector {
         if new_kq == -1 {
             Err(io::Error::last_os_error())
         } else {
-            Ok(Selector {
-                id: self.id,
-                kq: new_kq,
-            })
+            Ok(Selector { kq: new_kq })
         }
     }
 
@@ -267,7 +273,7 @@ impl Selector {
     #[cfg(any(target_os = "freebsd", target_os = "ios", target_os = "macos"))]
     pub fn wake(&self, token: Token) -> io::Result<()> {
         let mut kevent = kevent!(
-            0,
+            token.0,
             libc::EVFILT_USER,
             libc::EV_ADD | libc::EV_RECEIPT,
             token.0
@@ -296,10 +302,7 @@ impl Selector {
 
 impl fmt::Debug for Selector {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt.debug_struct("Selector")
-            .field("id", &self.id)
-            .field("kq", &self.kq)
-            .finish()
+        fmt.debug_struct("Selector").field("kq", &self.kq).finish()
     }
 }
 
@@ -431,6 +434,61 @@ impl Events {
     pub fn clear(&mut self) {
         self.events.clear();
     }
+
+    /// Add a user space readiness event, returns `false` if there is no room
+    /// for it.
+    pub fn push(&mut self, token: Token, readiness: Interests) -> bool {
+        // kqueue returns separate events for reading and writing.
+        let n = readiness.is_readable() as usize + readiness.is_writable() as usize;
+        if self.events.len() + n > self.events.capacity() {
+            return false;
+        }
+        if readiness.is_readable() {
+            self.events.push(kevent!(0, libc::EVFILT_READ, 0, token.0));
+        }
+        if readiness.is_writable() {
+            self.events.push(kevent!(0, libc::EVFILT_WRITE, 0, token.0));
+        }
+        true
+    }
+
+    /// Add an event with `revents` returned by `poll`, returns `false` if
+    /// there is no room for it.
+    pub fn push_poll(&mut self, token: Token, revents: libc::c_short) -> bool {
+        let readable = revents
+            & (libc::POLLIN | libc::POLLPRI | libc::POLLHUP | libc::POLLERR | libc::POLLNVAL)
+            != 0;
+        let writable = revents & (libc::POLLOUT | libc::POLLERR) != 0;
+        let n = readable as usize + writable as usize;
+        if n == 0 || self.events.len() + n > self.events.capacity() {
+            return n == 0;
+        }
+
+        let mut flags = 0;
+        if revents & libc::POLLHUP != 0 {
+            flags |= libc::EV_EOF;
+        }
+        if revents & (libc::POLLERR | libc::POLLNVAL) != 0 {
+            flags |= libc::EV_ERROR;
+        }
+        if readable {
+            self.events
+                .push(kevent!(0, libc::EVFILT_READ, flags, token.0));
+        }
+        if writable {
+            self.events
+                .push(kevent!(0, libc::EVFILT_WRITE, flags, token.0));
+        }
+        true
+    }
+
+    /// Remove the events for which `f` returns `false`.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(Token) -> bool,
+    {
+        self.events.retain(|event| f(Token(event.udata as usize)))
+    }
 }
 
 impl fmt::Debug for Events {
@@ -4,10 +4,13 @@ use libc::{self, c_int};
 pub mod dlsym;
 
 #[cfg(any(target_os = "linux", target_os = "android", target_os = "solaris"))]
-mod epoll;
+pub mod epoll;
 
 #[cfg(any(target_os = "linux", target_os = "android", target_os = "solaris"))]
-pub use self::epoll::{Event, Events, Selector, SysEvent};
+pub use self::epoll::{Event, Events, SysEvent};
+
+#[cfg(all(feature = "io_uring", any(target_os = "linux", target_os = "android")))]
+pub mod uring;
 
 #[cfg(any(
     target_os = "bitrig",
@@ -18,7 +21,7 @@ pub use self::epoll::{Event, Events, Selector, SysEvent};
     target_os = "netbsd",
     target_os = "openbsd"
 ))]
-mod kqueue;
+pub mod kqueue;
 
 #[cfg(any(
     target_os = "bitrig",
@@ -29,20 +32,45 @@ mod kqueue;
     target_os = "netbsd",
     target_os = "openbsd"
 ))]
-pub use self::kqueue::{Event, Events, Selector, SysEvent};
+pub use self::kqueue::{Event, Events, SysEvent};
+
+pub mod poll;
 
 mod eventedfd;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod inotify;
 mod io;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod pidfd;
+mod queue;
+mod selector;
+mod signal_pipe;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod signalfd;
 mod tcp;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod timerfd;
 mod udp;
+mod uds;
 mod uio;
 mod waker;
 
 pub use self::eventedfd::EventedFd;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub use self::inotify::Inotify;
 pub use self::io::{set_nonblock, Io};
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub use self::pidfd::Process;
+pub use self::queue::{Registration, SetReadiness};
+pub use self::selector::{Selector, Waker};
+pub use self::signal_pipe::{SignalPipe, MAX_SIGNAL};
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub use self::signalfd::Signals;
 pub use self::tcp::{TcpListener, TcpStream};
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub use self::timerfd::Timer;
 pub use self::udp::UdpSocket;
-pub use self::waker::Waker;
+pub use self::uds::{UnixDatagram, UnixListener, UnixSeqpacket, UnixSeqpacketListener, UnixStream};
 
 pub use iovec::IoVec;
 
@@ -56,20 +84,29 @@ pub fn pipe() -> std::io::Result<(Io, Io)> {
 
     let mut pipes = [0; 2];
     let flags = libc::O_NONBLOCK | libc::O_CLOEXEC;
-    unsafe {
+    let has_flags = unsafe {
         match pipe2.get() {
             Some(pipe2_fn) => {
                 cvt(pipe2_fn(pipes.as_mut_ptr(), flags))?;
+                true
             }
             None => {
                 cvt(libc::pipe(pipes.as_mut_ptr()))?;
-                libc::fcntl(pipes[0], libc::F_SETFL, flags);
-                libc::fcntl(pipes[1], libc::F_SETFL, flags);
+                false
             }
         }
+    };
+
+    // Both ends are closed if setting the flags fails.
+    let pipe = unsafe { (Io::from_raw_fd(pipes[0]), Io::from_raw_fd(pipes[1])) };
+    if !has_flags {
+        // `O_CLOEXEC` is a file descriptor flag, which `F_SETFL` ignores.
+        for &fd in &pipes {
+            set_nonblock(fd)?;
+            io::set_cloexec(fd)?;
+        }
     }
-
-    unsafe { Ok((Io::from_raw_fd(pipes[0]), Io::from_raw_fd(pipes[1]))) }
+    Ok(pipe)
 }
 
 trait IsMinusOne {
@@ -0,0 +1,149 @@
+use crate::event::Evented;
+use crate::sys::unix::cvt;
+use crate::sys::unix::eventedfd::EventedFd;
+use crate::{Interests, Registry, Token};
+
+use libc::{self, c_int, c_uint, pid_t};
+use std::fs::File;
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use std::os::unix::process::ExitStatusExt;
+use std::process::ExitStatus;
+use std::{fmt, io, mem, ptr};
+
+/// Process handle backed by a pidfd, which becomes readable once the process
+/// exits.
+pub struct Process {
+    fd: File,
+    pid: pid_t,
+}
+
+impl Process {
+    pub fn open(pid: pid_t) -> io::Result<Process> {
+        dlsym!(fn pidfd_open(pid_t, c_uint) -> c_int);
+
+        // The pidfd is always created with the close-on-exec flag set.
+        let fd = unsafe {
+            match pidfd_open.get() {
+                Some(pidfd_open_fn) => cvt(pidfd_open_fn(pid, 0))?,
+                None => cvt(libc::syscall(libc::SYS_pidfd_open, pid, 0 as c_uint) as c_int)?,
+            }
+        };
+        let fd = unsafe { File::from_raw_fd(fd) };
+        Ok(Process { fd, pid })
+    }
+
+    pub fn pid(&self) -> pid_t {
+        self.pid
+    }
+
+    pub fn try_wait(&self) -> io::Result<Option<ExitStatus>> {
+        let mut info: libc::siginfo_t = unsafe { mem::zeroed() };
+        let flags = libc::WEXITED | libc::WNOHANG;
+        let res = unsafe {
+            libc::waitid(
+                libc::P_PIDFD,
+                self.as_raw_fd() as libc::id_t,
+                &mut info,
+                flags,
+            )
+        };
+        match cvt(res) {
+            Ok(_) => {}
+            // `P_PIDFD` is only supported since Linux 5.4, `pidfd_open` since
+            // 5.3. The process can't have been reaped and its pid reused, as
+            // we're the ones reaping it.
+            Err(ref err) if err.raw_os_error() == Some(libc::EINVAL) => {
+                return self.try_waitpid();
+            }
+            Err(err) => return Err(err),
+        }
+
+        // If the process is still running `waitid` returns 0 and leaves
+        // `si_pid` zeroed.
+        if unsafe { info.si_pid() } == 0 {
+            return Ok(None);
+        }
+        let status = unsafe { info.si_status() };
+        let status = match info.si_code {
+            libc::CLD_EXITED => (status & 0xff) << 8,
+            libc::CLD_KILLED => status & 0x7f,
+            libc::CLD_DUMPED => (status & 0x7f) | 0x80,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "unexpected waitid code",
+                ))
+            }
+        };
+        Ok(Some(ExitStatus::from_raw(status)))
+    }
+
+    fn try_waitpid(&self) -> io::Result<Option<ExitStatus>> {
+        let mut status = 0;
+        match cvt(unsafe { libc::waitpid(self.pid, &mut status, libc::WNOHANG) })? {
+            0 => Ok(None),
+            _ => Ok(Some(ExitStatus::from_raw(status))),
+        }
+    }
+
+    pub fn send_signal(&self, signal: c_int) -> io::Result<()> {
+        dlsym!(fn pidfd_send_signal(c_int, c_int, *mut libc::siginfo_t, c_uint) -> c_int);
+
+        let fd = self.as_raw_fd();
+        unsafe {
+            match pidfd_send_signal.get() {
+                Some(pidfd_send_signal_fn) => {
+                    cvt(pidfd_send_signal_fn(fd, signal, ptr::null_mut(), 0))?
+                }
+                None => cvt(libc::syscall(
+                    libc::SYS_pidfd_send_signal,
+                    fd,
+                    signal,
+                    ptr::null_mut::<libc::siginfo_t>(),
+                    0 as c_uint,
+                ) as c_int)?,
+            };
+        }
+        Ok(())
+    }
+}
+
+impl Evented for Process {
+    fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).register(registry, token, interests)
+    }
+
+    fn reregister(
+        &self,
+        registry: &Registry,
+        token: Token,
+        interests: Interests,
+    ) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).reregister(registry, token, interests)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).deregister(registry)
+    }
+}
+
+impl fmt::Debug for Process {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Process")
+            .field("fd", &self.as_raw_fd())
+            .field("pid", &self.pid)
+            .finish()
+    }
+}
+
+impl AsRawFd for Process {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
+
+impl IntoRawFd for Process {
+    fn into_raw_fd(self) -> RawFd {
+        self.fd.into_raw_fd()
+    }
+}
@@ -0,0 +1,504 @@
+use crate::selector::{self, Wake};
+use crate::sys::unix::cvt;
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "solaris"))]
+use crate::sys::unix::epoll::Selector as Mirror;
+#[cfg(any(
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+use crate::sys::unix::kqueue::Selector as Mirror;
+use crate::sys::unix::{pipe, Io};
+use crate::sys::Events;
+use crate::{Interests, Token, Trigger};
+
+use libc::{self, c_short};
+use log::debug;
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use std::{fmt, mem};
+
+/// Selector backed by `poll(2)`, available on all Unix platforms.
+///
+/// `poll` is level-triggered, edge triggers are emulated by disarming the
+/// interests of a file descriptor once they're returned. They're armed again
+/// by I/O operations on the handle (see `SelectorId::did_io`), or when the
+/// handle is reregistered. Oneshot
+/// registrations are disarmed completely until they're reregistered.
+///
+/// The file descriptors are passed to each call to `poll`. Changes made while
+/// `select` is blocked, and `Waker`s, wake it up using a pipe, after which it
+/// polls again with the changed file descriptors.
+///
+/// `poll` doesn't have a file descriptor of its own. The first call to
+/// `as_raw_fd` creates an epoll or kqueue instance mirroring the armed
+/// events of the registrations and the pipe, level-triggered, which is
+/// readable exactly when `select` would return events.
+pub struct Selector {
+    inner: Arc<Inner>,
+}
+
+/// State shared with the `Waker`s.
+struct Inner {
+    state: Mutex<State>,
+    /// Reading end of the pipe used to wake up `select`, always polled.
+    notify_receiver: Io,
+    notify_sender: Io,
+}
+
+struct State {
+    registrations: HashMap<RawFd, Registration>,
+    /// Set while `select` is blocked in `poll`.
+    polling: bool,
+    /// Tokens of the `Waker`s woken since the last call to `select`.
+    woken: Vec<Token>,
+    /// Created by `as_raw_fd`.
+    mirror: Option<Mirror>,
+}
+
+/// Token of the registrations of the mirror, its events are never read.
+const MIRROR: Token = Token(0);
+
+struct Registration {
+    token: Token,
+    /// `POLLIN` and/or `POLLOUT`, depending on the interests.
+    events: c_short,
+    trigger: Trigger,
+    /// The events that are polled, i.e. `events` minus the events that are
+    /// disarmed.
+    armed: c_short,
+}
+
+impl Selector {
+    pub fn new() -> io::Result<Selector> {
+        let (notify_receiver, notify_sender) = pipe()?;
+
+        Ok(Selector {
+            inner: Arc::new(Inner {
+                state: Mutex::new(State {
+                    registrations: HashMap::new(),
+                    polling: false,
+                    woken: Vec::new(),
+                    mirror: None,
+                }),
+                notify_receiver,
+                notify_sender,
+            }),
+        })
+    }
+
+    /// Wait for events from the OS
+    pub fn select(&self, evts: &mut Events, timeout: Option<Duration>) -> io::Result<()> {
+        evts.clear();
+        let start = Instant::now();
+        self.poll(evts, timeout)?;
+        // Changes made while polling wake us up without any events, in which
+        // case we poll again for the remainder of the timeout.
+        while evts.is_empty() {
+            let remaining = match timeout {
+                Some(timeout) if start.elapsed() >= timeout => break,
+                Some(timeout) => Some(timeout - start.elapsed()),
+                None => None,
+            };
+            self.poll(evts, remaining)?;
+        }
+        Ok(())
+    }
+
+    /// Call `poll` once, moving the returned events into `evts`.
+    fn poll(&self, evts: &mut Events, timeout: Option<Duration>) -> io::Result<()> {
+        let mut fds = Vec::new();
+        let timeout = {
+            let mut state = self.inner.state();
+            fds.reserve(state.registrations.len() + 1);
+            fds.push(pollfd(self.inner.notify_receiver.as_raw_fd(), libc::POLLIN));
+            fds.extend(
+                state
+                    .registrations
+                    .iter()
+                    .filter(|&(_, registration)| registration.armed != 0)
+                    .map(|(&fd, registration)| pollfd(fd, registration.armed)),
+            );
+            state.polling = true;
+            if state.woken.is_empty() {
+                timeout
+            } else {
+                Some(Duration::from_millis(0))
+            }
+        };
+
+        let res = poll(&mut fds, timeout);
+        let mut state = self.inner.state();
+        state.polling = false;
+        res?;
+
+        if fds[0].revents != 0 {
+            self.inner.empty_notify();
+        }
+
+        for token in mem::replace(&mut state.woken, Vec::new()) {
+            if !evts.push(token, Interests::READABLE) {
+                state.woken.push(token);
+            }
+        }
+
+        for pollfd in &fds[1..] {
+            if pollfd.revents == 0 {
+                continue;
+            }
+            if pollfd.revents & libc::POLLNVAL != 0 {
+                // Closed without being deregistered, which removes the
+                // registration on other platforms, and from the mirror.
+                state.registrations.remove(&pollfd.fd);
+                continue;
+            }
+            // The registration may have changed while we were polling.
+            let registration = match state.registrations.get_mut(&pollfd.fd) {
+                Some(registration) => registration,
+                None => continue,
+            };
+            if registration.armed == 0 {
+                continue;
+            }
+
+            let revents = pollfd.revents & (registration.armed | libc::POLLERR | libc::POLLHUP);
+            if !evts.push_poll(registration.token, revents) {
+                // Returned by the next call, as the events are still armed.
+                break;
+            }
+
+            let armed = registration.armed;
+            match registration.trigger {
+                Trigger::Edge if revents & (libc::POLLERR | libc::POLLHUP) != 0 => {
+                    registration.armed = 0
+                }
+                Trigger::Edge => registration.armed &= !revents,
+                Trigger::Level => {}
+                Trigger::Oneshot => registration.armed = 0,
+            }
+            let rearmed = registration.armed;
+            state.mirror(pollfd.fd, armed, rearmed);
+        }
+
+        if !state.woken.is_empty() && state.mirror.is_some() {
+            // The pipe was emptied, but some tokens didn't fit in `evts`.
+            self.inner.write_notify();
+        }
+        Ok(())
+    }
+
+    /// Register event interests for the given IO handle with the OS
+    pub fn register(
+        &self,
+        fd: RawFd,
+        token: Token,
+        interests: Interests,
+        trigger: Trigger,
+    ) -> io::Result<()> {
+        // Like `epoll_ctl`, check that the file descriptor is valid.
+        unsafe {
+            cvt(libc::fcntl(fd, libc::F_GETFD))?;
+        }
+
+        let mut state = self.inner.state();
+        if state.registrations.contains_key(&fd) {
+            return Err(io::Error::from_raw_os_error(libc::EEXIST));
+        }
+        let events = interests_to_poll(interests);
+        state.registrations.insert(
+            fd,
+            Registration {
+                token,
+                events,
+                trigger,
+                armed: events,
+            },
+        );
+        state.mirror(fd, 0, events);
+        self.inner.notify(&state);
+        Ok(())
+    }
+
+    /// Register event interests for the given IO handle with the OS
+    pub fn reregister(
+        &self,
+        fd: RawFd,
+        token: Token,
+        interests: Interests,
+        trigger: Trigger,
+    ) -> io::Result<()> {
+        let mut state = self.inner.state();
+        let armed = match state.registrations.get_mut(&fd) {
+            Some(registration) => {
+                let armed = registration.armed;
+                registration.token = token;
+                registration.events = interests_to_poll(interests);
+                registration.trigger = trigger;
+                registration.armed = registration.events;
+                armed
+            }
+            None => return Err(io::Error::from_raw_os_error(libc::ENOENT)),
+        };
+        state.mirror(fd, armed, interests_to_poll(interests));
+        self.inner.notify(&state);
+        Ok(())
+    }
+
+    /// Deregister event interests for the given IO handle with the OS
+    pub fn deregister(&self, fd: RawFd) -> io::Result<()> {
+        let mut state = self.inner.state();
+        match state.registrations.remove(&fd) {
+            Some(registration) => {
+                state.mirror(fd, registration.armed, 0);
+                self.inner.notify(&state);
+                Ok(())
+            }
+            None => Err(io::Error::from_raw_os_error(libc::ENOENT)),
+        }
+    }
+}
+
+impl Inner {
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap()
+    }
+
+    /// Wake up `select` if it's polling, so that it polls again with the
+    /// changes made to `state`.
+    fn notify(&self, state: &State) {
+        if state.polling {
+            self.write_notify();
+        }
+    }
+
+    /// Make the pipe used by `notify` readable.
+    fn write_notify(&self) {
+        // If the pipe is full it's readable anyway.
+        let _ = (&self.notify_sender).write(&[1]);
+    }
+
+    /// Create the mirror of the armed registrations, see `Selector`.
+    fn new_mirror(&self, state: &mut State) -> io::Result<()> {
+        let mirror = Mirror::new()?;
+        mirror.register(
+            self.notify_receiver.as_raw_fd(),
+            MIRROR,
+            Interests::READABLE,
+            Trigger::Level,
+        )?;
+        state.mirror = Some(mirror);
+        for (&fd, registration) in &state.registrations {
+            state.mirror(fd, 0, registration.armed);
+        }
+        if !state.woken.is_empty() {
+            self.write_notify();
+        }
+        Ok(())
+    }
+
+    /// Empty the pipe used by `notify`.
+    fn empty_notify(&self) {
+        let mut buf = [0; 64];
+        while let Ok(n) = (&self.notify_receiver).read(&mut buf) {
+            if n == 0 {
+                break;
+            }
+        }
+    }
+}
+
+impl selector::Hook for Selector {
+    /// Arm the disarmed `interests` of an edge-triggered registration, called
+    /// by handles after an I/O operation, see `SelectorId::did_io`.
+    fn rearm(&self, fd: RawFd, interests: Interests) {
+        let mut state = self.inner.state();
+        let (armed, rearmed) = match state.registrations.get_mut(&fd) {
+            Some(ref mut registration) if registration.trigger == Trigger::Edge => {
+                let armed = registration.armed;
+                registration.armed |= registration.events & interests_to_poll(interests);
+                (armed, registration.armed)
+            }
+            _ => return,
+        };
+        if armed != rearmed {
+            state.mirror(fd, armed, rearmed);
+            self.inner.notify(&state);
+        }
+    }
+
+    /// Remove the registration of `fd`, called by handles before they close
+    /// it.
+    ///
+    /// Unlike epoll and kqueue, `poll` doesn't know when a file descriptor is
+    /// closed, and would poll another file opened with the same file
+    /// descriptor.
+    fn release(&self, fd: RawFd) {
+        let mut state = self.inner.state();
+        if let Some(registration) = state.registrations.remove(&fd) {
+            state.mirror(fd, registration.armed, 0);
+            self.inner.notify(&state);
+        }
+    }
+}
+
+impl State {
+    /// Change the registration of `fd` in the mirror, if any, after its armed
+    /// events changed from `armed` to `rearmed`.
+    fn mirror(&self, fd: RawFd, armed: c_short, rearmed: c_short) {
+        let mirror = match self.mirror {
+            Some(ref mirror) => mirror,
+            None => return,
+        };
+        let res = match (poll_to_interests(armed), poll_to_interests(rearmed)) {
+            (None, Some(interests)) => mirror.register(fd, MIRROR, interests, Trigger::Level),
+            (Some(_), Some(interests)) if armed != rearmed => {
+                mirror.reregister(fd, MIRROR, interests, Trigger::Level)
+            }
+            (Some(_), None) => mirror.deregister(fd),
+            _ => Ok(()),
+        };
+        if let Err(err) = res {
+            debug!("error mirroring poll registration of fd={}: {}", fd, err);
+        }
+    }
+}
+
+fn pollfd(fd: RawFd, events: c_short) -> libc::pollfd {
+    libc::pollfd {
+        fd,
+        events,
+        revents: 0,
+    }
+}
+
+/// Wait for events using `ppoll`, which has nanosecond precision.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn poll(fds: &mut [libc::pollfd], timeout: Option<Duration>) -> io::Result<()> {
+    use crate::sys::unix::timerfd;
+    use std::ptr;
+
+    let timeout = timeout.map(timerfd::timespec);
+    unsafe {
+        cvt(libc::ppoll(
+            fds.as_mut_ptr(),
+            fds.len() as libc::nfds_t,
+            timeout
+                .as_ref()
+                .map(|timeout| timeout as *const libc::timespec)
+                .unwrap_or(ptr::null()),
+            ptr::null(),
+        ))
+        .map(|_| ())
+    }
+}
+
+/// Wait for events using `poll`, rounding `timeout` up to whole milliseconds.
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn poll(fds: &mut [libc::pollfd], timeout: Option<Duration>) -> io::Result<()> {
+    let timeout_ms = timeout
+        .map(|to| {
+            let millis = to
+                .as_secs()
+                .saturating_mul(1_000)
+                .saturating_add((u64::from(to.subsec_nanos()) + 999_999) / 1_000_000);
+            std::cmp::min(millis, libc::c_int::max_value() as u64) as libc::c_int
+        })
+        .unwrap_or(-1);
+    unsafe {
+        cvt(libc::poll(
+            fds.as_mut_ptr(),
+            fds.len() as libc::nfds_t,
+            timeout_ms,
+        ))
+        .map(|_| ())
+    }
+}
+
+fn interests_to_poll(interests: Interests) -> c_short {
+    let mut kind = 0;
+
+    if interests.is_readable() {
+        kind |= libc::POLLIN;
+    }
+
+    if interests.is_writable() {
+        kind |= libc::POLLOUT;
+    }
+
+    kind
+}
+
+fn poll_to_interests(events: c_short) -> Option<Interests> {
+    match (events & libc::POLLIN != 0, events & libc::POLLOUT != 0) {
+        (true, true) => Some(Interests::READABLE | Interests::WRITABLE),
+        (true, false) => Some(Interests::READABLE),
+        (false, true) => Some(Interests::WRITABLE),
+        (false, false) => None,
+    }
+}
+
+impl AsRawFd for Selector {
+    /// Returns the file descriptor of the mirror of the registrations, see
+    /// `Selector`, or -1 if it can't be created.
+    fn as_raw_fd(&self) -> RawFd {
+        let mut state = self.inner.state();
+        if state.mirror.is_none() {
+            if let Err(err) = self.inner.new_mirror(&mut state) {
+                debug!("error creating mirror of poll selector: {}", err);
+            }
+        }
+        state
+            .mirror
+            .as_ref()
+            .map_or(-1, |mirror| mirror.as_raw_fd())
+    }
+}
+
+impl fmt::Debug for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Selector")
+            .field("registrations", &self.inner.state().registrations.len())
+            .finish()
+    }
+}
+
+/// Waker that wakes up `select` using the pipe of the selector.
+pub struct Waker {
+    inner: Arc<Inner>,
+    token: Token,
+}
+
+impl Waker {
+    pub fn new(selector: &Selector, token: Token) -> io::Result<Waker> {
+        Ok(Waker {
+            inner: selector.inner.clone(),
+            token,
+        })
+    }
+}
+
+impl Wake for Waker {
+    fn wake(&self) -> io::Result<()> {
+        let mut state = self.inner.state();
+        if !state.woken.contains(&self.token) {
+            state.woken.push(self.token);
+        }
+        // The mirror must be readable as well.
+        if state.polling || state.mirror.is_some() {
+            self.inner.write_notify();
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Waker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Waker").field("token", &self.token).finish()
+    }
+}
@@ -0,0 +1,319 @@
+use crate::event::Evented;
+use crate::sys::{Events, Selector, Waker};
+use crate::{poll, Interests, Registry, Token, Trigger};
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::{fmt, io, usize};
+
+/// Token used by the `Waker` of the readiness queue, it's filtered out by
+/// `Selector::select`.
+const WAKE: Token = Token(usize::MAX);
+
+/// Queue of user space readiness events, see `Registration`.
+///
+/// `Selector::select` doesn't block if the queue isn't empty, otherwise it
+/// marks the queue as sleeping after which `SetReadiness` uses the `Waker` to
+/// wake it up.
+pub struct ReadinessQueue {
+    inner: Arc<QueueInner>,
+}
+
+struct QueueInner {
+    /// Set once the `waker` is created. Until then the queue is empty and
+    /// `select` doesn't lock `state`.
+    active: AtomicBool,
+    /// Set while `select` may block without having locked `state`.
+    selecting: AtomicBool,
+    state: Mutex<QueueState>,
+    /// Created when the first `Registration` is registered, so that a `Poll`
+    /// without any doesn't use an additional file descriptor.
+    waker: Mutex<Option<Waker>>,
+}
+
+struct QueueState {
+    nodes: VecDeque<Arc<Node>>,
+    sleeping: bool,
+}
+
+/// State shared by a `Registration` and its `SetReadiness` handles.
+struct Node {
+    state: Mutex<NodeState>,
+}
+
+struct NodeState {
+    readiness: Option<Interests>,
+    registration: Option<NodeRegistration>,
+    /// Whether or not the node is in the queue of `registration`.
+    queued: bool,
+}
+
+struct NodeRegistration {
+    queue: Weak<QueueInner>,
+    token: Token,
+    interests: Interests,
+    trigger: Trigger,
+    /// Set to false after an event for a oneshot registration.
+    armed: bool,
+}
+
+pub struct Registration {
+    node: Arc<Node>,
+}
+
+#[derive(Clone)]
+pub struct SetReadiness {
+    node: Arc<Node>,
+}
+
+impl ReadinessQueue {
+    pub fn new() -> ReadinessQueue {
+        ReadinessQueue {
+            inner: Arc::new(QueueInner {
+                active: AtomicBool::new(false),
+                selecting: AtomicBool::new(false),
+                state: Mutex::new(QueueState {
+                    nodes: VecDeque::new(),
+                    sleeping: false,
+                }),
+                waker: Mutex::new(None),
+            }),
+        }
+    }
+
+    /// Prepare the queue for `select` to block, returns `false` if there are
+    /// queued nodes, in which case `select` shouldn't block.
+    pub fn prepare_for_sleep(&self) -> bool {
+        // Either we see `active`, or `setup_waker` sees `selecting` and wakes
+        // us up.
+        self.inner.selecting.store(true, Ordering::SeqCst);
+        if !self.inner.active.load(Ordering::SeqCst) {
+            return true;
+        }
+        let mut state = self.inner.state.lock().unwrap();
+        if state.nodes.is_empty() {
+            state.sleeping = true;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Move events for the queued nodes into `events`, as long as there is
+    /// room for them.
+    pub fn poll(&self, events: &mut Events) {
+        if !self.inner.active.load(Ordering::SeqCst) {
+            self.inner.selecting.store(false, Ordering::SeqCst);
+            return;
+        }
+        let mut state = self.inner.state.lock().unwrap();
+        state.sleeping = false;
+
+        // Level triggered nodes are queued again, but shouldn't be returned
+        // twice in a single call.
+        let mut n = state.nodes.len();
+        while n > 0 && events.len() < events.capacity() {
+            n -= 1;
+            let node = state.nodes.pop_front().unwrap();
+            let mut guard = node.state.lock().unwrap();
+            let node_state = &mut *guard;
+            let readiness = match node_state.effective_readiness() {
+                Some(readiness) => readiness,
+                None => {
+                    node_state.queued = false;
+                    continue;
+                }
+            };
+
+            let registration = node_state.registration.as_mut().unwrap();
+            if !events.push(registration.token, readiness) {
+                // No room left, try again in the next call.
+                drop(guard);
+                state.nodes.push_front(node);
+                break;
+            }
+            match registration.trigger {
+                Trigger::Edge => node_state.queued = false,
+                Trigger::Level => {}
+                Trigger::Oneshot => {
+                    registration.armed = false;
+                    node_state.queued = false;
+                }
+            }
+
+            let queued = node_state.queued;
+            drop(guard);
+            if queued {
+                state.nodes.push_back(node);
+            }
+        }
+    }
+
+    /// Create the `Waker` used to wake up `selector`, if it doesn't exist yet.
+    fn setup_waker(&self, selector: &Selector) -> io::Result<()> {
+        let mut waker = self.inner.waker.lock().unwrap();
+        if waker.is_none() {
+            let new_waker = Waker::new(selector, WAKE)?;
+            self.inner.active.store(true, Ordering::SeqCst);
+            // `select` may be blocked without having marked the queue as
+            // sleeping, in which case `enqueue` wouldn't wake it up.
+            if self.inner.selecting.load(Ordering::SeqCst) {
+                new_waker.wake()?;
+            }
+            *waker = Some(new_waker);
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for ReadinessQueue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReadinessQueue").finish()
+    }
+}
+
+impl QueueInner {
+    /// Add `node` to the queue, waking up the selector if it's sleeping.
+    fn enqueue(&self, node: Arc<Node>) -> io::Result<()> {
+        let wake = {
+            let mut state = self.state.lock().unwrap();
+            state.nodes.push_back(node);
+            let sleeping = state.sleeping;
+            state.sleeping = false;
+            sleeping
+        };
+        if wake {
+            if let Some(ref waker) = *self.waker.lock().unwrap() {
+                return waker.wake();
+            }
+        }
+        Ok(())
+    }
+}
+
+impl NodeState {
+    /// The readiness that should be returned in an event, if any.
+    fn effective_readiness(&self) -> Option<Interests> {
+        let registration = self.registration.as_ref()?;
+        if !registration.armed {
+            return None;
+        }
+        let readiness = self.readiness?;
+        intersect(readiness, registration.interests)
+    }
+}
+
+/// Update the state of `node` using `f`, queuing the node if that results in
+/// an event.
+fn update<F>(node: &Arc<Node>, f: F) -> io::Result<()>
+where
+    F: FnOnce(&mut NodeState),
+{
+    let queue = {
+        let mut state = node.state.lock().unwrap();
+        f(&mut state);
+        if state.queued || state.effective_readiness().is_none() {
+            return Ok(());
+        }
+        match state.registration.as_ref().unwrap().queue.upgrade() {
+            Some(queue) => {
+                state.queued = true;
+                queue
+            }
+            // The `Poll` was dropped.
+            None => return Ok(()),
+        }
+    };
+    queue.enqueue(node.clone())
+}
+
+impl Registration {
+    pub fn new() -> (Registration, SetReadiness) {
+        let node = Arc::new(Node {
+            state: Mutex::new(NodeState {
+                readiness: None,
+                registration: None,
+                queued: false,
+            }),
+        });
+        let set_readiness = SetReadiness { node: node.clone() };
+        (Registration { node }, set_readiness)
+    }
+
+    fn update(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        let selector = poll::selector(registry);
+        let queue = selector.readiness_queue();
+        queue.setup_waker(selector)?;
+        let registration = NodeRegistration {
+            queue: Arc::downgrade(&queue.inner),
+            token,
+            interests,
+            trigger: poll::trigger(registry),
+            armed: true,
+        };
+        update(&self.node, |state| state.registration = Some(registration))
+    }
+}
+
+impl Evented for Registration {
+    fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        self.update(registry, token, interests)
+    }
+
+    fn reregister(
+        &self,
+        registry: &Registry,
+        token: Token,
+        interests: Interests,
+    ) -> io::Result<()> {
+        self.update(registry, token, interests)
+    }
+
+    fn deregister(&self, _registry: &Registry) -> io::Result<()> {
+        update(&self.node, |state| state.registration = None)
+    }
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        // Stops `SetReadiness` from queuing the node, a queued node is removed
+        // by the next call to `poll`.
+        self.node.state.lock().unwrap().registration = None;
+    }
+}
+
+impl fmt::Debug for Registration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Registration").finish()
+    }
+}
+
+impl SetReadiness {
+    pub fn readiness(&self) -> Option<Interests> {
+        self.node.state.lock().unwrap().readiness
+    }
+
+    pub fn set_readiness(&self, readiness: Option<Interests>) -> io::Result<()> {
+        update(&self.node, |state| state.readiness = readiness)
+    }
+}
+
+impl fmt::Debug for SetReadiness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SetReadiness").finish()
+    }
+}
+
+/// Returns the readable and writable interests in both `a` and `b`.
+fn intersect(a: Interests, b: Interests) -> Option<Interests> {
+    match (
+        a.is_readable() && b.is_readable(),
+        a.is_writable() && b.is_writable(),
+    ) {
+        (true, true) => Some(Interests::READABLE | Interests::WRITABLE),
+        (true, false) => Some(Interests::READABLE),
+        (false, true) => Some(Interests::WRITABLE),
+        (false, false) => None,
+    }
+}
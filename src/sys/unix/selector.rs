@@ -0,0 +1,184 @@
+use crate::selector::{self, DefaultSelector, DefaultWaker, Wake};
+use crate::sys::unix::queue::ReadinessQueue;
+use crate::sys::Events;
+use crate::{Interests, Token, Trigger};
+
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use std::{fmt, io};
+
+/// Each Selector has a globally unique(ish) ID associated with it. This ID
+/// gets tracked by `TcpStream`, `TcpListener`, etc... when they are first
+/// registered with the `Selector`. If a type that is previously associated with
+/// a `Selector` attempts to register itself with a different `Selector`, the
+/// operation will return with an error. This matches windows behavior.
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// The selector of a `Poll`, wrapping the `selector::Selector` implementation
+/// in use.
+pub struct Selector {
+    id: usize,
+    selector: Inner,
+    /// User space readiness events, see `Registration`.
+    readiness_queue: ReadinessQueue,
+}
+
+/// The default selector is called directly, only selectors passed to
+/// `Poll::with_selector` are called through a trait object.
+enum Inner {
+    Default(DefaultSelector),
+    Custom(Box<dyn selector::Selector>),
+}
+
+impl Selector {
+    /// Create the default selector, see the `selector` module.
+    pub fn new() -> io::Result<Selector> {
+        DefaultSelector::new().map(|selector| Selector::with_inner(Inner::Default(selector)))
+    }
+
+    pub fn with_selector(selector: Box<dyn selector::Selector>) -> Selector {
+        Selector::with_inner(Inner::Custom(selector))
+    }
+
+    fn with_inner(selector: Inner) -> Selector {
+        // offset by 1 to avoid choosing 0 as the id of a selector
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed) + 1;
+
+        Selector {
+            id,
+            selector,
+            readiness_queue: ReadinessQueue::new(),
+        }
+    }
+
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Wait for events from the OS, or from the readiness queue. Returns
+    /// `true` if `waker` was woken, its events are not returned.
+    pub fn select(
+        &self,
+        evts: &mut Events,
+        waker: Token,
+        mut timeout: Option<Duration>,
+    ) -> io::Result<bool> {
+        // Don't block if there are user space readiness events queued.
+        if timeout != Some(Duration::from_millis(0)) && !self.readiness_queue.prepare_for_sleep() {
+            timeout = Some(Duration::from_millis(0));
+        }
+
+        evts.clear();
+        match self.selector {
+            Inner::Default(ref selector) => selector.sys.select(evts, timeout)?,
+            Inner::Custom(ref selector) => {
+                selector.select(crate::Events::from_sys_mut(evts), timeout)?
+            }
+        }
+
+        // The readiness queue, and some selectors internally, use the `waker`
+        // token.
+        let cnt = evts.len();
+        evts.retain(|token| token != waker);
+        let woken = evts.len() != cnt;
+
+        self.readiness_queue.poll(evts);
+        Ok(woken)
+    }
+
+    pub fn readiness_queue(&self) -> &ReadinessQueue {
+        &self.readiness_queue
+    }
+
+    pub fn register(
+        &self,
+        fd: RawFd,
+        token: Token,
+        interests: Interests,
+        trigger: Trigger,
+    ) -> io::Result<()> {
+        match self.selector {
+            Inner::Default(ref selector) => selector.sys.register(fd, token, interests, trigger),
+            Inner::Custom(ref selector) => selector.register(fd, token, interests, trigger),
+        }
+    }
+
+    pub fn reregister(
+        &self,
+        fd: RawFd,
+        token: Token,
+        interests: Interests,
+        trigger: Trigger,
+    ) -> io::Result<()> {
+        match self.selector {
+            Inner::Default(ref selector) => selector.sys.reregister(fd, token, interests, trigger),
+            Inner::Custom(ref selector) => selector.reregister(fd, token, interests, trigger),
+        }
+    }
+
+    pub fn deregister(&self, fd: RawFd) -> io::Result<()> {
+        match self.selector {
+            Inner::Default(ref selector) => selector.sys.deregister(fd),
+            Inner::Custom(ref selector) => selector.deregister(fd),
+        }
+    }
+
+    pub fn hooks(&self) -> Option<selector::Hooks<'_>> {
+        match self.selector {
+            Inner::Default(ref selector) => selector::Selector::hooks(selector),
+            Inner::Custom(ref selector) => selector.hooks(),
+        }
+    }
+}
+
+impl AsRawFd for Selector {
+    fn as_raw_fd(&self) -> RawFd {
+        match self.selector {
+            Inner::Default(ref selector) => selector.sys.as_raw_fd(),
+            Inner::Custom(ref selector) => selector.as_raw_fd(),
+        }
+    }
+}
+
+impl fmt::Debug for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Selector").field("id", &self.id).finish()
+    }
+}
+
+/// Waker of the default selector, or created by `selector::Selector::waker`.
+pub struct Waker {
+    inner: WakerInner,
+    token: Token,
+}
+
+enum WakerInner {
+    Default(DefaultWaker),
+    Custom(Box<dyn Wake>),
+}
+
+impl Waker {
+    pub fn new(selector: &Selector, token: Token) -> io::Result<Waker> {
+        let inner = match selector.selector {
+            Inner::Default(ref selector) => {
+                DefaultWaker::new(&selector.sys, token).map(WakerInner::Default)?
+            }
+            Inner::Custom(ref selector) => selector.waker(token).map(WakerInner::Custom)?,
+        };
+        Ok(Waker { inner, token })
+    }
+
+    pub fn wake(&self) -> io::Result<()> {
+        match self.inner {
+            WakerInner::Default(ref waker) => waker.wake(),
+            WakerInner::Custom(ref waker) => waker.wake(),
+        }
+    }
+}
+
+impl fmt::Debug for Waker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Waker").field("token", &self.token).finish()
+    }
+}
@@ -0,0 +1,309 @@
+use crate::event::Evented;
+use crate::sys::unix::{cvt, pipe, Io};
+use crate::{Interests, Registry, Token};
+
+use libc::{self, c_int, c_void};
+use std::cell::UnsafeCell;
+use std::io::{self, Read};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+use std::sync::{Mutex, Once};
+use std::{fmt, mem, ptr, thread};
+
+#[cfg(any(target_os = "linux", target_os = "dragonfly"))]
+use libc::__errno_location as errno_location;
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "bitrig",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+use libc::__errno as errno_location;
+
+#[cfg(any(target_os = "freebsd", target_os = "ios", target_os = "macos"))]
+use libc::__error as errno_location;
+
+#[cfg(target_os = "solaris")]
+use libc::___errno as errno_location;
+
+/// Signal numbers must be smaller than this, large enough for the real-time
+/// signals on Linux.
+pub const MAX_SIGNAL: usize = 65;
+
+/// Maximum number of `SignalPipe`s that can exist at the same time.
+const MAX_PIPES: usize = 32;
+
+/// Signal receiver backed by `sigaction` handlers writing to a pipe.
+///
+/// The signal handler is installed process wide the first time a signal is
+/// added, by any `SignalPipe`, and is never uninstalled. On delivery it
+/// increments the count of the signal for every `SignalPipe` that includes it
+/// and writes a single byte into its pipe, only if the pipe wasn't written to
+/// since the last `receive`. Finally it calls the handler that was installed
+/// before ours, if any.
+///
+/// The handler only uses atomics and `write(2)`, which are async-signal-safe.
+pub struct SignalPipe {
+    slot: usize,
+    inner: Box<Inner>,
+    receiver: Io,
+    _sender: Io,
+}
+
+/// State shared with the signal handler.
+struct Inner {
+    sender: RawFd,
+    /// Set if the pipe contains a byte that hasn't been read by `receive`.
+    dirty: AtomicBool,
+    interested: Vec<AtomicBool>,
+    counts: Vec<AtomicUsize>,
+}
+
+impl SignalPipe {
+    pub fn new() -> io::Result<SignalPipe> {
+        let (receiver, sender) = pipe()?;
+        let inner = Box::new(Inner {
+            sender: sender.as_raw_fd(),
+            dirty: AtomicBool::new(false),
+            interested: (0..MAX_SIGNAL).map(|_| AtomicBool::new(false)).collect(),
+            counts: (0..MAX_SIGNAL).map(|_| AtomicUsize::new(0)).collect(),
+        });
+
+        let globals = globals();
+        let _guard = globals.installed.lock().unwrap();
+        let slot = globals
+            .pipes
+            .iter()
+            .position(|pipe| pipe.load(Ordering::SeqCst).is_null())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "too many signal pipes"))?;
+        globals.pipes[slot].store(&*inner as *const Inner as *mut Inner, Ordering::SeqCst);
+
+        Ok(SignalPipe {
+            slot,
+            inner,
+            receiver,
+            _sender: sender,
+        })
+    }
+
+    pub fn add(&self, signal: c_int) -> io::Result<()> {
+        let index = check_signal(signal)?;
+        install(signal)?;
+        self.inner.interested[index].store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    pub fn remove(&self, signal: c_int) -> io::Result<()> {
+        let index = check_signal(signal)?;
+        self.inner.interested[index].store(false, Ordering::SeqCst);
+        Ok(())
+    }
+
+    pub fn contains(&self, signal: c_int) -> bool {
+        match check_signal(signal) {
+            Ok(index) => self.inner.interested[index].load(Ordering::SeqCst),
+            Err(_) => false,
+        }
+    }
+
+    /// Drain the pipe and move the signal counts into `counts`. Returns a
+    /// `WouldBlock` error if no signals were delivered.
+    pub fn receive(&self, counts: &mut [usize; MAX_SIGNAL]) -> io::Result<()> {
+        let mut drained = 0;
+        let mut buf = [0; 16];
+        loop {
+            match (&self.receiver).read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => drained += n,
+                Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => break,
+                Err(ref err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            }
+        }
+
+        // The order is important here. After draining the pipe we allow the
+        // signal handler to write to it again, before taking the counts. So
+        // if a signal is delivered after taking the counts a byte is written
+        // to the pipe, ensuring the caller gets another readiness event.
+        self.inner.dirty.store(false, Ordering::SeqCst);
+        let mut any = false;
+        for (count, shared) in counts.iter_mut().zip(self.inner.counts.iter()) {
+            *count = shared.swap(0, Ordering::SeqCst);
+            any |= *count != 0;
+        }
+
+        if drained == 0 && !any {
+            Err(io::ErrorKind::WouldBlock.into())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Drop for SignalPipe {
+    fn drop(&mut self) {
+        let globals = globals();
+        globals.pipes[self.slot].store(ptr::null_mut(), Ordering::SeqCst);
+        // Wait for any signal handler that might still be using our shared
+        // state or sender file descriptor.
+        while globals.active.load(Ordering::SeqCst) != 0 {
+            thread::yield_now();
+        }
+    }
+}
+
+impl Inner {
+    /// Called from the signal handler.
+    fn notify(&self, index: usize) {
+        if !self.interested[index].load(Ordering::SeqCst) {
+            return;
+        }
+        self.counts[index].fetch_add(1, Ordering::SeqCst);
+        if !self.dirty.swap(true, Ordering::SeqCst) {
+            // If this fails the pipe is full, which means the reading side
+            // will get woken up anyway.
+            unsafe {
+                libc::write(self.sender, &1u8 as *const u8 as *const c_void, 1);
+            }
+        }
+    }
+}
+
+/// Process wide state, shared by all `SignalPipe`s and the signal handler.
+struct Globals {
+    /// Signals for which our handler is installed. The lock is also used to
+    /// serialise claiming of `pipes` slots.
+    installed: Mutex<Vec<bool>>,
+    /// Handlers that were installed before ours, indexed by signal. Only
+    /// written, while holding the `installed` lock, before our handler is
+    /// installed for the signal.
+    previous: Vec<UnsafeCell<libc::sigaction>>,
+    /// Shared state of the existing `SignalPipe`s.
+    pipes: Vec<AtomicPtr<Inner>>,
+    /// Number of signal handlers that are currently running.
+    active: AtomicUsize,
+}
+
+unsafe impl Sync for Globals {}
+
+static INIT: Once = Once::new();
+static mut GLOBALS: *const Globals = ptr::null();
+
+fn globals() -> &'static Globals {
+    INIT.call_once(|| {
+        let globals = Globals {
+            installed: Mutex::new(vec![false; MAX_SIGNAL]),
+            previous: (0..MAX_SIGNAL)
+                .map(|_| UnsafeCell::new(unsafe { mem::zeroed() }))
+                .collect(),
+            pipes: (0..MAX_PIPES)
+                .map(|_| AtomicPtr::new(ptr::null_mut()))
+                .collect(),
+            active: AtomicUsize::new(0),
+        };
+        unsafe { GLOBALS = Box::into_raw(Box::new(globals)) };
+    });
+    unsafe { &*GLOBALS }
+}
+
+fn check_signal(signal: c_int) -> io::Result<usize> {
+    if signal > 0 && (signal as usize) < MAX_SIGNAL {
+        Ok(signal as usize)
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "invalid signal number",
+        ))
+    }
+}
+
+/// Install our signal handler for `signal`, if not already done.
+fn install(signal: c_int) -> io::Result<()> {
+    let globals = globals();
+    let mut installed = globals.installed.lock().unwrap();
+    if installed[signal as usize] {
+        return Ok(());
+    }
+
+    unsafe {
+        let mut action: libc::sigaction = mem::zeroed();
+        action.sa_sigaction = handler as Handler as libc::sighandler_t;
+        action.sa_flags = libc::SA_SIGINFO | libc::SA_RESTART;
+        cvt(libc::sigemptyset(&mut action.sa_mask))?;
+
+        // Retrieve the current handler first, so it's in place before our
+        // handler can be called.
+        let previous = globals.previous[signal as usize].get();
+        cvt(libc::sigaction(signal, ptr::null(), previous))?;
+        cvt(libc::sigaction(signal, &action, ptr::null_mut()))?;
+    }
+    installed[signal as usize] = true;
+    Ok(())
+}
+
+type Handler = extern "C" fn(c_int, *mut libc::siginfo_t, *mut c_void);
+
+extern "C" fn handler(signal: c_int, info: *mut libc::siginfo_t, context: *mut c_void) {
+    // Writing to the pipe could overwrite `errno`, which the interrupted code
+    // might be about to read.
+    let errno = unsafe { *errno_location() };
+
+    let globals = unsafe { &*GLOBALS };
+    let index = signal as usize;
+    globals.active.fetch_add(1, Ordering::SeqCst);
+    for pipe in globals.pipes.iter() {
+        let inner = pipe.load(Ordering::SeqCst);
+        if !inner.is_null() {
+            unsafe { (*inner).notify(index) };
+        }
+    }
+    globals.active.fetch_sub(1, Ordering::SeqCst);
+
+    // Chain to the previously installed handler, ignoring the default
+    // disposition as that would defeat the point of handling the signal.
+    let previous = unsafe { &*globals.previous[index].get() };
+    if previous.sa_flags & libc::SA_SIGINFO != 0 {
+        let previous: Handler = unsafe { mem::transmute(previous.sa_sigaction) };
+        previous(signal, info, context);
+    } else if previous.sa_sigaction != libc::SIG_DFL && previous.sa_sigaction != libc::SIG_IGN {
+        let previous: extern "C" fn(c_int) = unsafe { mem::transmute(previous.sa_sigaction) };
+        previous(signal);
+    }
+
+    unsafe { *errno_location() = errno };
+}
+
+impl Evented for SignalPipe {
+    fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        self.receiver.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &self,
+        registry: &Registry,
+        token: Token,
+        interests: Interests,
+    ) -> io::Result<()> {
+        self.receiver.reregister(registry, token, interests)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.receiver.deregister(registry)
+    }
+}
+
+impl fmt::Debug for SignalPipe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SignalPipe")
+            .field("receiver", &self.receiver.as_raw_fd())
+            .field("sender", &self.inner.sender)
+            .finish()
+    }
+}
+
+impl AsRawFd for SignalPipe {
+    fn as_raw_fd(&self) -> RawFd {
+        self.receiver.as_raw_fd()
+    }
+}
@@ -0,0 +1,135 @@
+use crate::event::Evented;
+use crate::sys::unix::cvt;
+use crate::unix::EventedFd;
+use crate::{Interests, Registry, Token};
+
+use libc::{self, c_int};
+use std::fs::File;
+use std::io::{self, Read};
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use std::{fmt, mem, ptr, slice};
+
+/// Signal receiver backed by `signalfd`.
+///
+/// `signalfd` only receives signals that are blocked, otherwise the signal's
+/// disposition (e.g. the default action of terminating the process) takes
+/// precedence. Blocking is done per thread, but threads inherit the signal
+/// mask of the thread that spawned them.
+pub struct Signals {
+    fd: File,
+    mask: libc::sigset_t,
+}
+
+impl Signals {
+    pub fn new(signals: &[c_int]) -> io::Result<Signals> {
+        let mut mask = empty_sigset()?;
+        for &signal in signals {
+            unsafe { cvt(libc::sigaddset(&mut mask, signal))? };
+        }
+        block(&mask)?;
+        let fd = unsafe {
+            cvt(libc::signalfd(
+                -1,
+                &mask,
+                libc::SFD_CLOEXEC | libc::SFD_NONBLOCK,
+            ))?
+        };
+        Ok(Signals {
+            fd: unsafe { File::from_raw_fd(fd) },
+            mask,
+        })
+    }
+
+    pub fn add(&mut self, signal: c_int) -> io::Result<()> {
+        let mut mask = self.mask;
+        unsafe { cvt(libc::sigaddset(&mut mask, signal))? };
+        let mut single = empty_sigset()?;
+        unsafe { cvt(libc::sigaddset(&mut single, signal))? };
+        block(&single)?;
+        self.set_mask(mask)
+    }
+
+    pub fn remove(&mut self, signal: c_int) -> io::Result<()> {
+        let mut mask = self.mask;
+        unsafe { cvt(libc::sigdelset(&mut mask, signal))? };
+        self.set_mask(mask)
+    }
+
+    pub fn contains(&self, signal: c_int) -> io::Result<bool> {
+        unsafe { cvt(libc::sigismember(&self.mask, signal)).map(|res| res == 1) }
+    }
+
+    /// Read a single signal, returns a `WouldBlock` error if no signal is
+    /// pending.
+    pub fn receive(&self) -> io::Result<libc::signalfd_siginfo> {
+        let mut info: libc::signalfd_siginfo = unsafe { mem::zeroed() };
+        let buf = unsafe {
+            slice::from_raw_parts_mut(
+                &mut info as *mut _ as *mut u8,
+                mem::size_of::<libc::signalfd_siginfo>(),
+            )
+        };
+        // A read of the size of a single `signalfd_siginfo` is never short.
+        (&self.fd).read(buf).map(|_| info)
+    }
+
+    fn set_mask(&mut self, mask: libc::sigset_t) -> io::Result<()> {
+        unsafe { cvt(libc::signalfd(self.fd.as_raw_fd(), &mask, 0))? };
+        self.mask = mask;
+        Ok(())
+    }
+}
+
+fn empty_sigset() -> io::Result<libc::sigset_t> {
+    let mut set: libc::sigset_t = unsafe { mem::zeroed() };
+    unsafe { cvt(libc::sigemptyset(&mut set))? };
+    Ok(set)
+}
+
+/// Block the signals in `set` for the calling thread.
+fn block(set: &libc::sigset_t) -> io::Result<()> {
+    // `pthread_sigmask` returns the error rather than setting `errno`.
+    match unsafe { libc::pthread_sigmask(libc::SIG_BLOCK, set, ptr::null_mut()) } {
+        0 => Ok(()),
+        err => Err(io::Error::from_raw_os_error(err)),
+    }
+}
+
+impl Evented for Signals {
+    fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).register(registry, token, interests)
+    }
+
+    fn reregister(
+        &self,
+        registry: &Registry,
+        token: Token,
+        interests: Interests,
+    ) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).reregister(registry, token, interests)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).deregister(registry)
+    }
+}
+
+impl fmt::Debug for Signals {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Signals")
+            .field("fd", &self.fd.as_raw_fd())
+            .finish()
+    }
+}
+
+impl IntoRawFd for Signals {
+    fn into_raw_fd(self) -> RawFd {
+        self.fd.into_raw_fd()
+    }
+}
+
+impl AsRawFd for Signals {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
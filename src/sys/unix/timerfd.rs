@@ -0,0 +1,136 @@
+use crate::event::Evented;
+use crate::sys::unix::cvt;
+use crate::unix::EventedFd;
+use crate::{Interests, Registry, Token};
+
+use libc;
+use std::fs::File;
+use std::io::{self, Read};
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use std::time::Duration;
+use std::{cmp, fmt, mem, ptr};
+
+/// Timer backed by `timerfd`.
+///
+/// A `timerfd` becomes readable once it has expired at least once. Reading
+/// from it returns the number of expirations since the last read (or since it
+/// was last armed) as a native endian 64 bit unsigned integer, and resets that
+/// count to 0.
+pub struct Timer {
+    fd: File,
+}
+
+impl Timer {
+    pub fn new(clock: libc::clockid_t) -> io::Result<Timer> {
+        let fd = unsafe {
+            cvt(libc::timerfd_create(
+                clock,
+                libc::TFD_CLOEXEC | libc::TFD_NONBLOCK,
+            ))?
+        };
+        Ok(Timer {
+            fd: unsafe { File::from_raw_fd(fd) },
+        })
+    }
+
+    /// Arm the timer, `value` being the first expiration and `interval` the
+    /// period after that. A zero `interval` makes the timer one-shot, a zero
+    /// `value` disarms the timer.
+    pub fn set(&self, value: Duration, interval: Duration) -> io::Result<()> {
+        let new_value = libc::itimerspec {
+            it_interval: timespec(interval),
+            it_value: timespec(value),
+        };
+        unsafe {
+            cvt(libc::timerfd_settime(
+                self.fd.as_raw_fd(),
+                0,
+                &new_value,
+                ptr::null_mut(),
+            ))
+            .map(|_| ())
+        }
+    }
+
+    /// Returns the time until the next expiration and the interval of the
+    /// timer.
+    pub fn get(&self) -> io::Result<(Duration, Duration)> {
+        let mut curr_value: libc::itimerspec = unsafe { mem::zeroed() };
+        unsafe {
+            cvt(libc::timerfd_gettime(self.fd.as_raw_fd(), &mut curr_value))?;
+        }
+        Ok((
+            duration(curr_value.it_value),
+            duration(curr_value.it_interval),
+        ))
+    }
+
+    /// Read the number of expirations, resetting the count to 0.
+    pub fn read(&self) -> io::Result<u64> {
+        let mut buf = [0; 8];
+        (&self.fd).read_exact(&mut buf)?;
+        Ok(u64::from_ne_bytes(buf))
+    }
+}
+
+/// Convert a `Duration` into a `timespec`, saturating at the maximum value of
+/// `time_t`.
+pub fn timespec(duration: Duration) -> libc::timespec {
+    libc::timespec {
+        tv_sec: cmp::min(duration.as_secs(), libc::time_t::max_value() as u64) as libc::time_t,
+        // `Duration::subsec_nanos` is always smaller than one billion, which
+        // fits in a C long on all platforms.
+        tv_nsec: libc::c_long::from(duration.subsec_nanos() as i32),
+    }
+}
+
+fn duration(timespec: libc::timespec) -> Duration {
+    Duration::new(timespec.tv_sec as u64, timespec.tv_nsec as u32)
+}
+
+impl Evented for Timer {
+    fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).register(registry, token, interests)
+    }
+
+    fn reregister(
+        &self,
+        registry: &Registry,
+        token: Token,
+        interests: Interests,
+    ) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).reregister(registry, token, interests)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).deregister(registry)
+    }
+}
+
+impl fmt::Debug for Timer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Timer")
+            .field("fd", &self.fd.as_raw_fd())
+            .finish()
+    }
+}
+
+impl FromRawFd for Timer {
+    unsafe fn from_raw_fd(fd: RawFd) -> Timer {
+        Timer {
+            fd: File::from_raw_fd(fd),
+        }
+    }
+}
+
+impl IntoRawFd for Timer {
+    fn into_raw_fd(self) -> RawFd {
+        self.fd.into_raw_fd()
+    }
+}
+
+impl AsRawFd for Timer {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
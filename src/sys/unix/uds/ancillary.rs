@@ -0,0 +1,195 @@
+use crate::sys::unix::cvt;
+use crate::sys::unix::io::Io;
+
+use libc::{self, c_void};
+use std::os::unix::io::{FromRawFd, RawFd};
+use std::{io, mem, ptr};
+
+/// Send `buf` on `socket`, along with `fds` in a `SCM_RIGHTS` control message.
+pub fn send_with_fds(socket: RawFd, buf: &[u8], fds: &[RawFd]) -> io::Result<usize> {
+    let mut iov = libc::iovec {
+        iov_base: buf.as_ptr() as *mut c_void,
+        iov_len: buf.len(),
+    };
+    let mut msg: libc::msghdr = unsafe { mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+
+    let mut control = ControlBuf::new(fds.len(), 0)?;
+    if !fds.is_empty() {
+        msg.msg_control = control.as_mut_ptr();
+        msg.msg_controllen = control.len() as _;
+        unsafe {
+            let cmsg = libc::CMSG_FIRSTHDR(&msg);
+            (*cmsg).cmsg_level = libc::SOL_SOCKET;
+            (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+            (*cmsg).cmsg_len = libc::CMSG_LEN(fds_len(fds.len())) as _;
+            ptr::copy_nonoverlapping(fds.as_ptr(), libc::CMSG_DATA(cmsg) as *mut RawFd, fds.len());
+        }
+    }
+
+    let n = cvt(unsafe { libc::sendmsg(socket, &msg, 0) })?;
+    Ok(n as usize)
+}
+
+/// Receive into `buf` from `socket`, along with at most `max_fds` file
+/// descriptors sent in `SCM_RIGHTS` control messages.
+pub fn recv_with_fds(
+    socket: RawFd,
+    buf: &mut [u8],
+    max_fds: usize,
+) -> io::Result<(usize, Vec<Io>)> {
+    // The credentials are received along with the file descriptors if
+    // `SO_PASSCRED` is set, without room for them the message is truncated.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    let extra_len = mem::size_of::<libc::ucred>() as u32;
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    let extra_len = 0;
+    recv_msg(socket, buf, max_fds, extra_len).map(|msg| (msg.len, msg.fds))
+}
+
+/// Receive into `buf` from `socket`, along with the credentials of the sender
+/// if `SO_PASSCRED` is set on `socket`.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn recv_with_cred(socket: RawFd, buf: &mut [u8]) -> io::Result<(usize, Option<libc::ucred>)> {
+    let cred_len = mem::size_of::<libc::ucred>() as u32;
+    recv_msg(socket, buf, 0, cred_len).map(|msg| (msg.len, msg.cred))
+}
+
+/// A message received by `recv_msg`.
+struct Received {
+    len: usize,
+    fds: Vec<Io>,
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    cred: Option<libc::ucred>,
+}
+
+/// Receive into `buf` from `socket`, with room for `max_fds` file descriptors
+/// and one other control message of `extra_len` bytes.
+///
+/// The received file descriptors are owned as soon as they're read from the
+/// control message, so they're closed if an error is returned after that.
+fn recv_msg(socket: RawFd, buf: &mut [u8], max_fds: usize, extra_len: u32) -> io::Result<Received> {
+    let mut iov = libc::iovec {
+        iov_base: buf.as_mut_ptr() as *mut c_void,
+        iov_len: buf.len(),
+    };
+    let mut msg: libc::msghdr = unsafe { mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+
+    let mut control = ControlBuf::new(max_fds, extra_len)?;
+    if control.len() != 0 {
+        msg.msg_control = control.as_mut_ptr();
+        msg.msg_controllen = control.len() as _;
+    }
+
+    #[cfg(not(any(target_os = "ios", target_os = "macos")))]
+    let flags = libc::MSG_CMSG_CLOEXEC;
+    #[cfg(any(target_os = "ios", target_os = "macos"))]
+    let flags = 0;
+
+    let n = cvt(unsafe { libc::recvmsg(socket, &mut msg, flags) })?;
+
+    let mut received = Received {
+        len: n as usize,
+        fds: Vec::new(),
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        cred: None,
+    };
+    if control.len() != 0 {
+        unsafe {
+            let mut cmsg = libc::CMSG_FIRSTHDR(&msg);
+            while !cmsg.is_null() {
+                let data = libc::CMSG_DATA(cmsg);
+                let len = (*cmsg).cmsg_len as usize - libc::CMSG_LEN(0) as usize;
+                match ((*cmsg).cmsg_level, (*cmsg).cmsg_type) {
+                    (libc::SOL_SOCKET, libc::SCM_RIGHTS) => {
+                        let data = data as *const RawFd;
+                        for i in 0..len / mem::size_of::<RawFd>() {
+                            let fd = ptr::read_unaligned(data.add(i));
+                            received.fds.push(Io::from_raw_fd(fd));
+                        }
+                    }
+                    #[cfg(any(target_os = "linux", target_os = "android"))]
+                    (libc::SOL_SOCKET, libc::SCM_CREDENTIALS)
+                        if len >= mem::size_of::<libc::ucred>() =>
+                    {
+                        let cred = ptr::read_unaligned(data as *const libc::ucred);
+                        received.cred = Some(cred);
+                    }
+                    _ => {}
+                }
+                cmsg = libc::CMSG_NXTHDR(&msg, cmsg);
+            }
+        }
+    }
+
+    #[cfg(any(target_os = "ios", target_os = "macos"))]
+    for fd in &received.fds {
+        use crate::sys::unix::io::set_cloexec;
+        use std::os::unix::io::AsRawFd;
+        set_cloexec(fd.as_raw_fd())?;
+    }
+
+    // Room for other control messages can fit more than `max_fds` file
+    // descriptors.
+    if msg.msg_flags & libc::MSG_CTRUNC != 0 || received.fds.len() > max_fds {
+        // The file descriptors that didn't fit are closed by the kernel,
+        // dropping `received` closes the ones that did.
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            "control message truncated, received more file descriptors than requested",
+        ));
+    }
+
+    Ok(received)
+}
+
+/// Maximum number of file descriptors in a single message. Linux limits this
+/// to 253 (`SCM_MAX_FD`), other platforms are in the same ballpark.
+const MAX_FDS: usize = 1024;
+
+/// Size, in bytes, of `n` file descriptors.
+fn fds_len(n: usize) -> u32 {
+    (n * mem::size_of::<RawFd>()) as u32
+}
+
+/// Buffer for control messages, aligned for `cmsghdr`.
+struct ControlBuf {
+    buf: Vec<usize>,
+    len: usize,
+}
+
+impl ControlBuf {
+    /// Create a buffer large enough for a control message containing `fds`
+    /// file descriptors, and one other control message of `extra_len` bytes.
+    fn new(fds: usize, extra_len: u32) -> io::Result<ControlBuf> {
+        if fds > MAX_FDS {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "too many file descriptors",
+            ));
+        }
+        let mut len = 0;
+        if fds != 0 {
+            len += unsafe { libc::CMSG_SPACE(fds_len(fds)) as usize };
+        }
+        if extra_len != 0 {
+            len += unsafe { libc::CMSG_SPACE(extra_len) as usize };
+        }
+        let word = mem::size_of::<usize>();
+        Ok(ControlBuf {
+            buf: vec![0; len / word + 1],
+            len,
+        })
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut c_void {
+        self.buf.as_mut_ptr() as *mut c_void
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+}
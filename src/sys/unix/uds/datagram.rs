@@ -0,0 +1,162 @@
+use crate::event::Evented;
+use crate::sys::unix::eventedfd::EventedFd;
+use crate::sys::unix::io::{set_nonblock, Io};
+use crate::sys::unix::uio::VecIo;
+use crate::{Interests, Registry, Token};
+
+use iovec::IoVec;
+use libc;
+use std::fmt;
+use std::io;
+use std::net::Shutdown;
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use std::os::unix::net::{self, SocketAddr};
+use std::path::Path;
+
+pub struct UnixDatagram {
+    inner: net::UnixDatagram,
+}
+
+impl UnixDatagram {
+    pub fn bind(path: &Path) -> io::Result<UnixDatagram> {
+        let socket = super::socket(libc::SOCK_DGRAM)?;
+        super::bind(socket.as_raw_fd(), path)?;
+        Ok(unsafe { UnixDatagram::from_raw_fd(socket.into_raw_fd()) })
+    }
+
+    pub fn unbound() -> io::Result<UnixDatagram> {
+        let socket = super::socket(libc::SOCK_DGRAM)?;
+        Ok(unsafe { UnixDatagram::from_raw_fd(socket.into_raw_fd()) })
+    }
+
+    pub fn pair() -> io::Result<(UnixDatagram, UnixDatagram)> {
+        let (a, b) = super::pair(libc::SOCK_DGRAM)?;
+        unsafe {
+            Ok((
+                UnixDatagram::from_raw_fd(a.into_raw_fd()),
+                UnixDatagram::from_raw_fd(b.into_raw_fd()),
+            ))
+        }
+    }
+
+    pub fn from_std(socket: net::UnixDatagram) -> io::Result<UnixDatagram> {
+        set_nonblock(socket.as_raw_fd())?;
+        Ok(UnixDatagram { inner: socket })
+    }
+
+    pub fn connect(&self, path: &Path) -> io::Result<()> {
+        super::connect(self.as_raw_fd(), path)
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.inner.local_addr()
+    }
+
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.inner.peer_addr()
+    }
+
+    pub fn try_clone(&self) -> io::Result<UnixDatagram> {
+        self.inner.try_clone().map(|s| UnixDatagram { inner: s })
+    }
+
+    pub fn send_to(&self, buf: &[u8], path: &Path) -> io::Result<usize> {
+        super::send_to(self.as_raw_fd(), buf, path)
+    }
+
+    pub fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        self.inner.recv_from(buf)
+    }
+
+    pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.send(buf)
+    }
+
+    pub fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.recv(buf)
+    }
+
+    pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+        self.inner.shutdown(how)
+    }
+
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.inner.take_error()
+    }
+
+    pub fn readv(&self, bufs: &mut [&mut IoVec]) -> io::Result<usize> {
+        self.inner.readv(bufs)
+    }
+
+    pub fn writev(&self, bufs: &[&IoVec]) -> io::Result<usize> {
+        self.inner.writev(bufs)
+    }
+
+    pub fn send_with_fds(&self, buf: &[u8], fds: &[RawFd]) -> io::Result<usize> {
+        super::send_with_fds(self.as_raw_fd(), buf, fds)
+    }
+
+    pub fn recv_with_fds(&self, buf: &mut [u8], max_fds: usize) -> io::Result<(usize, Vec<Io>)> {
+        super::recv_with_fds(self.as_raw_fd(), buf, max_fds)
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn recv_with_cred(&self, buf: &mut [u8]) -> io::Result<(usize, Option<libc::ucred>)> {
+        super::recv_with_cred(self.as_raw_fd(), buf)
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn set_passcred(&self, passcred: bool) -> io::Result<()> {
+        super::set_passcred(self.as_raw_fd(), passcred)
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn passcred(&self) -> io::Result<bool> {
+        super::passcred(self.as_raw_fd())
+    }
+}
+
+impl Evented for UnixDatagram {
+    fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).register(registry, token, interests)
+    }
+
+    fn reregister(
+        &self,
+        registry: &Registry,
+        token: Token,
+        interests: Interests,
+    ) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).reregister(registry, token, interests)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).deregister(registry)
+    }
+}
+
+impl fmt::Debug for UnixDatagram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.inner, f)
+    }
+}
+
+impl FromRawFd for UnixDatagram {
+    unsafe fn from_raw_fd(fd: RawFd) -> UnixDatagram {
+        UnixDatagram {
+            inner: net::UnixDatagram::from_raw_fd(fd),
+        }
+    }
+}
+
+impl IntoRawFd for UnixDatagram {
+    fn into_raw_fd(self) -> RawFd {
+        self.inner.into_raw_fd()
+    }
+}
+
+impl AsRawFd for UnixDatagram {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
@@ -0,0 +1,90 @@
+use crate::event::Evented;
+use crate::sys::unix::eventedfd::EventedFd;
+use crate::sys::unix::io::set_nonblock;
+use crate::{Interests, Registry, Token};
+
+use libc;
+use std::fmt;
+use std::io;
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use std::os::unix::net::{self, SocketAddr};
+use std::path::Path;
+
+pub struct UnixListener {
+    inner: net::UnixListener,
+}
+
+impl UnixListener {
+    pub fn bind(path: &Path, unlink_stale: bool) -> io::Result<UnixListener> {
+        let socket = super::listen(libc::SOCK_STREAM, path, unlink_stale)?;
+        Ok(UnixListener {
+            inner: unsafe { net::UnixListener::from_raw_fd(socket.into_raw_fd()) },
+        })
+    }
+
+    pub fn from_std(listener: net::UnixListener) -> io::Result<UnixListener> {
+        set_nonblock(listener.as_raw_fd())?;
+        Ok(UnixListener { inner: listener })
+    }
+
+    pub fn accept(&self) -> io::Result<(net::UnixStream, SocketAddr)> {
+        self.inner.accept()
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.inner.local_addr()
+    }
+
+    pub fn try_clone(&self) -> io::Result<UnixListener> {
+        self.inner.try_clone().map(|s| UnixListener { inner: s })
+    }
+
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.inner.take_error()
+    }
+}
+
+impl Evented for UnixListener {
+    fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).register(registry, token, interests)
+    }
+
+    fn reregister(
+        &self,
+        registry: &Registry,
+        token: Token,
+        interests: Interests,
+    ) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).reregister(registry, token, interests)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).deregister(registry)
+    }
+}
+
+impl fmt::Debug for UnixListener {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.inner, f)
+    }
+}
+
+impl FromRawFd for UnixListener {
+    unsafe fn from_raw_fd(fd: RawFd) -> UnixListener {
+        UnixListener {
+            inner: net::UnixListener::from_raw_fd(fd),
+        }
+    }
+}
+
+impl IntoRawFd for UnixListener {
+    fn into_raw_fd(self) -> RawFd {
+        self.inner.into_raw_fd()
+    }
+}
+
+impl AsRawFd for UnixListener {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
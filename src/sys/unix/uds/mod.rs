@@ -0,0 +1,234 @@
+use crate::sys::unix::cvt;
+use crate::sys::unix::io::Io;
+
+use libc::{self, c_int};
+use std::fs;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+use std::path::Path;
+use std::{io, mem};
+
+mod ancillary;
+mod datagram;
+mod listener;
+mod seqpacket;
+mod stream;
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub use self::ancillary::recv_with_cred;
+pub use self::ancillary::{recv_with_fds, send_with_fds};
+pub use self::datagram::UnixDatagram;
+pub use self::listener::UnixListener;
+pub use self::seqpacket::{UnixSeqpacket, UnixSeqpacketListener};
+pub use self::stream::UnixStream;
+
+/// Converts `path` into a `sockaddr_un`, returning it and the length of the
+/// used part of it.
+///
+/// A `path` starting with a null byte is an address in the Linux abstract
+/// namespace, the remaining bytes are used as is (not null terminated).
+fn socket_addr(path: &Path) -> io::Result<(libc::sockaddr_un, libc::socklen_t)> {
+    let mut addr: libc::sockaddr_un = unsafe { mem::zeroed() };
+    addr.sun_family = libc::AF_UNIX as libc::sa_family_t;
+
+    let bytes = path.as_os_str().as_bytes();
+    let abstract_namespace = bytes.first() == Some(&0);
+    if !abstract_namespace && bytes.contains(&0) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "paths may not contain interior null bytes",
+        ));
+    }
+    // Pathnames need room for the null terminator.
+    if bytes.len() + (!abstract_namespace as usize) > addr.sun_path.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "path must be shorter than SUN_LEN",
+        ));
+    }
+    for (dst, src) in addr.sun_path.iter_mut().zip(bytes) {
+        *dst = *src as libc::c_char;
+    }
+
+    let offset = path_offset(&addr);
+    let mut len = offset + bytes.len();
+    if !abstract_namespace && !bytes.is_empty() {
+        len += 1;
+    }
+    Ok((addr, len as libc::socklen_t))
+}
+
+/// Returns `true` if `path` is an address in the Linux abstract namespace.
+fn is_abstract(path: &Path) -> bool {
+    path.as_os_str().as_bytes().first() == Some(&0)
+}
+
+fn path_offset(addr: &libc::sockaddr_un) -> usize {
+    &addr.sun_path as *const _ as usize - addr as *const _ as usize
+}
+
+/// Create a new non-blocking, close-on-exec Unix socket of type `ty`.
+pub fn socket(ty: c_int) -> io::Result<Io> {
+    #[cfg(not(any(target_os = "ios", target_os = "macos")))]
+    let ty = ty | libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC;
+
+    let socket = unsafe { Io::from_raw_fd(cvt(libc::socket(libc::AF_UNIX, ty, 0))?) };
+
+    #[cfg(any(target_os = "ios", target_os = "macos"))]
+    {
+        use crate::sys::unix::io::{set_cloexec, set_nonblock};
+        set_nonblock(socket.as_raw_fd())?;
+        set_cloexec(socket.as_raw_fd())?;
+    }
+
+    Ok(socket)
+}
+
+/// Create a pair of connected, non-blocking and close-on-exec Unix sockets of
+/// type `ty`.
+pub fn pair(ty: c_int) -> io::Result<(Io, Io)> {
+    #[cfg(not(any(target_os = "ios", target_os = "macos")))]
+    let ty = ty | libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC;
+
+    let mut fds = [-1; 2];
+    cvt(unsafe { libc::socketpair(libc::AF_UNIX, ty, 0, fds.as_mut_ptr()) })?;
+    let pair = unsafe { (Io::from_raw_fd(fds[0]), Io::from_raw_fd(fds[1])) };
+
+    #[cfg(any(target_os = "ios", target_os = "macos"))]
+    {
+        use crate::sys::unix::io::{set_cloexec, set_nonblock};
+        set_nonblock(pair.0.as_raw_fd())?;
+        set_cloexec(pair.0.as_raw_fd())?;
+        set_nonblock(pair.1.as_raw_fd())?;
+        set_cloexec(pair.1.as_raw_fd())?;
+    }
+
+    Ok(pair)
+}
+
+/// Bind `socket` to `path`.
+pub fn bind(socket: RawFd, path: &Path) -> io::Result<()> {
+    let (addr, len) = socket_addr(path)?;
+    let addr = &addr as *const libc::sockaddr_un as *const libc::sockaddr;
+    cvt(unsafe { libc::bind(socket, addr, len) }).map(|_| ())
+}
+
+/// Issue a non-blocking connect of `socket` to `path`. For datagram sockets
+/// this sets the default destination.
+pub fn connect(socket: RawFd, path: &Path) -> io::Result<()> {
+    let (addr, len) = socket_addr(path)?;
+    let addr = &addr as *const libc::sockaddr_un as *const libc::sockaddr;
+    match cvt(unsafe { libc::connect(socket, addr, len) }) {
+        Ok(..) => Ok(()),
+        Err(ref e) if e.raw_os_error() == Some(libc::EINPROGRESS) => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Send `buf` on `socket` to `path`.
+pub fn send_to(socket: RawFd, buf: &[u8], path: &Path) -> io::Result<usize> {
+    let (addr, len) = socket_addr(path)?;
+    let addr = &addr as *const libc::sockaddr_un as *const libc::sockaddr;
+    let n = cvt(unsafe {
+        libc::sendto(
+            socket,
+            buf.as_ptr() as *const libc::c_void,
+            buf.len(),
+            0,
+            addr,
+            len,
+        )
+    })?;
+    Ok(n as usize)
+}
+
+/// Returns the credentials of the peer of `socket`, as they were when the
+/// connection was established, using `SO_PEERCRED`.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn peer_cred(socket: RawFd) -> io::Result<libc::ucred> {
+    let mut cred: libc::ucred = unsafe { mem::zeroed() };
+    let mut len = mem::size_of::<libc::ucred>() as libc::socklen_t;
+    cvt(unsafe {
+        libc::getsockopt(
+            socket,
+            libc::SOL_SOCKET,
+            libc::SO_PEERCRED,
+            &mut cred as *mut libc::ucred as *mut libc::c_void,
+            &mut len,
+        )
+    })?;
+    Ok(cred)
+}
+
+/// Sets the `SO_PASSCRED` option on `socket`.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn set_passcred(socket: RawFd, passcred: bool) -> io::Result<()> {
+    let value = passcred as c_int;
+    cvt(unsafe {
+        libc::setsockopt(
+            socket,
+            libc::SOL_SOCKET,
+            libc::SO_PASSCRED,
+            &value as *const c_int as *const libc::c_void,
+            mem::size_of::<c_int>() as libc::socklen_t,
+        )
+    })
+    .map(|_| ())
+}
+
+/// Gets the `SO_PASSCRED` option of `socket`.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn passcred(socket: RawFd) -> io::Result<bool> {
+    let mut value: c_int = 0;
+    let mut len = mem::size_of::<c_int>() as libc::socklen_t;
+    cvt(unsafe {
+        libc::getsockopt(
+            socket,
+            libc::SOL_SOCKET,
+            libc::SO_PASSCRED,
+            &mut value as *mut c_int as *mut libc::c_void,
+            &mut len,
+        )
+    })?;
+    Ok(value != 0)
+}
+
+/// Create a new listening Unix socket of type `ty`, bound to `path`. If
+/// `unlink_stale` is true a stale socket file at `path` is removed first.
+pub fn listen(ty: c_int, path: &Path, unlink_stale: bool) -> io::Result<Io> {
+    if unlink_stale {
+        self::unlink_stale(ty, path)?;
+    }
+
+    let socket = socket(ty)?;
+    bind(socket.as_raw_fd(), path)?;
+    cvt(unsafe { libc::listen(socket.as_raw_fd(), 1024) })?;
+    Ok(socket)
+}
+
+/// Removes the socket file at `path` if no process is listening on it any
+/// more, i.e. connecting to it is refused.
+///
+/// Anything that isn't a socket file is left alone, so that `bind` reports the
+/// error.
+fn unlink_stale(ty: c_int, path: &Path) -> io::Result<()> {
+    if is_abstract(path) {
+        return Ok(());
+    }
+    match fs::symlink_metadata(path) {
+        Ok(ref metadata) if metadata.file_type().is_socket() => {}
+        _ => return Ok(()),
+    }
+
+    let socket = socket(ty)?;
+    match connect(socket.as_raw_fd(), path) {
+        Err(ref err) if err.raw_os_error() == Some(libc::ECONNREFUSED) => {
+            match fs::remove_file(path) {
+                Err(ref err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+                res => res,
+            }
+        }
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,223 @@
+use crate::event::Evented;
+use crate::sys::unix::eventedfd::EventedFd;
+use crate::sys::unix::io::{set_nonblock, Io};
+use crate::sys::unix::uio::VecIo;
+use crate::{Interests, Registry, Token};
+
+use iovec::IoVec;
+use libc;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::Shutdown;
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use std::os::unix::net::{self, SocketAddr};
+use std::path::Path;
+
+// The standard library doesn't have seqpacket sockets, but the methods of its
+// stream types used here are plain system calls on the file descriptor that
+// work the same for any type of Unix socket.
+
+pub struct UnixSeqpacketListener {
+    inner: net::UnixListener,
+}
+
+pub struct UnixSeqpacket {
+    inner: net::UnixStream,
+}
+
+impl UnixSeqpacketListener {
+    pub fn bind(path: &Path, unlink_stale: bool) -> io::Result<UnixSeqpacketListener> {
+        let socket = super::listen(libc::SOCK_SEQPACKET, path, unlink_stale)?;
+        Ok(unsafe { UnixSeqpacketListener::from_raw_fd(socket.into_raw_fd()) })
+    }
+
+    pub fn accept(&self) -> io::Result<(UnixSeqpacket, SocketAddr)> {
+        let (socket, addr) = self.inner.accept()?;
+        set_nonblock(socket.as_raw_fd())?;
+        Ok((UnixSeqpacket { inner: socket }, addr))
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.inner.local_addr()
+    }
+
+    pub fn try_clone(&self) -> io::Result<UnixSeqpacketListener> {
+        self.inner
+            .try_clone()
+            .map(|s| UnixSeqpacketListener { inner: s })
+    }
+
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.inner.take_error()
+    }
+}
+
+impl UnixSeqpacket {
+    pub fn connect(path: &Path) -> io::Result<UnixSeqpacket> {
+        let socket = super::socket(libc::SOCK_SEQPACKET)?;
+        super::connect(socket.as_raw_fd(), path)?;
+        Ok(unsafe { UnixSeqpacket::from_raw_fd(socket.into_raw_fd()) })
+    }
+
+    pub fn pair() -> io::Result<(UnixSeqpacket, UnixSeqpacket)> {
+        let (a, b) = super::pair(libc::SOCK_SEQPACKET)?;
+        unsafe {
+            Ok((
+                UnixSeqpacket::from_raw_fd(a.into_raw_fd()),
+                UnixSeqpacket::from_raw_fd(b.into_raw_fd()),
+            ))
+        }
+    }
+
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.inner.peer_addr()
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.inner.local_addr()
+    }
+
+    pub fn try_clone(&self) -> io::Result<UnixSeqpacket> {
+        self.inner.try_clone().map(|s| UnixSeqpacket { inner: s })
+    }
+
+    pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        (&self.inner).write(buf)
+    }
+
+    pub fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        (&self.inner).read(buf)
+    }
+
+    pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+        self.inner.shutdown(how)
+    }
+
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.inner.take_error()
+    }
+
+    pub fn readv(&self, bufs: &mut [&mut IoVec]) -> io::Result<usize> {
+        self.inner.readv(bufs)
+    }
+
+    pub fn writev(&self, bufs: &[&IoVec]) -> io::Result<usize> {
+        self.inner.writev(bufs)
+    }
+
+    pub fn send_with_fds(&self, buf: &[u8], fds: &[RawFd]) -> io::Result<usize> {
+        super::send_with_fds(self.as_raw_fd(), buf, fds)
+    }
+
+    pub fn recv_with_fds(&self, buf: &mut [u8], max_fds: usize) -> io::Result<(usize, Vec<Io>)> {
+        super::recv_with_fds(self.as_raw_fd(), buf, max_fds)
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn peer_cred(&self) -> io::Result<libc::ucred> {
+        super::peer_cred(self.as_raw_fd())
+    }
+}
+
+impl Evented for UnixSeqpacketListener {
+    fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).register(registry, token, interests)
+    }
+
+    fn reregister(
+        &self,
+        registry: &Registry,
+        token: Token,
+        interests: Interests,
+    ) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).reregister(registry, token, interests)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).deregister(registry)
+    }
+}
+
+impl Evented for UnixSeqpacket {
+    fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).register(registry, token, interests)
+    }
+
+    fn reregister(
+        &self,
+        registry: &Registry,
+        token: Token,
+        interests: Interests,
+    ) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).reregister(registry, token, interests)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).deregister(registry)
+    }
+}
+
+impl fmt::Debug for UnixSeqpacketListener {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut builder = f.debug_struct("UnixSeqpacketListener");
+        builder.field("fd", &self.as_raw_fd());
+        if let Ok(addr) = self.local_addr() {
+            builder.field("local", &addr);
+        }
+        builder.finish()
+    }
+}
+
+impl fmt::Debug for UnixSeqpacket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut builder = f.debug_struct("UnixSeqpacket");
+        builder.field("fd", &self.as_raw_fd());
+        if let Ok(addr) = self.local_addr() {
+            builder.field("local", &addr);
+        }
+        if let Ok(addr) = self.peer_addr() {
+            builder.field("peer", &addr);
+        }
+        builder.finish()
+    }
+}
+
+impl FromRawFd for UnixSeqpacketListener {
+    unsafe fn from_raw_fd(fd: RawFd) -> UnixSeqpacketListener {
+        UnixSeqpacketListener {
+            inner: net::UnixListener::from_raw_fd(fd),
+        }
+    }
+}
+
+impl IntoRawFd for UnixSeqpacketListener {
+    fn into_raw_fd(self) -> RawFd {
+        self.inner.into_raw_fd()
+    }
+}
+
+impl AsRawFd for UnixSeqpacketListener {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+impl FromRawFd for UnixSeqpacket {
+    unsafe fn from_raw_fd(fd: RawFd) -> UnixSeqpacket {
+        UnixSeqpacket {
+            inner: net::UnixStream::from_raw_fd(fd),
+        }
+    }
+}
+
+impl IntoRawFd for UnixSeqpacket {
+    fn into_raw_fd(self) -> RawFd {
+        self.inner.into_raw_fd()
+    }
+}
+
+impl AsRawFd for UnixSeqpacket {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
@@ -0,0 +1,145 @@
+use crate::event::Evented;
+use crate::sys::unix::eventedfd::EventedFd;
+use crate::sys::unix::io::{set_nonblock, Io};
+use crate::sys::unix::uio::VecIo;
+use crate::{Interests, Registry, Token};
+
+use iovec::IoVec;
+use libc;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::Shutdown;
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use std::os::unix::net::{self, SocketAddr};
+use std::path::Path;
+
+pub struct UnixStream {
+    inner: net::UnixStream,
+}
+
+impl UnixStream {
+    pub fn connect(path: &Path) -> io::Result<UnixStream> {
+        let socket = super::socket(libc::SOCK_STREAM)?;
+        super::connect(socket.as_raw_fd(), path)?;
+        Ok(UnixStream {
+            inner: unsafe { net::UnixStream::from_raw_fd(socket.into_raw_fd()) },
+        })
+    }
+
+    pub fn pair() -> io::Result<(UnixStream, UnixStream)> {
+        let (a, b) = super::pair(libc::SOCK_STREAM)?;
+        unsafe {
+            Ok((
+                UnixStream::from_raw_fd(a.into_raw_fd()),
+                UnixStream::from_raw_fd(b.into_raw_fd()),
+            ))
+        }
+    }
+
+    pub fn from_std(stream: net::UnixStream) -> io::Result<UnixStream> {
+        set_nonblock(stream.as_raw_fd())?;
+        Ok(UnixStream { inner: stream })
+    }
+
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.inner.peer_addr()
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.inner.local_addr()
+    }
+
+    pub fn try_clone(&self) -> io::Result<UnixStream> {
+        self.inner.try_clone().map(|s| UnixStream { inner: s })
+    }
+
+    pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+        self.inner.shutdown(how)
+    }
+
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.inner.take_error()
+    }
+
+    pub fn readv(&self, bufs: &mut [&mut IoVec]) -> io::Result<usize> {
+        self.inner.readv(bufs)
+    }
+
+    pub fn writev(&self, bufs: &[&IoVec]) -> io::Result<usize> {
+        self.inner.writev(bufs)
+    }
+
+    pub fn send_with_fds(&self, buf: &[u8], fds: &[RawFd]) -> io::Result<usize> {
+        super::send_with_fds(self.as_raw_fd(), buf, fds)
+    }
+
+    pub fn recv_with_fds(&self, buf: &mut [u8], max_fds: usize) -> io::Result<(usize, Vec<Io>)> {
+        super::recv_with_fds(self.as_raw_fd(), buf, max_fds)
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn peer_cred(&self) -> io::Result<libc::ucred> {
+        super::peer_cred(self.as_raw_fd())
+    }
+}
+
+impl Read for &UnixStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        (&self.inner).read(buf)
+    }
+}
+
+impl Write for &UnixStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (&self.inner).write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        (&self.inner).flush()
+    }
+}
+
+impl Evented for UnixStream {
+    fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).register(registry, token, interests)
+    }
+
+    fn reregister(
+        &self,
+        registry: &Registry,
+        token: Token,
+        interests: Interests,
+    ) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).reregister(registry, token, interests)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).deregister(registry)
+    }
+}
+
+impl fmt::Debug for UnixStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.inner, f)
+    }
+}
+
+impl FromRawFd for UnixStream {
+    unsafe fn from_raw_fd(fd: RawFd) -> UnixStream {
+        UnixStream {
+            inner: net::UnixStream::from_raw_fd(fd),
+        }
+    }
+}
+
+impl IntoRawFd for UnixStream {
+    fn into_raw_fd(self) -> RawFd {
+        self.inner.into_raw_fd()
+    }
+}
+
+impl AsRawFd for UnixStream {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
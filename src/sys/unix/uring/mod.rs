@@ -0,0 +1,528 @@
+//! Minimal io_uring bindings.
+//!
+//! The kernel interface is described in the [`io_uring_setup(2)`] and
+//! [`io_uring_enter(2)`] manuals. The types below mirror the ones in
+//! `linux/io_uring.h`, which the `libc` crate doesn't provide.
+//!
+//! [`io_uring_setup(2)`]: http://man7.org/linux/man-pages/man2/io_uring_setup.2.html
+//! [`io_uring_enter(2)`]: http://man7.org/linux/man-pages/man2/io_uring_enter.2.html
+
+use crate::sys::unix::cvt;
+
+use libc::{self, c_int, c_uint, c_void};
+use std::fs::File;
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+use std::sync::atomic::{self, AtomicU32, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+use std::{fmt, io, mem, ptr};
+
+mod selector;
+
+pub use self::selector::{Selector, Waker};
+
+pub const IORING_OP_NOP: u8 = 0;
+pub const IORING_OP_READV: u8 = 1;
+pub const IORING_OP_FSYNC: u8 = 3;
+pub const IORING_OP_POLL_ADD: u8 = 6;
+pub const IORING_OP_POLL_REMOVE: u8 = 7;
+pub const IORING_OP_ACCEPT: u8 = 13;
+pub const IORING_OP_ASYNC_CANCEL: u8 = 14;
+pub const IORING_OP_READ: u8 = 22;
+pub const IORING_OP_WRITE: u8 = 23;
+
+/// `op_flags` of `IORING_OP_FSYNC`.
+pub const IORING_FSYNC_DATASYNC: u32 = 1 << 0;
+
+/// `len` flags of `IORING_OP_POLL_ADD`.
+pub const IORING_POLL_ADD_MULTI: u32 = 1 << 0;
+
+/// `flags` of `Cqe`, set if the request will produce more completions.
+pub const IORING_CQE_F_MORE: u32 = 1 << 1;
+
+const IORING_FEAT_SINGLE_MMAP: u32 = 1 << 0;
+const IORING_FEAT_NODROP: u32 = 1 << 1;
+const IORING_FEAT_EXT_ARG: u32 = 1 << 8;
+/// Added in the same release (5.13) as `IORING_POLL_ADD_MULTI`, which can't
+/// be detected otherwise.
+const IORING_FEAT_RSRC_TAGS: u32 = 1 << 10;
+const REQUIRED_FEATURES: u32 =
+    IORING_FEAT_SINGLE_MMAP | IORING_FEAT_NODROP | IORING_FEAT_EXT_ARG | IORING_FEAT_RSRC_TAGS;
+
+/// `flags` of the submission queue, set if completions overflowed.
+const IORING_SQ_CQ_OVERFLOW: u32 = 1 << 1;
+
+const IORING_ENTER_GETEVENTS: c_uint = 1 << 0;
+const IORING_ENTER_EXT_ARG: c_uint = 1 << 3;
+
+const IORING_OFF_SQ_RING: libc::off_t = 0;
+const IORING_OFF_SQES: libc::off_t = 0x1000_0000;
+
+#[repr(C)]
+#[derive(Default)]
+struct SqringOffsets {
+    head: u32,
+    tail: u32,
+    ring_mask: u32,
+    ring_entries: u32,
+    flags: u32,
+    dropped: u32,
+    array: u32,
+    resv1: u32,
+    user_addr: u64,
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct CqringOffsets {
+    head: u32,
+    tail: u32,
+    ring_mask: u32,
+    ring_entries: u32,
+    overflow: u32,
+    cqes: u32,
+    flags: u32,
+    resv1: u32,
+    user_addr: u64,
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct Params {
+    sq_entries: u32,
+    cq_entries: u32,
+    flags: u32,
+    sq_thread_cpu: u32,
+    sq_thread_idle: u32,
+    features: u32,
+    wq_fd: u32,
+    resv: [u32; 3],
+    sq_off: SqringOffsets,
+    cq_off: CqringOffsets,
+}
+
+#[repr(C)]
+struct GeteventsArg {
+    sigmask: u64,
+    sigmask_sz: u32,
+    pad: u32,
+    ts: u64,
+}
+
+/// `struct __kernel_timespec`, which is 64 bit on all architectures.
+#[repr(C)]
+struct KernelTimespec {
+    tv_sec: i64,
+    tv_nsec: i64,
+}
+
+/// Submission queue entry, `struct io_uring_sqe`.
+///
+/// Only the fields used by mio are named, the unions are named after the
+/// member mio uses.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Sqe {
+    pub opcode: u8,
+    pub flags: u8,
+    pub ioprio: u16,
+    pub fd: i32,
+    pub off: u64,
+    pub addr: u64,
+    pub len: u32,
+    pub op_flags: u32,
+    pub user_data: u64,
+    pub buf_index: u16,
+    pub personality: u16,
+    pub splice_fd_in: i32,
+    pub pad: [u64; 2],
+}
+
+impl Sqe {
+    pub fn new(opcode: u8, fd: RawFd, user_data: u64) -> Sqe {
+        Sqe {
+            opcode,
+            flags: 0,
+            ioprio: 0,
+            fd,
+            off: 0,
+            addr: 0,
+            len: 0,
+            op_flags: 0,
+            user_data,
+            buf_index: 0,
+            personality: 0,
+            splice_fd_in: 0,
+            pad: [0; 2],
+        }
+    }
+
+    /// `IORING_OP_POLL_ADD` for `events` (`POLLIN` etc.), `flags` being the
+    /// `IORING_POLL_*` flags.
+    pub fn poll_add(fd: RawFd, events: u32, flags: u32, user_data: u64) -> Sqe {
+        let mut sqe = Sqe::new(IORING_OP_POLL_ADD, fd, user_data);
+        sqe.len = flags;
+        // `poll32_events` is stored with swapped half words on big endian.
+        sqe.op_flags = if cfg!(target_endian = "big") {
+            events.rotate_left(16)
+        } else {
+            events
+        };
+        sqe
+    }
+
+    /// `IORING_OP_POLL_REMOVE` of the poll request with `target` as user data.
+    pub fn poll_remove(target: u64, user_data: u64) -> Sqe {
+        let mut sqe = Sqe::new(IORING_OP_POLL_REMOVE, -1, user_data);
+        sqe.addr = target;
+        sqe
+    }
+
+    /// `IORING_OP_READ`, `IORING_OP_WRITE` or `IORING_OP_READV` of `len` bytes
+    /// (or iovecs) at `addr`. An `offset` of `u64::MAX` uses the file
+    /// position.
+    pub fn rw(opcode: u8, fd: RawFd, addr: u64, len: u32, offset: u64, user_data: u64) -> Sqe {
+        let mut sqe = Sqe::new(opcode, fd, user_data);
+        sqe.addr = addr;
+        sqe.len = len;
+        sqe.off = offset;
+        sqe
+    }
+
+    /// `IORING_OP_FSYNC`, `flags` being the `IORING_FSYNC_*` flags.
+    pub fn fsync(fd: RawFd, flags: u32, user_data: u64) -> Sqe {
+        let mut sqe = Sqe::new(IORING_OP_FSYNC, fd, user_data);
+        sqe.op_flags = flags;
+        sqe
+    }
+
+    /// `IORING_OP_ACCEPT`, `flags` being the `accept4(2)` flags.
+    pub fn accept(fd: RawFd, flags: c_int, user_data: u64) -> Sqe {
+        let mut sqe = Sqe::new(IORING_OP_ACCEPT, fd, user_data);
+        sqe.op_flags = flags as u32;
+        sqe
+    }
+
+    /// `IORING_OP_ASYNC_CANCEL` of the request with `target` as user data.
+    pub fn async_cancel(target: u64, user_data: u64) -> Sqe {
+        let mut sqe = Sqe::new(IORING_OP_ASYNC_CANCEL, -1, user_data);
+        sqe.addr = target;
+        sqe
+    }
+}
+
+/// Completion queue entry, `struct io_uring_cqe`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct Cqe {
+    pub user_data: u64,
+    pub res: i32,
+    pub flags: u32,
+}
+
+/// Memory mapped region of the ring, unmapped when dropped.
+struct Mmap {
+    ptr: *mut c_void,
+    len: usize,
+}
+
+impl Mmap {
+    fn new(fd: RawFd, offset: libc::off_t, len: usize) -> io::Result<Mmap> {
+        let ptr = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED | libc::MAP_POPULATE,
+                fd,
+                offset,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(Mmap { ptr, len })
+        }
+    }
+
+    /// Returns a pointer `offset` bytes into the region.
+    fn offset<T>(&self, offset: u32) -> *mut T {
+        unsafe { (self.ptr as *mut u8).add(offset as usize) as *mut T }
+    }
+}
+
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = libc::munmap(self.ptr, self.len);
+        }
+    }
+}
+
+/// Submission queue, shared with the kernel.
+pub struct SubmissionQueue {
+    head: *const AtomicU32,
+    tail: *const AtomicU32,
+    flags: *const AtomicU32,
+    ring_mask: u32,
+    ring_entries: u32,
+    array: *mut u32,
+    sqes: *mut Sqe,
+    _sqes_mmap: Mmap,
+}
+
+impl SubmissionQueue {
+    /// Add `sqe` to the queue, returns `false` if the queue is full.
+    pub fn push(&mut self, sqe: &Sqe) -> bool {
+        unsafe {
+            let head = (*self.head).load(Ordering::Acquire);
+            let tail = (*self.tail).load(Ordering::Relaxed);
+            if tail.wrapping_sub(head) == self.ring_entries {
+                return false;
+            }
+            let index = tail & self.ring_mask;
+            ptr::write(self.sqes.add(index as usize), *sqe);
+            ptr::write(self.array.add(index as usize), index);
+            (*self.tail).store(tail.wrapping_add(1), Ordering::Release);
+        }
+        true
+    }
+
+    /// Turn the entries with `user_data` not yet consumed by the kernel into
+    /// `IORING_OP_NOP` requests with 0 as user data, returns `true` if any
+    /// were found.
+    pub fn cancel(&mut self, user_data: u64) -> bool {
+        let mut found = false;
+        unsafe {
+            let head = (*self.head).load(Ordering::Acquire);
+            let tail = (*self.tail).load(Ordering::Relaxed);
+            let mut pos = head;
+            while pos != tail {
+                let index = ptr::read(self.array.add((pos & self.ring_mask) as usize));
+                let sqe = self.sqes.add(index as usize);
+                if (*sqe).user_data == user_data {
+                    ptr::write(sqe, Sqe::new(IORING_OP_NOP, -1, 0));
+                    found = true;
+                }
+                pos = pos.wrapping_add(1);
+            }
+        }
+        found
+    }
+
+    /// Returns the number of entries not yet consumed by the kernel.
+    pub fn pending(&self) -> u32 {
+        unsafe {
+            let head = (*self.head).load(Ordering::Acquire);
+            let tail = (*self.tail).load(Ordering::Relaxed);
+            tail.wrapping_sub(head)
+        }
+    }
+
+    /// Returns `true` if completions didn't fit in the completion queue. The
+    /// kernel keeps them until they're flushed by `Ring::submit_and_wait`.
+    pub fn cq_overflow(&self) -> bool {
+        unsafe { (*self.flags).load(Ordering::Acquire) & IORING_SQ_CQ_OVERFLOW != 0 }
+    }
+}
+
+/// Completion queue, shared with the kernel.
+pub struct CompletionQueue {
+    head: *const AtomicU32,
+    tail: *const AtomicU32,
+    ring_mask: u32,
+    cqes: *const Cqe,
+}
+
+impl CompletionQueue {
+    /// Remove the next completion from the queue, if any.
+    pub fn pop(&mut self) -> Option<Cqe> {
+        unsafe {
+            let head = (*self.head).load(Ordering::Relaxed);
+            let tail = (*self.tail).load(Ordering::Acquire);
+            if head == tail {
+                return None;
+            }
+            let cqe = ptr::read(self.cqes.add((head & self.ring_mask) as usize));
+            (*self.head).store(head.wrapping_add(1), Ordering::Release);
+            Some(cqe)
+        }
+    }
+}
+
+/// An io_uring instance.
+pub struct Ring {
+    fd: File,
+    sq: Mutex<SubmissionQueue>,
+    cq: Mutex<CompletionQueue>,
+    // Kept alive for the pointers in the queues, unmapped after them.
+    _ring_mmap: Mmap,
+}
+
+impl Ring {
+    /// Create a new ring with room for at least `entries` submissions.
+    ///
+    /// Returns an error if the kernel doesn't support the features mio
+    /// requires (Linux 5.13+).
+    pub fn new(entries: u32) -> io::Result<Ring> {
+        let mut params = Params::default();
+        let fd = unsafe {
+            cvt(libc::syscall(
+                libc::SYS_io_uring_setup,
+                entries as c_uint,
+                &mut params as *mut Params,
+            ) as c_int)?
+        };
+        // The ring is always created with the close-on-exec flag set.
+        let fd = unsafe { File::from_raw_fd(fd) };
+        if params.features & REQUIRED_FEATURES != REQUIRED_FEATURES {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "io_uring doesn't support the required features (Linux 5.13+)",
+            ));
+        }
+
+        // `IORING_FEAT_SINGLE_MMAP`: both rings share a single mapping.
+        let sq_len = params.sq_off.array as usize + params.sq_entries as usize * 4;
+        let cq_len = params.cq_off.cqes as usize + params.cq_entries as usize * 16;
+        let ring_mmap = Mmap::new(
+            fd.as_raw_fd(),
+            IORING_OFF_SQ_RING,
+            std::cmp::max(sq_len, cq_len),
+        )?;
+        let sqes_mmap = Mmap::new(
+            fd.as_raw_fd(),
+            IORING_OFF_SQES,
+            params.sq_entries as usize * mem::size_of::<Sqe>(),
+        )?;
+
+        let sq = unsafe {
+            SubmissionQueue {
+                head: ring_mmap.offset(params.sq_off.head),
+                tail: ring_mmap.offset(params.sq_off.tail),
+                flags: ring_mmap.offset(params.sq_off.flags),
+                ring_mask: *ring_mmap.offset::<u32>(params.sq_off.ring_mask),
+                ring_entries: *ring_mmap.offset::<u32>(params.sq_off.ring_entries),
+                array: ring_mmap.offset(params.sq_off.array),
+                sqes: sqes_mmap.ptr as *mut Sqe,
+                _sqes_mmap: sqes_mmap,
+            }
+        };
+        let cq = unsafe {
+            CompletionQueue {
+                head: ring_mmap.offset(params.cq_off.head),
+                tail: ring_mmap.offset(params.cq_off.tail),
+                ring_mask: *ring_mmap.offset::<u32>(params.cq_off.ring_mask),
+                cqes: ring_mmap.offset(params.cq_off.cqes),
+            }
+        };
+        Ok(Ring {
+            fd,
+            sq: Mutex::new(sq),
+            cq: Mutex::new(cq),
+            _ring_mmap: ring_mmap,
+        })
+    }
+
+    /// Lock the submission queue.
+    pub fn sq(&self) -> MutexGuard<'_, SubmissionQueue> {
+        self.sq.lock().unwrap()
+    }
+
+    /// Lock the completion queue.
+    pub fn cq(&self) -> MutexGuard<'_, CompletionQueue> {
+        self.cq.lock().unwrap()
+    }
+
+    /// Submit `to_submit` entries, without waiting for completions.
+    pub fn submit(&self, to_submit: u32) -> io::Result<()> {
+        self.enter(to_submit, 0, 0, ptr::null())
+    }
+
+    /// Submit `to_submit` entries and wait for at least `min_complete`
+    /// completions, or until `timeout` expires.
+    ///
+    /// An expired timeout is not an error.
+    pub fn submit_and_wait(
+        &self,
+        to_submit: u32,
+        min_complete: u32,
+        timeout: Option<Duration>,
+    ) -> io::Result<()> {
+        let ts = timeout.map(|timeout| KernelTimespec {
+            tv_sec: timespec_secs(timeout),
+            tv_nsec: i64::from(timeout.subsec_nanos()),
+        });
+        let arg = GeteventsArg {
+            sigmask: 0,
+            sigmask_sz: 0,
+            pad: 0,
+            ts: ts
+                .as_ref()
+                .map(|ts| ts as *const KernelTimespec as u64)
+                .unwrap_or(0),
+        };
+        let flags = IORING_ENTER_GETEVENTS | IORING_ENTER_EXT_ARG;
+        match self.enter(to_submit, min_complete, flags, &arg) {
+            Err(ref err) if err.raw_os_error() == Some(libc::ETIME) => Ok(()),
+            res => res,
+        }
+    }
+
+    fn enter(
+        &self,
+        to_submit: u32,
+        min_complete: u32,
+        flags: c_uint,
+        arg: *const GeteventsArg,
+    ) -> io::Result<()> {
+        // Make sure the kernel sees the entries, the tail is stored with
+        // `Release` ordering already.
+        atomic::fence(Ordering::SeqCst);
+        let argsz = if arg.is_null() {
+            0
+        } else {
+            mem::size_of::<GeteventsArg>()
+        };
+        unsafe {
+            cvt(libc::syscall(
+                libc::SYS_io_uring_enter,
+                self.fd.as_raw_fd(),
+                to_submit as c_uint,
+                min_complete as c_uint,
+                flags,
+                arg,
+                argsz,
+            ) as c_int)
+            .map(|_| ())
+        }
+    }
+}
+
+/// Returns the whole seconds of `timeout`, saturating at `i64::MAX`.
+fn timespec_secs(timeout: Duration) -> i64 {
+    std::cmp::min(timeout.as_secs(), i64::max_value() as u64) as i64
+}
+
+impl AsRawFd for Ring {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
+
+impl fmt::Debug for Ring {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ring")
+            .field("fd", &self.fd.as_raw_fd())
+            .finish()
+    }
+}
+
+// The raw pointers point into memory shared with the kernel, which may be
+// accessed from any thread.
+unsafe impl Send for SubmissionQueue {}
+unsafe impl Sync for SubmissionQueue {}
+unsafe impl Send for CompletionQueue {}
+unsafe impl Sync for CompletionQueue {}
+unsafe impl Send for Mmap {}
+unsafe impl Sync for Mmap {}
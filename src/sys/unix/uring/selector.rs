@@ -0,0 +1,410 @@
+use crate::selector::{self, Wake};
+use crate::sys::unix::uring::{
+    Ring, Sqe, SubmissionQueue, IORING_CQE_F_MORE, IORING_OP_NOP, IORING_POLL_ADD_MULTI,
+};
+use crate::sys::Events;
+use crate::{Interests, Token, Trigger};
+
+use libc;
+use log::debug;
+use std::collections::HashMap;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use std::{fmt, io};
+
+/// Number of submission queue entries. Registration changes are batched until
+/// the next call to `select`, unless the queue fills up before that.
+const ENTRIES: u32 = 256;
+
+/// Selector backed by io_uring, using `IORING_OP_POLL_ADD` requests in place
+/// of `epoll_ctl` and `epoll_wait`.
+///
+/// Edge-triggered registrations use multishot poll requests, level-triggered
+/// and oneshot registrations use single poll requests. Level-triggered
+/// requests are re-armed after each completion, which returns another
+/// completion in the next call to `select` if the file descriptor is still
+/// ready.
+///
+/// Registration changes are queued in the submission queue and submitted by
+/// the next call to `select`, in a single system call. If `select` is blocked
+/// while a change is made, the change is submitted right away.
+///
+/// Unlike epoll, a poll request keeps its file open. Handles therefore remove
+/// their registration before they're closed, see `Hook::release`.
+pub struct Selector {
+    inner: Arc<Inner>,
+}
+
+/// State shared with the `Waker`s.
+struct Inner {
+    ring: Ring,
+    state: Mutex<State>,
+    /// Set while `select` waits for completions.
+    waiting: AtomicBool,
+}
+
+struct State {
+    registrations: HashMap<RawFd, Registration>,
+    /// Used to create a unique user data for each poll request, so that
+    /// completions of removed requests can be recognised.
+    generation: u32,
+    /// Tokens of the `Waker`s, keyed by the user data of their requests.
+    wakers: HashMap<u64, Token>,
+    next_waker: u64,
+}
+
+struct Registration {
+    /// User data of the active poll request.
+    user_data: u64,
+    token: Token,
+    events: u32,
+    trigger: Trigger,
+}
+
+impl Selector {
+    pub fn new() -> io::Result<Selector> {
+        let ring = Ring::new(ENTRIES)?;
+
+        Ok(Selector {
+            inner: Arc::new(Inner {
+                ring,
+                state: Mutex::new(State {
+                    registrations: HashMap::new(),
+                    generation: 0,
+                    wakers: HashMap::new(),
+                    next_waker: 1,
+                }),
+                waiting: AtomicBool::new(false),
+            }),
+        })
+    }
+
+    /// Wait for events from the OS
+    pub fn select(&self, evts: &mut Events, timeout: Option<Duration>) -> io::Result<()> {
+        evts.clear();
+        let start = Instant::now();
+        self.wait(timeout)?;
+        self.reap(evts)?;
+        // Completions of removed requests are ignored, but still wake us up,
+        // in which case we wait again for the remainder of the timeout.
+        while evts.is_empty() {
+            let remaining = match timeout {
+                Some(timeout) if start.elapsed() >= timeout => break,
+                Some(timeout) => Some(timeout - start.elapsed()),
+                None => None,
+            };
+            self.wait(remaining)?;
+            self.reap(evts)?;
+        }
+        Ok(())
+    }
+
+    /// Submit the queued changes and wait for a completion, or until
+    /// `timeout` expires.
+    fn wait(&self, timeout: Option<Duration>) -> io::Result<()> {
+        let min_complete = if timeout == Some(Duration::from_millis(0)) {
+            0
+        } else {
+            1
+        };
+        let to_submit = {
+            // Changes made after this are submitted by `submit`.
+            let _state = self.inner.state.lock().unwrap();
+            self.inner.waiting.store(true, Ordering::SeqCst);
+            self.inner.ring.sq().pending()
+        };
+        let res = self
+            .inner
+            .ring
+            .submit_and_wait(to_submit, min_complete, timeout);
+        self.inner.waiting.store(false, Ordering::SeqCst);
+        match res {
+            // The completion queue overflowed, the backlog is flushed once
+            // we've made room.
+            Err(ref err)
+                if err.raw_os_error() == Some(libc::EBUSY)
+                    || err.raw_os_error() == Some(libc::EAGAIN) =>
+            {
+                Ok(())
+            }
+            res => res,
+        }
+    }
+
+    /// Move completions into `evts`, as long as there is room for them.
+    fn reap(&self, evts: &mut Events) -> io::Result<()> {
+        let mut state = self.inner.state.lock().unwrap();
+        let mut cq = self.inner.ring.cq();
+        while evts.len() < evts.capacity() {
+            let cqe = match cq.pop() {
+                Some(cqe) => cqe,
+                None => break,
+            };
+
+            if let Some(&token) = state.wakers.get(&cqe.user_data) {
+                evts.push(token, Interests::READABLE);
+                continue;
+            }
+
+            let fd = fd_from_user_data(cqe.user_data);
+            let registration = match state.registrations.get(&fd) {
+                Some(registration) if registration.user_data == cqe.user_data => registration,
+                // Completion of a removed request, or of `poll_remove`.
+                _ => continue,
+            };
+            if cqe.res < 0 {
+                // The request failed, e.g. `EBADF`, leaving the file
+                // descriptor without a request, as if it was deregistered.
+                debug!(
+                    "io_uring poll request for fd={} failed: {}",
+                    fd,
+                    io::Error::from_raw_os_error(-cqe.res)
+                );
+                state.registrations.remove(&fd);
+                continue;
+            }
+
+            let rearm = match registration.trigger {
+                // The kernel stops a multishot request if it can't post more
+                // completions.
+                Trigger::Edge => cqe.flags & IORING_CQE_F_MORE == 0,
+                Trigger::Level => true,
+                Trigger::Oneshot => false,
+            };
+            if rearm {
+                self.inner
+                    .push(&mut self.inner.ring.sq(), &registration.poll_add(fd))?;
+            }
+
+            evts.push_raw(registration.token, cqe.res as u32);
+        }
+        Ok(())
+    }
+
+    /// Register event interests for the given IO handle with the OS
+    pub fn register(
+        &self,
+        fd: RawFd,
+        token: Token,
+        interests: Interests,
+        trigger: Trigger,
+    ) -> io::Result<()> {
+        let mut state = self.inner.state.lock().unwrap();
+        if state.registrations.contains_key(&fd) {
+            return Err(io::Error::from_raw_os_error(libc::EEXIST));
+        }
+
+        let registration = Registration {
+            user_data: state.next_user_data(fd),
+            token,
+            events: interests_to_poll(interests),
+            trigger,
+        };
+        self.inner.submit(&[registration.poll_add(fd)])?;
+        state.registrations.insert(fd, registration);
+        Ok(())
+    }
+
+    /// Register event interests for the given IO handle with the OS
+    pub fn reregister(
+        &self,
+        fd: RawFd,
+        token: Token,
+        interests: Interests,
+        trigger: Trigger,
+    ) -> io::Result<()> {
+        let mut state = self.inner.state.lock().unwrap();
+        let user_data = state.next_user_data(fd);
+        let registration = match state.registrations.get_mut(&fd) {
+            Some(registration) => registration,
+            None => return Err(io::Error::from_raw_os_error(libc::ENOENT)),
+        };
+
+        // Oneshot requests may have completed already, in which case removing
+        // them fails, which we ignore.
+        let remove = Sqe::poll_remove(registration.user_data, 0);
+        registration.user_data = user_data;
+        registration.token = token;
+        registration.events = interests_to_poll(interests);
+        registration.trigger = trigger;
+        self.inner.submit(&[remove, registration.poll_add(fd)])
+    }
+
+    /// Deregister event interests for the given IO handle with the OS
+    pub fn deregister(&self, fd: RawFd) -> io::Result<()> {
+        let mut state = self.inner.state.lock().unwrap();
+        match state.registrations.remove(&fd) {
+            Some(registration) => self
+                .inner
+                .submit(&[Sqe::poll_remove(registration.user_data, 0)]),
+            None => Err(io::Error::from_raw_os_error(libc::ENOENT)),
+        }
+    }
+
+    /// Setup a `Waker`, see `Waker::new`.
+    fn setup_waker(&self, token: Token) -> u64 {
+        let mut state = self.inner.state.lock().unwrap();
+        let user_data = state.next_waker;
+        state.next_waker += 1;
+        state.wakers.insert(user_data, token);
+        user_data
+    }
+}
+
+impl Inner {
+    /// Queue `sqes`, submitting them right away if `select` is waiting.
+    ///
+    /// The caller must hold the state lock, see `Selector::select`.
+    fn submit(&self, sqes: &[Sqe]) -> io::Result<()> {
+        let mut sq = self.ring.sq();
+        for sqe in sqes {
+            self.push(&mut sq, sqe)?;
+        }
+        if self.waiting.load(Ordering::SeqCst) {
+            self.ring.submit(sq.pending())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Add `sqe` to the submission queue, submitting the queue if it's full.
+    fn push(&self, sq: &mut SubmissionQueue, sqe: &Sqe) -> io::Result<()> {
+        if !sq.push(sqe) {
+            self.ring.submit(sq.pending())?;
+            if !sq.push(sqe) {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "io_uring submission queue is full",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl selector::Hook for Selector {
+    /// Remove the registration of `fd`, called by handles before they close
+    /// it.
+    ///
+    /// epoll removes closed file descriptors itself, but a poll request keeps
+    /// the file open until it's removed. The removal is submitted right away,
+    /// so that closing `fd` closes the file. A poll request that wasn't
+    /// submitted yet is dropped from the submission queue instead, removing it
+    /// in the same submission doesn't cancel it.
+    fn release(&self, fd: RawFd) {
+        let mut state = self.inner.state.lock().unwrap();
+        if let Some(registration) = state.registrations.remove(&fd) {
+            let mut sq = self.inner.ring.sq();
+            let res = if sq.cancel(registration.user_data) {
+                Ok(())
+            } else {
+                self.inner
+                    .push(&mut sq, &Sqe::poll_remove(registration.user_data, 0))
+            }
+            .and_then(|()| self.inner.ring.submit(sq.pending()));
+            if let Err(err) = res {
+                debug!("error removing io_uring poll request: {}", err);
+            }
+        }
+    }
+}
+
+impl State {
+    fn next_user_data(&mut self, fd: RawFd) -> u64 {
+        // The generation is never 0, so that the user data of poll requests
+        // doesn't overlap with those of `Waker`s and of requests
+        // whose completions are ignored.
+        self.generation = self.generation.wrapping_add(1);
+        if self.generation == 0 {
+            self.generation = 1;
+        }
+        u64::from(self.generation) << 32 | u64::from(fd as u32)
+    }
+}
+
+impl Registration {
+    fn poll_add(&self, fd: RawFd) -> Sqe {
+        let flags = match self.trigger {
+            Trigger::Edge => IORING_POLL_ADD_MULTI,
+            Trigger::Level | Trigger::Oneshot => 0,
+        };
+        Sqe::poll_add(fd, self.events, flags, self.user_data)
+    }
+}
+
+fn fd_from_user_data(user_data: u64) -> RawFd {
+    user_data as u32 as RawFd
+}
+
+fn interests_to_poll(interests: Interests) -> u32 {
+    let mut kind = 0;
+
+    if interests.is_readable() {
+        kind |= libc::POLLIN;
+    }
+
+    if interests.is_writable() {
+        kind |= libc::POLLOUT;
+    }
+
+    kind as u32
+}
+
+impl AsRawFd for Selector {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.ring.as_raw_fd()
+    }
+}
+
+impl fmt::Debug for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Selector")
+            .field("ring", &self.inner.ring)
+            .finish()
+    }
+}
+
+/// Waker backed by `IORING_OP_NOP` requests.
+///
+/// Waking submits a no-op request, its completion wakes up `select`, which
+/// returns it as a readable event.
+pub struct Waker {
+    inner: Arc<Inner>,
+    user_data: u64,
+}
+
+impl Waker {
+    pub fn new(selector: &Selector, token: Token) -> io::Result<Waker> {
+        Ok(Waker {
+            inner: selector.inner.clone(),
+            user_data: selector.setup_waker(token),
+        })
+    }
+}
+
+impl Wake for Waker {
+    fn wake(&self) -> io::Result<()> {
+        let _state = self.inner.state.lock().unwrap();
+        let mut sq = self.inner.ring.sq();
+        self.inner
+            .push(&mut sq, &Sqe::new(IORING_OP_NOP, -1, self.user_data))?;
+        self.inner.ring.submit(sq.pending())
+    }
+}
+
+impl Drop for Waker {
+    fn drop(&mut self) {
+        let mut state = self.inner.state.lock().unwrap();
+        state.wakers.remove(&self.user_data);
+    }
+}
+
+impl fmt::Debug for Waker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Waker")
+            .field("user_data", &self.user_data)
+            .finish()
+    }
+}
@@ -5,8 +5,9 @@ mod eventfd {
     use std::mem;
     use std::os::unix::io::FromRawFd;
 
-    use crate::sys::Selector;
-    use crate::{Interests, Token};
+    use crate::selector::Wake;
+    use crate::sys::unix::epoll::Selector;
+    use crate::{Interests, Token, Trigger};
 
     /// Waker backed by `eventfd`.
     ///
@@ -26,13 +27,26 @@ mod eventfd {
                 return Err(io::Error::last_os_error());
             }
 
-            selector.register(fd, token, Interests::READABLE)?;
-            Ok(Waker {
-                fd: unsafe { File::from_raw_fd(fd) },
-            })
+            let file = unsafe { File::from_raw_fd(fd) };
+            selector.register(fd, token, Interests::READABLE, Trigger::Edge)?;
+            Ok(Waker { fd: file })
+        }
+
+        /// Reset the eventfd object, only need to call this if `wake` fails.
+        fn reset(&self) -> io::Result<()> {
+            let mut buf: [u8; 8] = [0; 8];
+            match (&self.fd).read(&mut buf) {
+                Ok(_) => Ok(()),
+                // If the `Waker` hasn't been awoken yet this will return a
+                // `WouldBlock` error which we can safely ignore.
+                Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => Ok(()),
+                Err(err) => Err(err),
+            }
         }
+    }
 
-        pub fn wake(&self) -> io::Result<()> {
+    impl Wake for Waker {
+        fn wake(&self) -> io::Result<()> {
             let buf: [u8; 8] = unsafe { mem::transmute(1u64) };
             match (&self.fd).write(&buf) {
                 Ok(_) => Ok(()),
@@ -45,18 +59,6 @@ mod eventfd {
                 Err(err) => Err(err),
             }
         }
-
-        /// Reset the eventfd object, only need to call this if `wake` fails.
-        fn reset(&self) -> io::Result<()> {
-            let mut buf: [u8; 8] = [0; 8];
-            match (&self.fd).read(&mut buf) {
-                Ok(_) => Ok(()),
-                // If the `Waker` hasn't been awoken yet this will return a
-                // `WouldBlock` error which we can safely ignore.
-                Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => Ok(()),
-                Err(err) => Err(err),
-            }
-        }
     }
 }
 
@@ -67,7 +69,8 @@ pub use self::eventfd::Waker;
 mod kqueue {
     use std::io;
 
-    use crate::sys::Selector;
+    use crate::selector::Wake;
+    use crate::sys::unix::kqueue::Selector;
     use crate::Token;
 
     /// Waker backed by kqueue user space notifications (`EVFILT_USER`).
@@ -90,8 +93,10 @@ mod kqueue {
                     .map(|()| Waker { selector, token })
             })
         }
+    }
 
-        pub fn wake(&self) -> io::Result<()> {
+    impl Wake for Waker {
+        fn wake(&self) -> io::Result<()> {
             self.selector.wake(self.token)
         }
     }
@@ -111,8 +116,13 @@ mod pipe {
     use std::io::{self, Read, Write};
     use std::os::unix::io::AsRawFd;
 
-    use crate::sys::{pipe, Io, Selector};
-    use crate::{Interests, Token};
+    use crate::selector::Wake;
+    #[cfg(target_os = "solaris")]
+    use crate::sys::unix::epoll::Selector;
+    #[cfg(not(target_os = "solaris"))]
+    use crate::sys::unix::kqueue::Selector;
+    use crate::sys::{pipe, Io};
+    use crate::{Interests, Token, Trigger};
 
     /// Waker backed by a unix pipe.
     ///
@@ -126,12 +136,31 @@ mod pipe {
 
     impl Waker {
         pub fn new(selector: &Selector, token: Token) -> io::Result<Waker> {
-            let (sender, receiver) = pipe()?;
-            selector.register(receiver.as_raw_fd(), token, Interests::READABLE)?;
+            let (receiver, sender) = pipe()?;
+            selector.register(
+                receiver.as_raw_fd(),
+                token,
+                Interests::READABLE,
+                Trigger::Edge,
+            )?;
             Ok(Waker { sender, receiver })
         }
 
-        pub fn wake(&self) -> io::Result<()> {
+        /// Empty the pipe's buffer, only need to call this if `wake` fails.
+        /// This ignores any errors.
+        fn empty(&self) {
+            let mut buf = [0; 4096];
+            loop {
+                match (&self.receiver).read(&mut buf) {
+                    Ok(n) if n > 0 => continue,
+                    _ => return,
+                }
+            }
+        }
+    }
+
+    impl Wake for Waker {
+        fn wake(&self) -> io::Result<()> {
             match (&self.sender).write(&[1]) {
                 Ok(_) => Ok(()),
                 Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {
@@ -144,18 +173,6 @@ mod pipe {
                 Err(err) => Err(err),
             }
         }
-
-        /// Empty the pipe's buffer, only need to call this if `wake` fails.
-        /// This ignores any errors.
-        fn empty(&self) {
-            let mut buf = [0; 4096];
-            loop {
-                match (&self.receiver).read(&mut buf) {
-                    Ok(n) if n > 0 => continue,
-                    _ => return,
-                }
-            }
-        }
     }
 }
 
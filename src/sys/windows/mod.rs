@@ -149,11 +149,13 @@ mod lazycell;
 mod poll_opt;
 mod queue;
 mod ready;
+mod registration;
 mod tcp;
 mod udp;
 mod waker;
 
 pub use self::event::{Event, SysEvent};
+pub use self::registration::{UserRegistration, UserSetReadiness};
 pub use self::selector::{Binding, Events, Overlapped, Selector};
 pub use self::tcp::{TcpListener, TcpStream};
 pub use self::udp::UdpSocket;
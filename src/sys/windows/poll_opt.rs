@@ -1,3 +1,5 @@
+use crate::Trigger;
+
 use std::{fmt, ops};
 
 /// Options supplied when registering an `Evented` handle with `Poll`
@@ -151,6 +153,16 @@ impl fmt::Debug for PollOpt {
     }
 }
 
+impl From<Trigger> for PollOpt {
+    fn from(src: Trigger) -> PollOpt {
+        match src {
+            Trigger::Edge => PollOpt::edge(),
+            Trigger::Level => PollOpt::level(),
+            Trigger::Oneshot => PollOpt::edge() | PollOpt::oneshot(),
+        }
+    }
+}
+
 impl From<PollOpt> for usize {
     fn from(src: PollOpt) -> usize {
         src.0
@@ -203,6 +203,33 @@ impl Registration {
     }
 }
 
+impl Registration {
+    /// Create a new `Registration` that isn't associated with a
+    /// `ReadinessQueue` yet, used by `event::Registration`. The queue is set
+    /// when it's first registered.
+    pub(crate) fn new_unassociated() -> (Registration, SetReadiness) {
+        // The new node will have `ref_count` set to 2: one SetReadiness and
+        // one Registration. `update` adds one for the Poll handle.
+        let node = Box::into_raw(Box::new(ReadinessNode::new(
+            ptr::null_mut(),
+            Token(0),
+            Ready::EMPTY,
+            PollOpt::empty(),
+            2,
+        )));
+
+        let registration = Registration {
+            inner: RegistrationInner { node },
+        };
+
+        let set_readiness = SetReadiness {
+            inner: RegistrationInner { node },
+        };
+
+        (registration, set_readiness)
+    }
+}
+
 impl Evented for Registration {
     fn register(
         &self,
@@ -214,7 +241,7 @@ impl Evented for Registration {
             &poll::selector(registry).readiness_queue,
             token,
             Ready::from_interests(interests),
-            PollOpt::edge(),
+            PollOpt::from(poll::trigger(registry)),
         )
     }
 
@@ -228,7 +255,7 @@ impl Evented for Registration {
             &poll::selector(registry).readiness_queue,
             token,
             Ready::from_interests(interests),
-            PollOpt::edge(),
+            PollOpt::from(poll::trigger(registry)),
         )
     }
 
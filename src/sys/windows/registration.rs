@@ -0,0 +1,79 @@
+use crate::event::Evented;
+use crate::sys::windows::queue;
+use crate::sys::windows::Ready;
+use crate::{Interests, Registry, Token};
+
+use std::{fmt, io};
+
+/// `event::Registration` backed by the readiness queue.
+pub struct UserRegistration {
+    inner: queue::Registration,
+}
+
+/// `event::SetReadiness` backed by the readiness queue.
+#[derive(Clone)]
+pub struct UserSetReadiness {
+    inner: queue::SetReadiness,
+}
+
+impl UserRegistration {
+    pub fn new() -> (UserRegistration, UserSetReadiness) {
+        let (registration, set_readiness) = queue::Registration::new_unassociated();
+        (
+            UserRegistration {
+                inner: registration,
+            },
+            UserSetReadiness {
+                inner: set_readiness,
+            },
+        )
+    }
+}
+
+impl Evented for UserRegistration {
+    fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        self.inner.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &self,
+        registry: &Registry,
+        token: Token,
+        interests: Interests,
+    ) -> io::Result<()> {
+        self.inner.reregister(registry, token, interests)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.inner.deregister(registry)
+    }
+}
+
+impl fmt::Debug for UserRegistration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.inner, f)
+    }
+}
+
+impl UserSetReadiness {
+    pub fn readiness(&self) -> Option<Interests> {
+        let readiness = self.inner.readiness();
+        match (readiness.is_readable(), readiness.is_writable()) {
+            (true, true) => Some(Interests::READABLE | Interests::WRITABLE),
+            (true, false) => Some(Interests::READABLE),
+            (false, true) => Some(Interests::WRITABLE),
+            (false, false) => None,
+        }
+    }
+
+    pub fn set_readiness(&self, readiness: Option<Interests>) -> io::Result<()> {
+        let readiness = readiness.map_or(Ready::EMPTY, Ready::from_interests);
+        self.inner.set_readiness(readiness)
+    }
+}
+
+impl fmt::Debug for UserSetReadiness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.inner, f)
+    }
+}
@@ -425,7 +425,7 @@ impl ReadyBinding {
             &poll::selector(registry).readiness_queue,
             token,
             events,
-            PollOpt::edge(),
+            PollOpt::from(poll::trigger(registry)),
         );
         self.readiness = Some(s);
         *registration.lock().unwrap() = Some(r);
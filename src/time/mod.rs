@@ -0,0 +1,24 @@
+//! Timers.
+//!
+//! The only timing facility offered by [`Poll`] itself is the `timeout`
+//! argument of [`Poll::poll`]. The types in this module build on top of that:
+//!
+//! * [`Timer`] delivers a timeout as a regular readiness event, with its own
+//!   [`Token`] (Linux and Android only).
+//! * [`Wheel`] tracks a large number of timeouts in userspace, determining the
+//!   `timeout` argument of [`Poll::poll`] and returning the tokens of the
+//!   timeouts that expired after polling.
+//!
+//! [`Poll`]: crate::Poll
+//! [`Poll::poll`]: crate::Poll::poll
+//! [`Token`]: crate::Token
+//! [`Timer`]: crate::time::Timer
+//! [`Wheel`]: crate::time::Wheel
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod timer;
+mod wheel;
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub use self::timer::{Clock, Timer};
+pub use self::wheel::{Expired, Timeout, Wheel};
@@ -0,0 +1,232 @@
+use crate::event::Evented;
+use crate::poll::SelectorId;
+use crate::{sys, Interests, Registry, Token};
+
+use std::fmt;
+use std::io;
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use std::time::Duration;
+
+/// The clock used to measure the expiration of a [`Timer`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Clock {
+    /// A nonsettable monotonically increasing clock, `CLOCK_MONOTONIC`.
+    ///
+    /// This clock does not advance while the system is suspended.
+    Monotonic,
+    /// Like `Monotonic`, but it includes any time that the system is
+    /// suspended, `CLOCK_BOOTTIME`.
+    Boottime,
+}
+
+impl Clock {
+    fn as_clockid(self) -> libc::clockid_t {
+        match self {
+            Clock::Monotonic => libc::CLOCK_MONOTONIC,
+            Clock::Boottime => libc::CLOCK_BOOTTIME,
+        }
+    }
+}
+
+/// A timer that can be registered with [`Poll`].
+///
+/// Once armed, the timer becomes [readable] when it expires. The number of
+/// expirations since the timer was last [read] or armed can then be read
+/// without blocking. A timer can be armed to expire once ([`set_timeout`]) or
+/// periodically ([`set_interval`]), rearmed at any time by calling either
+/// method again, and disarmed using [`cancel`].
+///
+/// [`Poll`]: crate::Poll
+/// [readable]: crate::event::Event::is_readable
+/// [read]: Timer::read
+/// [`set_timeout`]: Timer::set_timeout
+/// [`set_interval`]: Timer::set_interval
+/// [`cancel`]: Timer::cancel
+///
+/// # Implementation notes
+///
+/// `Timer` is backed by [timerfd] and is only available on Linux and Android.
+///
+/// [timerfd]: http://man7.org/linux/man-pages/man2/timerfd_create.2.html
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use std::time::Duration;
+///
+/// use mio::time::Timer;
+/// use mio::{Events, Interests, Poll, Token};
+///
+/// const TIMEOUT: Token = Token(0);
+///
+/// let mut poll = Poll::new()?;
+/// let mut events = Events::with_capacity(8);
+///
+/// let timer = Timer::new()?;
+/// poll.registry().register(&timer, TIMEOUT, Interests::READABLE)?;
+///
+/// // Expire once, after 10 milliseconds.
+/// timer.set_timeout(Duration::from_millis(10))?;
+///
+/// poll.poll(&mut events, None)?;
+///
+/// let event = events.iter().next().unwrap();
+/// assert_eq!(event.token(), TIMEOUT);
+/// assert_eq!(timer.read()?, 1);
+/// #     Ok(())
+/// # }
+/// ```
+pub struct Timer {
+    sys: sys::Timer,
+    selector_id: SelectorId,
+}
+
+impl Timer {
+    /// Create a new, disarmed, `Timer` using the [monotonic] clock.
+    ///
+    /// [monotonic]: Clock::Monotonic
+    pub fn new() -> io::Result<Timer> {
+        Timer::with_clock(Clock::Monotonic)
+    }
+
+    /// Create a new, disarmed, `Timer` using the provided `clock`.
+    pub fn with_clock(clock: Clock) -> io::Result<Timer> {
+        Ok(Timer {
+            sys: sys::Timer::new(clock.as_clockid())?,
+            selector_id: SelectorId::new(),
+        })
+    }
+
+    /// Arm the timer to expire once, after `timeout`.
+    ///
+    /// This overrides any previous setting of the timer and resets the
+    /// expiration count to 0.
+    pub fn set_timeout(&self, timeout: Duration) -> io::Result<()> {
+        self.sys.set(non_zero(timeout), Duration::from_secs(0))
+    }
+
+    /// Arm the timer to expire periodically, every `interval`, starting after
+    /// `interval`.
+    ///
+    /// This overrides any previous setting of the timer and resets the
+    /// expiration count to 0.
+    pub fn set_interval(&self, interval: Duration) -> io::Result<()> {
+        self.set_timeout_interval(interval, interval)
+    }
+
+    /// Arm the timer to first expire after `timeout` and then periodically,
+    /// every `interval`.
+    ///
+    /// An `interval` of zero makes the timer expire only once, making this
+    /// equivalent to [`set_timeout`].
+    ///
+    /// This overrides any previous setting of the timer and resets the
+    /// expiration count to 0.
+    ///
+    /// [`set_timeout`]: Timer::set_timeout
+    pub fn set_timeout_interval(&self, timeout: Duration, interval: Duration) -> io::Result<()> {
+        self.sys.set(non_zero(timeout), interval)
+    }
+
+    /// Disarm the timer.
+    ///
+    /// After this returns the timer will not expire until it's armed again.
+    /// Like arming the timer, this resets the expiration count to 0, so
+    /// expirations that weren't [read] before the call are lost and `read`
+    /// returns a [`WouldBlock`] error.
+    ///
+    /// [read]: Timer::read
+    /// [`WouldBlock`]: std::io::ErrorKind::WouldBlock
+    pub fn cancel(&self) -> io::Result<()> {
+        self.sys.set(Duration::from_secs(0), Duration::from_secs(0))
+    }
+
+    /// Returns the time remaining until the next expiration of the timer, or
+    /// `None` if the timer is disarmed.
+    pub fn remaining(&self) -> io::Result<Option<Duration>> {
+        self.sys.get().map(|(value, _)| {
+            if value == Duration::from_secs(0) {
+                None
+            } else {
+                Some(value)
+            }
+        })
+    }
+
+    /// Returns the number of times the timer expired since it was last read
+    /// or armed, resetting the count to 0.
+    ///
+    /// If the timer didn't expire this returns a [`WouldBlock`] error, the
+    /// caller should then wait for another readiness event.
+    ///
+    /// [`WouldBlock`]: std::io::ErrorKind::WouldBlock
+    pub fn read(&self) -> io::Result<u64> {
+        self.selector_id
+            .did_io(Interests::READABLE, self.sys.read())
+    }
+}
+
+/// Zero disarms a timerfd, so the shortest timeout we can use to expire
+/// "immediately" is one nanosecond.
+fn non_zero(timeout: Duration) -> Duration {
+    if timeout == Duration::from_secs(0) {
+        Duration::new(0, 1)
+    } else {
+        timeout
+    }
+}
+
+impl Evented for Timer {
+    fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        self.selector_id.associate_fd(registry, self.as_raw_fd())?;
+        self.sys.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &self,
+        registry: &Registry,
+        token: Token,
+        interests: Interests,
+    ) -> io::Result<()> {
+        self.sys.reregister(registry, token, interests)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.sys.deregister(registry)
+    }
+}
+
+impl fmt::Debug for Timer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.sys, f)
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        self.selector_id.release();
+    }
+}
+
+impl IntoRawFd for Timer {
+    fn into_raw_fd(self) -> RawFd {
+        unsafe { crate::poll::into_sys(self, |timer| (&timer.selector_id, &timer.sys)) }
+            .into_raw_fd()
+    }
+}
+
+impl AsRawFd for Timer {
+    fn as_raw_fd(&self) -> RawFd {
+        self.sys.as_raw_fd()
+    }
+}
+
+impl FromRawFd for Timer {
+    unsafe fn from_raw_fd(fd: RawFd) -> Timer {
+        Timer {
+            sys: FromRawFd::from_raw_fd(fd),
+            selector_id: SelectorId::new(),
+        }
+    }
+}
@@ -0,0 +1,530 @@
+use crate::Token;
+
+use slab::Slab;
+use std::time::{Duration, Instant};
+use std::{cmp, fmt, u64};
+
+/// Number of slots in each level of the wheel.
+const LEVEL_MULT: u64 = 64;
+
+/// Number of bits needed to index a slot in a level.
+const SLOT_BITS: u64 = 6;
+
+/// Number of levels in the wheel. With 64 slots per level this allows for
+/// timeouts up to 2^36 ticks in the future, which is a little over two years
+/// for a tick of 1 millisecond.
+const NUM_LEVELS: usize = 6;
+
+/// The maximum number of ticks a timeout can be in the future, timeouts
+/// further out are clamped to this.
+const MAX_DURATION: u64 = (1 << (SLOT_BITS * NUM_LEVELS as u64)) - 1;
+
+/// A hashed hierarchical timing wheel.
+///
+/// `Wheel` keeps track of a large number of timeouts in userspace, at the cost
+/// of a fixed granularity: the `tick`. Inserting and cancelling a timeout are
+/// both `O(1)` operations, which makes the wheel well suited for things like
+/// idle or read timeouts on many connections, where most timeouts are
+/// cancelled before they expire.
+///
+/// A `Wheel` is not tied to a [`Poll`] instance, instead it's used alongside
+/// one. Before calling [`Poll::poll`] the [`next_timeout`] method is used to
+/// determine the timeout to poll with, after which the tokens of all timeouts
+/// that have [expired] can be handled together with the received events.
+///
+/// Timeouts never expire early, but they may expire up to one `tick` late
+/// (plus however late the wheel is checked).
+///
+/// [`Poll`]: crate::Poll
+/// [`Poll::poll`]: crate::Poll::poll
+/// [`next_timeout`]: Wheel::next_timeout
+/// [expired]: Wheel::expired
+///
+/// # Implementation notes
+///
+/// The wheel consists of 6 levels of 64 slots each. A slot in the first level
+/// covers a single tick, a slot in the second level covers 64 ticks, etc. A
+/// timeout is stored in the slot of the lowest level that can represent its
+/// deadline, once the wheel reaches a slot in a higher level the timeouts in
+/// that slot cascade into the lower levels.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use std::time::Duration;
+///
+/// use mio::time::Wheel;
+/// use mio::{Events, Poll, Token};
+///
+/// let mut poll = Poll::new()?;
+/// let mut events = Events::with_capacity(128);
+/// let mut wheel = Wheel::new(Duration::from_millis(1));
+///
+/// // Register some `Evented` handles with `poll` and set timeouts for them.
+/// let timeout = wheel.insert(Duration::from_millis(10), Token(0));
+/// wheel.insert(Duration::from_millis(20), Token(1));
+///
+/// // The first timeout is no longer needed.
+/// assert_eq!(wheel.cancel(timeout), Some(Token(0)));
+///
+/// loop {
+///     poll.poll(&mut events, wheel.next_timeout())?;
+///
+///     for event in &events {
+///         // Handle readiness events.
+///         # drop(event);
+///     }
+///
+///     for token in wheel.expired() {
+///         // Handle the expired timeouts.
+///         assert_eq!(token, Token(1));
+///         # return Ok(());
+///     }
+/// }
+/// # }
+/// ```
+pub struct Wheel {
+    /// The duration of a single tick.
+    tick: Duration,
+    /// The instant the wheel started, tick 0.
+    start: Instant,
+    /// The number of ticks the wheel has processed.
+    elapsed: u64,
+    levels: [Level; NUM_LEVELS],
+    /// Timeouts that have expired, but are not yet returned by `expired`.
+    expired: List,
+    entries: Slab<Entry>,
+    /// Generation of the next inserted timeout.
+    generation: u64,
+}
+
+/// A handle to a timeout in a [`Wheel`], used to [cancel] it.
+///
+/// A handle remains safe to use after its timeout has expired or was
+/// cancelled, in that case cancelling is a no-op, even if the internal storage
+/// of the timeout has since been reused for another timeout.
+///
+/// [cancel]: Wheel::cancel
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Timeout {
+    key: usize,
+    generation: u64,
+}
+
+/// Iterator returned by [`Wheel::expired`].
+#[derive(Debug)]
+pub struct Expired<'a> {
+    wheel: &'a mut Wheel,
+}
+
+struct Level {
+    /// Bit `n` is set if `slots[n]` is not empty.
+    occupied: u64,
+    slots: [List; LEVEL_MULT as usize],
+}
+
+/// Intrusive doubly linked list of entries, linked through the `prev` and
+/// `next` fields of `Entry`.
+#[derive(Copy, Clone, Default)]
+struct List {
+    head: Option<usize>,
+    tail: Option<usize>,
+}
+
+struct Entry {
+    token: Token,
+    /// Deadline in ticks.
+    deadline: u64,
+    generation: u64,
+    location: Location,
+    prev: Option<usize>,
+    next: Option<usize>,
+}
+
+#[derive(Copy, Clone)]
+enum Location {
+    Slot { level: usize, slot: usize },
+    Expired,
+}
+
+/// The next slot to expire.
+struct Expiration {
+    level: usize,
+    slot: usize,
+    /// Deadline of the slot, in ticks.
+    deadline: u64,
+}
+
+impl Wheel {
+    /// Create a new `Wheel` with the provided `tick` granularity.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `tick` is zero.
+    pub fn new(tick: Duration) -> Wheel {
+        Wheel::with_capacity(tick, 0)
+    }
+
+    /// Create a new `Wheel` with the provided `tick` granularity, able to hold
+    /// at least `capacity` timeouts without reallocating.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `tick` is zero.
+    pub fn with_capacity(tick: Duration, capacity: usize) -> Wheel {
+        assert!(tick != Duration::from_secs(0), "tick must be non-zero");
+        Wheel {
+            tick,
+            start: Instant::now(),
+            elapsed: 0,
+            levels: [
+                Level::new(),
+                Level::new(),
+                Level::new(),
+                Level::new(),
+                Level::new(),
+                Level::new(),
+            ],
+            expired: List::default(),
+            entries: Slab::with_capacity(capacity),
+            generation: 0,
+        }
+    }
+
+    /// Returns the tick granularity of the wheel.
+    pub fn tick(&self) -> Duration {
+        self.tick
+    }
+
+    /// Returns the number of timeouts in the wheel, including those that
+    /// expired but have not yet been returned by [`expired`].
+    ///
+    /// [`expired`]: Wheel::expired
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the wheel contains no timeouts.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Insert a timeout that expires after `timeout` has elapsed, returning
+    /// `token` from [`expired`] once it does.
+    ///
+    /// [`expired`]: Wheel::expired
+    pub fn insert(&mut self, timeout: Duration, token: Token) -> Timeout {
+        self.insert_at(Instant::now() + timeout, token)
+    }
+
+    /// Insert a timeout that expires at `deadline`, returning `token` from
+    /// [`expired`] once it does.
+    ///
+    /// If `deadline` already passed the timeout is returned by the next call
+    /// to [`expired`].
+    ///
+    /// [`expired`]: Wheel::expired
+    pub fn insert_at(&mut self, deadline: Instant, token: Token) -> Timeout {
+        let deadline = self.deadline_ticks(deadline);
+        let generation = self.generation;
+        self.generation = self.generation.wrapping_add(1);
+
+        let key = self.entries.insert(Entry {
+            token,
+            deadline,
+            generation,
+            location: Location::Expired,
+            prev: None,
+            next: None,
+        });
+        self.place(key);
+
+        Timeout { key, generation }
+    }
+
+    /// Cancel `timeout`, returning its token if it didn't yet expire.
+    ///
+    /// A timeout that expired, but was not yet returned by [`expired`] is
+    /// cancelled as well.
+    ///
+    /// [`expired`]: Wheel::expired
+    pub fn cancel(&mut self, timeout: Timeout) -> Option<Token> {
+        match self.entries.get(timeout.key) {
+            Some(entry) if entry.generation == timeout.generation => {}
+            _ => return None,
+        }
+
+        self.unlink(timeout.key);
+        Some(self.entries.remove(timeout.key).token)
+    }
+
+    /// Returns the duration until the next timeout expires, or `None` if the
+    /// wheel is empty.
+    ///
+    /// This is intended to be used as the `timeout` argument to
+    /// [`Poll::poll`]. The returned duration may be shorter than the actual
+    /// time until the next timeout expires, in which case [`expired`] simply
+    /// returns no tokens after polling.
+    ///
+    /// [`Poll::poll`]: crate::Poll::poll
+    /// [`expired`]: Wheel::expired
+    pub fn next_timeout(&self) -> Option<Duration> {
+        if self.expired.head.is_some() {
+            return Some(Duration::from_secs(0));
+        }
+
+        self.next_expiration().map(|expiration| {
+            let deadline = self.start + self.ticks_to_duration(expiration.deadline);
+            let now = Instant::now();
+            if deadline > now {
+                deadline - now
+            } else {
+                Duration::from_secs(0)
+            }
+        })
+    }
+
+    /// Returns an iterator over the tokens of all timeouts that expired.
+    ///
+    /// Expired timeouts are removed from the wheel as they are returned by the
+    /// iterator. Timeouts that are not consumed from the iterator remain in the
+    /// wheel and are returned by the next call to `expired`.
+    pub fn expired(&mut self) -> Expired<'_> {
+        self.expired_at(Instant::now())
+    }
+
+    /// Same as [`expired`], but using `now` as the current time.
+    ///
+    /// [`expired`]: Wheel::expired
+    pub fn expired_at(&mut self, now: Instant) -> Expired<'_> {
+        let now = self.elapsed_ticks(now);
+        self.advance(now);
+        Expired { wheel: self }
+    }
+
+    /// Advance the wheel to `now` (in ticks), moving all timeouts that expired
+    /// onto the expired list.
+    fn advance(&mut self, now: u64) {
+        while let Some(expiration) = self.next_expiration() {
+            if expiration.deadline > now {
+                break;
+            }
+
+            self.elapsed = expiration.deadline;
+            let mut list = self.levels[expiration.level].take(expiration.slot);
+            while let Some(key) = list.head {
+                list.head = self.entries[key].next;
+                // Either moves the timeout to a lower level or onto the expired
+                // list.
+                self.place(key);
+            }
+        }
+
+        self.elapsed = cmp::max(self.elapsed, now);
+    }
+
+    /// Find the next slot to expire, searching the levels from low to high.
+    fn next_expiration(&self) -> Option<Expiration> {
+        for (level, l) in self.levels.iter().enumerate() {
+            if let Some(slot) = l.next_occupied_slot(level, self.elapsed) {
+                let level_range = level_range(level);
+                let slot_range = slot_range(level);
+                let level_start = self.elapsed & !(level_range - 1);
+                let mut deadline = level_start + slot as u64 * slot_range;
+                if deadline <= self.elapsed {
+                    // Only possible for timeouts that were clamped to the
+                    // maximum duration, they're in a slot "before" the current
+                    // one.
+                    deadline += level_range;
+                }
+                return Some(Expiration {
+                    level,
+                    slot,
+                    deadline,
+                });
+            }
+        }
+        None
+    }
+
+    /// Place the (unlinked) entry `key` in the correct slot, or on the
+    /// expired list if its deadline passed.
+    fn place(&mut self, key: usize) {
+        let deadline = self.entries[key].deadline;
+        if deadline <= self.elapsed {
+            self.entries[key].location = Location::Expired;
+            let mut list = self.expired;
+            list.push_back(&mut self.entries, key);
+            self.expired = list;
+            return;
+        }
+
+        let deadline = cmp::min(deadline, self.elapsed + MAX_DURATION);
+        let level = level_for(self.elapsed, deadline);
+        let slot = ((deadline >> (level as u64 * SLOT_BITS)) % LEVEL_MULT) as usize;
+        self.entries[key].location = Location::Slot { level, slot };
+        self.levels[level].push(&mut self.entries, slot, key);
+    }
+
+    /// Remove entry `key` from the list it's in.
+    fn unlink(&mut self, key: usize) {
+        match self.entries[key].location {
+            Location::Slot { level, slot } => {
+                self.levels[level].remove(&mut self.entries, slot, key)
+            }
+            Location::Expired => {
+                let mut list = self.expired;
+                list.remove(&mut self.entries, key);
+                self.expired = list;
+            }
+        }
+    }
+
+    fn pop_expired(&mut self) -> Option<Token> {
+        let mut list = self.expired;
+        let key = list.pop_front(&mut self.entries);
+        self.expired = list;
+        key.map(|key| self.entries.remove(key).token)
+    }
+
+    /// Number of whole ticks elapsed at `now`.
+    fn elapsed_ticks(&self, now: Instant) -> u64 {
+        if now <= self.start {
+            return 0;
+        }
+        let ticks = (now - self.start).as_nanos() / self.tick.as_nanos();
+        cmp::min(ticks, u128::from(u64::MAX)) as u64
+    }
+
+    /// The first tick at or after `deadline`, so that timeouts never expire
+    /// early.
+    fn deadline_ticks(&self, deadline: Instant) -> u64 {
+        if deadline <= self.start {
+            return 0;
+        }
+        let nanos = (deadline - self.start).as_nanos();
+        let tick = self.tick.as_nanos();
+        let ticks = (nanos + tick - 1) / tick;
+        cmp::min(ticks, u128::from(u64::MAX)) as u64
+    }
+
+    fn ticks_to_duration(&self, ticks: u64) -> Duration {
+        let nanos = self.tick.as_nanos().saturating_mul(u128::from(ticks));
+        let secs = nanos / 1_000_000_000;
+        if secs > u128::from(u64::MAX) {
+            Duration::new(u64::MAX, 0)
+        } else {
+            Duration::new(secs as u64, (nanos % 1_000_000_000) as u32)
+        }
+    }
+}
+
+impl fmt::Debug for Wheel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Wheel")
+            .field("tick", &self.tick)
+            .field("elapsed", &self.elapsed)
+            .field("len", &self.entries.len())
+            .finish()
+    }
+}
+
+impl<'a> Iterator for Expired<'a> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        self.wheel.pop_expired()
+    }
+}
+
+/// The level a timeout with `deadline` should be placed in, given the current
+/// `elapsed` ticks. This is the level of the most significant slot digit in
+/// which the two differ.
+fn level_for(elapsed: u64, deadline: u64) -> usize {
+    let masked = (elapsed ^ deadline) | (LEVEL_MULT - 1);
+    let significant = 63 - masked.leading_zeros() as u64;
+    cmp::min((significant / SLOT_BITS) as usize, NUM_LEVELS - 1)
+}
+
+/// Number of ticks covered by a single slot in `level`.
+fn slot_range(level: usize) -> u64 {
+    LEVEL_MULT.pow(level as u32)
+}
+
+/// Number of ticks covered by all slots in `level`.
+fn level_range(level: usize) -> u64 {
+    LEVEL_MULT * slot_range(level)
+}
+
+impl Level {
+    fn new() -> Level {
+        Level {
+            occupied: 0,
+            slots: [List::default(); LEVEL_MULT as usize],
+        }
+    }
+
+    fn next_occupied_slot(&self, level: usize, elapsed: u64) -> Option<usize> {
+        if self.occupied == 0 {
+            return None;
+        }
+
+        let now_slot = elapsed / slot_range(level);
+        let occupied = self.occupied.rotate_right((now_slot % LEVEL_MULT) as u32);
+        let zeros = u64::from(occupied.trailing_zeros());
+        Some(((zeros + now_slot) % LEVEL_MULT) as usize)
+    }
+
+    fn push(&mut self, entries: &mut Slab<Entry>, slot: usize, key: usize) {
+        self.slots[slot].push_back(entries, key);
+        self.occupied |= 1 << slot;
+    }
+
+    fn remove(&mut self, entries: &mut Slab<Entry>, slot: usize, key: usize) {
+        self.slots[slot].remove(entries, key);
+        if self.slots[slot].head.is_none() {
+            self.occupied &= !(1 << slot);
+        }
+    }
+
+    /// Take all entries in `slot`, the entries keep their links.
+    fn take(&mut self, slot: usize) -> List {
+        self.occupied &= !(1 << slot);
+        let list = self.slots[slot];
+        self.slots[slot] = List::default();
+        list
+    }
+}
+
+impl List {
+    fn push_back(&mut self, entries: &mut Slab<Entry>, key: usize) {
+        entries[key].prev = self.tail;
+        entries[key].next = None;
+        match self.tail {
+            Some(tail) => entries[tail].next = Some(key),
+            None => self.head = Some(key),
+        }
+        self.tail = Some(key);
+    }
+
+    fn pop_front(&mut self, entries: &mut Slab<Entry>) -> Option<usize> {
+        let key = self.head?;
+        self.remove(entries, key);
+        Some(key)
+    }
+
+    fn remove(&mut self, entries: &mut Slab<Entry>, key: usize) {
+        let (prev, next) = (entries[key].prev, entries[key].next);
+        match prev {
+            Some(prev) => entries[prev].next = next,
+            None => self.head = next,
+        }
+        match next {
+            Some(next) => entries[next].prev = prev,
+            None => self.tail = prev,
+        }
+        entries[key].prev = None;
+        entries[key].next = None;
+    }
+}
@@ -0,0 +1,70 @@
+/// How readiness events are delivered for a registration.
+///
+/// By default handles are registered [edge-triggered], see
+/// [`Registry::register_with`] for registering a handle using a different
+/// trigger mode.
+///
+/// [edge-triggered]: Trigger::Edge
+/// [`Registry::register_with`]: crate::Registry::register_with
+///
+/// # Implementation notes
+///
+/// On epoll and kqueue platforms the trigger mode maps directly to a flag of
+/// the system selector.
+///
+/// | Trigger   | epoll          | kqueue        |
+/// |-----------|----------------|---------------|
+/// | `Edge`    | `EPOLLET`      | `EV_CLEAR`    |
+/// | `Level`   | (no flag)      | (no flag)     |
+/// | `Oneshot` | `EPOLLONESHOT` | `EV_DISPATCH` |
+///
+/// On Windows readiness is tracked by Mio itself, the trigger mode is
+/// emulated by the internal readiness queue.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Trigger {
+    /// An event is returned only when the readiness of the handle changes,
+    /// e.g. when new data arrives on a socket.
+    ///
+    /// After receiving an event the operation must be performed repeatedly
+    /// until it returns [`WouldBlock`], otherwise there is no guarantee that
+    /// another event will be returned.
+    ///
+    /// This is the default.
+    ///
+    /// [`WouldBlock`]: std::io::ErrorKind::WouldBlock
+    Edge,
+    /// An event is returned on every call to [`poll`] for as long as the
+    /// handle is ready, e.g. as long as there is unread data in a socket's
+    /// receive buffer.
+    ///
+    /// This doesn't require draining the handle until [`WouldBlock`] is
+    /// returned, which is the model used by `poll(2)` and `select(2)`.
+    ///
+    /// [`poll`]: crate::Poll::poll
+    /// [`WouldBlock`]: std::io::ErrorKind::WouldBlock
+    Level,
+    /// At most one event is returned, after which the registration is
+    /// disabled until it's rearmed by reregistering the handle.
+    ///
+    /// This makes it possible to hand a handle to a single thread for every
+    /// readiness event, even when multiple threads are polling. Note that
+    /// [`reregister`] registers the handle edge-triggered, use
+    /// [`reregister_with`] to rearm it for another single event.
+    ///
+    /// If the handle is still ready once rearmed another event is returned
+    /// right away.
+    ///
+    /// On kqueue platforms the readable and writable interests are disabled
+    /// independently, a handle registered with both interests can return one
+    /// event for each before it needs to be rearmed.
+    ///
+    /// [`reregister`]: crate::Registry::reregister
+    /// [`reregister_with`]: crate::Registry::reregister_with
+    Oneshot,
+}
+
+impl Default for Trigger {
+    fn default() -> Trigger {
+        Trigger::Edge
+    }
+}
@@ -0,0 +1,168 @@
+use crate::unix::pipe::{Receiver, Sender};
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use crate::unix::Process;
+
+use std::io;
+use std::process::{self, Command, ExitStatus, Stdio};
+
+/// Extension to [`std::process::Command`] to spawn a child process with
+/// non-blocking stdio.
+pub trait CommandExt {
+    /// Spawn the command as a child process, with stdin, stdout and stderr
+    /// piped to the calling process as non-blocking [`pipe`]s.
+    ///
+    /// Any stdio configuration on the command is overwritten.
+    ///
+    /// [`pipe`]: crate::unix::pipe
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use mio::unix::CommandExt;
+    /// use mio::{Events, Interests, Poll, Token};
+    /// use std::io::Read;
+    /// use std::process::Command;
+    ///
+    /// const STDOUT: Token = Token(0);
+    ///
+    /// let mut poll = Poll::new()?;
+    /// let mut events = Events::with_capacity(8);
+    ///
+    /// let mut child = Command::new("echo").arg("Hello").spawn_evented()?;
+    /// let mut stdout = child.stdout.take().unwrap();
+    /// poll.registry().register(&stdout, STDOUT, Interests::READABLE)?;
+    ///
+    /// let mut output = Vec::new();
+    /// loop {
+    ///     poll.poll(&mut events, None)?;
+    ///     // Read until the child closes its stdout.
+    ///     match stdout.read_to_end(&mut output) {
+    ///         Ok(_) => break,
+    ///         Err(ref err) if err.kind() == std::io::ErrorKind::WouldBlock => continue,
+    ///         Err(err) => return Err(err.into()),
+    ///     }
+    /// }
+    /// assert_eq!(output, b"Hello\n");
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn spawn_evented(&mut self) -> io::Result<Child>;
+}
+
+impl CommandExt for Command {
+    fn spawn_evented(&mut self) -> io::Result<Child> {
+        self.stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .and_then(Child::new)
+    }
+}
+
+/// A child process with non-blocking stdio, spawned using
+/// [`CommandExt::spawn_evented`].
+///
+/// The stdio handles can be registered with `Poll`, like any other [`pipe`].
+/// On Linux and Android [`process`] returns a handle that becomes readable
+/// once the child exits, after which [`try_wait`] collects its exit status.
+///
+/// Like [`std::process::Child`], dropping the `Child` does **not** kill or
+/// wait for the child process.
+///
+/// [`pipe`]: crate::unix::pipe
+/// [`process`]: Child::process
+/// [`try_wait`]: Child::try_wait
+#[derive(Debug)]
+pub struct Child {
+    /// Handle to the stdin of the child process.
+    pub stdin: Option<Sender>,
+    /// Handle to the stdout of the child process.
+    pub stdout: Option<Receiver>,
+    /// Handle to the stderr of the child process.
+    pub stderr: Option<Receiver>,
+    inner: process::Child,
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    process: Process,
+}
+
+impl Child {
+    fn new(mut inner: process::Child) -> io::Result<Child> {
+        // Don't leave the child process running, or unreaped, if it can't be
+        // returned.
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        let process = match Process::new(&inner) {
+            Ok(process) => process,
+            Err(err) => {
+                let _ = inner.kill();
+                let _ = inner.wait();
+                return Err(err);
+            }
+        };
+
+        let mut child = Child {
+            stdin: inner.stdin.take().map(Sender::from),
+            stdout: inner.stdout.take().map(Receiver::from),
+            stderr: inner.stderr.take().map(Receiver::from),
+            inner,
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            process,
+        };
+        if let Err(err) = child.set_nonblocking() {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(err);
+        }
+        Ok(child)
+    }
+
+    fn set_nonblocking(&self) -> io::Result<()> {
+        if let Some(ref stdin) = self.stdin {
+            stdin.set_nonblocking(true)?;
+        }
+        if let Some(ref stdout) = self.stdout {
+            stdout.set_nonblocking(true)?;
+        }
+        if let Some(ref stderr) = self.stderr {
+            stderr.set_nonblocking(true)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the process id of the child process.
+    pub fn id(&self) -> u32 {
+        self.inner.id()
+    }
+
+    /// Returns a handle that becomes readable once the child process exits.
+    ///
+    /// Use [`Child::try_wait`], not [`Process::try_wait`], to collect the exit
+    /// status afterwards, so that the exit status can be collected more than
+    /// once.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn process(&self) -> &Process {
+        &self.process
+    }
+
+    /// Kill the child process, see [`std::process::Child::kill`].
+    pub fn kill(&mut self) -> io::Result<()> {
+        self.inner.kill()
+    }
+
+    /// Collect the exit status of the child process, if it has exited.
+    ///
+    /// If the process is still running this returns `Ok(None)`. This operation
+    /// does not block.
+    pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
+        self.inner.try_wait()
+    }
+
+    /// Wait for the child process to exit, **blocking** the calling thread,
+    /// see [`std::process::Child::wait`].
+    ///
+    /// The child's stdin is closed before waiting, to avoid a deadlock.
+    pub fn wait(&mut self) -> io::Result<ExitStatus> {
+        drop(self.stdin.take());
+        self.inner.wait()
+    }
+}
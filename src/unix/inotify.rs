@@ -0,0 +1,445 @@
+//! Filesystem notifications using inotify.
+//!
+//! See [`Inotify`] for documentation.
+
+use crate::event::Evented;
+use crate::poll::SelectorId;
+use crate::{sys, Interests, Registry, Token};
+
+use std::ffi::OsStr;
+use std::os::raw::c_int;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::io::{AsRawFd, IntoRawFd, RawFd};
+use std::path::Path;
+use std::{fmt, io, mem, ops, ptr};
+
+/// Watch files and directories for changes.
+///
+/// Files and directories are watched by [adding] a watch for them, with a
+/// [`WatchMask`] selecting the kinds of changes to watch. Once a change
+/// happens `Inotify` becomes [readable], after which the queued events can be
+/// [read] without blocking.
+///
+/// [adding]: Inotify::add_watch
+/// [readable]: crate::event::Event::is_readable
+/// [read]: Inotify::read_events
+///
+/// # Implementation notes
+///
+/// `Inotify` is backed by [inotify] and is only available on Linux and
+/// Android.
+///
+/// [inotify]: http://man7.org/linux/man-pages/man7/inotify.7.html
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use mio::unix::inotify::{EventMask, WatchMask};
+/// use mio::unix::Inotify;
+/// use mio::{Events, Interests, Poll, Token};
+/// use std::fs::File;
+/// # use tempdir::TempDir;
+/// # let dir = TempDir::new("inotify")?;
+/// # let dir = dir.path();
+///
+/// const INOTIFY: Token = Token(0);
+///
+/// let mut poll = Poll::new()?;
+/// let mut events = Events::with_capacity(8);
+///
+/// let inotify = Inotify::new()?;
+/// let wd = inotify.add_watch(dir, WatchMask::CREATE | WatchMask::DELETE)?;
+/// poll.registry().register(&inotify, INOTIFY, Interests::READABLE)?;
+///
+/// File::create(dir.join("file"))?;
+/// poll.poll(&mut events, None)?;
+///
+/// let mut buf = [0; Inotify::MIN_BUFFER_SIZE];
+/// for event in inotify.read_events(&mut buf)? {
+///     assert_eq!(event.wd(), Some(wd));
+///     assert!(event.mask().contains(EventMask::CREATE));
+///     assert_eq!(event.name().unwrap(), "file");
+/// }
+/// #     Ok(())
+/// # }
+/// ```
+pub struct Inotify {
+    sys: sys::Inotify,
+    selector_id: SelectorId,
+}
+
+impl Inotify {
+    /// Size of a buffer that can hold any single event.
+    ///
+    /// Buffers passed to [`read_events`] should be at least this large,
+    /// otherwise reading fails if the next event has a long name.
+    ///
+    /// [`read_events`]: Inotify::read_events
+    // The name is at most `NAME_MAX` (255) bytes, plus a null terminator.
+    pub const MIN_BUFFER_SIZE: usize = mem::size_of::<libc::inotify_event>() + 255 + 1;
+
+    /// Create a new `Inotify`, without any watches.
+    pub fn new() -> io::Result<Inotify> {
+        Ok(Inotify {
+            sys: sys::Inotify::new()?,
+            selector_id: SelectorId::new(),
+        })
+    }
+
+    /// Watch the file or directory at `path` for the changes in `mask`.
+    ///
+    /// If `path` is already watched, the same watch descriptor is returned and
+    /// its mask is replaced by `mask`, or extended with `mask` if it includes
+    /// [`WatchMask::MASK_ADD`]. This can be done after `Inotify` is
+    /// registered.
+    pub fn add_watch<P: AsRef<Path>>(
+        &self,
+        path: P,
+        mask: WatchMask,
+    ) -> io::Result<WatchDescriptor> {
+        self.sys
+            .add_watch(path.as_ref(), mask.0)
+            .map(WatchDescriptor)
+    }
+
+    /// Stop watching `wd`.
+    ///
+    /// This queues an event with [`EventMask::IGNORED`] for `wd`, after which
+    /// no events for it are received.
+    pub fn remove_watch(&self, wd: WatchDescriptor) -> io::Result<()> {
+        self.sys.rm_watch(wd.0)
+    }
+
+    /// Read the queued events into `buf`, returning an iterator over them.
+    ///
+    /// Only whole events are read, as many as fit into `buf`. If the next
+    /// event doesn't fit at all this returns an [`InvalidInput`] error, see
+    /// [`MIN_BUFFER_SIZE`].
+    ///
+    /// If no event is queued this returns a [`WouldBlock`] error, the caller
+    /// should then wait for another readiness event. If the queue overflowed
+    /// it contains an event for which [`InotifyEvent::is_overflow`] returns
+    /// `true`.
+    ///
+    /// [`InvalidInput`]: std::io::ErrorKind::InvalidInput
+    /// [`MIN_BUFFER_SIZE`]: Inotify::MIN_BUFFER_SIZE
+    /// [`WouldBlock`]: std::io::ErrorKind::WouldBlock
+    pub fn read_events<'a>(&self, buf: &'a mut [u8]) -> io::Result<InotifyEvents<'a>> {
+        let n = self
+            .selector_id
+            .did_io(Interests::READABLE, self.sys.read(buf))?;
+        Ok(InotifyEvents::new(&buf[..n]))
+    }
+}
+
+impl Evented for Inotify {
+    fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        self.selector_id.associate_fd(registry, self.as_raw_fd())?;
+        self.sys.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &self,
+        registry: &Registry,
+        token: Token,
+        interests: Interests,
+    ) -> io::Result<()> {
+        self.sys.reregister(registry, token, interests)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.sys.deregister(registry)
+    }
+}
+
+impl fmt::Debug for Inotify {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.sys, f)
+    }
+}
+
+impl Drop for Inotify {
+    fn drop(&mut self) {
+        self.selector_id.release();
+    }
+}
+
+impl IntoRawFd for Inotify {
+    fn into_raw_fd(self) -> RawFd {
+        unsafe { crate::poll::into_sys(self, |inotify| (&inotify.selector_id, &inotify.sys)) }
+            .into_raw_fd()
+    }
+}
+
+impl AsRawFd for Inotify {
+    fn as_raw_fd(&self) -> RawFd {
+        self.sys.as_raw_fd()
+    }
+}
+
+/// Identifies a watch, returned by [`Inotify::add_watch`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WatchDescriptor(c_int);
+
+/// Iterator over the events read by [`Inotify::read_events`].
+#[derive(Clone, Debug)]
+pub struct InotifyEvents<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> InotifyEvents<'a> {
+    /// Decode the events in `buf`, as read from an inotify file descriptor.
+    ///
+    /// An incomplete event at the end of `buf` is ignored.
+    pub fn new(buf: &'a [u8]) -> InotifyEvents<'a> {
+        InotifyEvents { buf }
+    }
+}
+
+impl<'a> Iterator for InotifyEvents<'a> {
+    type Item = InotifyEvent<'a>;
+
+    fn next(&mut self) -> Option<InotifyEvent<'a>> {
+        const HEADER_LEN: usize = mem::size_of::<libc::inotify_event>();
+        if self.buf.len() < HEADER_LEN {
+            self.buf = &[];
+            return None;
+        }
+        // `buf` isn't necessarily aligned for `inotify_event`.
+        let raw = unsafe { ptr::read_unaligned(self.buf.as_ptr() as *const libc::inotify_event) };
+        let len = HEADER_LEN + raw.len as usize;
+        if self.buf.len() < len {
+            self.buf = &[];
+            return None;
+        }
+
+        // The name is padded with null bytes.
+        let name = &self.buf[HEADER_LEN..len];
+        let name = match name.iter().position(|&b| b == 0) {
+            Some(end) => &name[..end],
+            None => name,
+        };
+        self.buf = &self.buf[len..];
+
+        Some(InotifyEvent {
+            wd: if raw.wd == -1 {
+                None
+            } else {
+                Some(WatchDescriptor(raw.wd))
+            },
+            mask: EventMask(raw.mask),
+            cookie: raw.cookie,
+            name: if name.is_empty() {
+                None
+            } else {
+                Some(OsStr::from_bytes(name))
+            },
+        })
+    }
+}
+
+/// A filesystem event, see [`Inotify::read_events`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InotifyEvent<'a> {
+    wd: Option<WatchDescriptor>,
+    mask: EventMask,
+    cookie: u32,
+    name: Option<&'a OsStr>,
+}
+
+impl<'a> InotifyEvent<'a> {
+    /// The watch the event is for, `None` if the event queue overflowed.
+    pub fn wd(&self) -> Option<WatchDescriptor> {
+        self.wd
+    }
+
+    /// The kind of event.
+    pub fn mask(&self) -> EventMask {
+        self.mask
+    }
+
+    /// Connects the [`MOVED_FROM`] and [`MOVED_TO`] events of a single rename,
+    /// 0 for other events.
+    ///
+    /// [`MOVED_FROM`]: EventMask::MOVED_FROM
+    /// [`MOVED_TO`]: EventMask::MOVED_TO
+    pub fn cookie(&self) -> u32 {
+        self.cookie
+    }
+
+    /// The name of the file the event is for, relative to the watched
+    /// directory. `None` for events about the watched file or directory
+    /// itself.
+    pub fn name(&self) -> Option<&'a OsStr> {
+        self.name
+    }
+
+    /// Returns `true` if the event queue overflowed, i.e. events were dropped.
+    ///
+    /// After an overflow the state of the watched files and directories
+    /// should be rescanned.
+    pub fn is_overflow(&self) -> bool {
+        self.mask.contains(EventMask::Q_OVERFLOW)
+    }
+}
+
+macro_rules! mask {
+    (
+        $(#[$meta:meta])*
+        pub struct $name: ident {
+            $(
+                $(#[$flag_meta:meta])*
+                const $flag: ident = $value: expr;
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Copy, Clone, PartialEq, Eq, Hash)]
+        pub struct $name(u32);
+
+        impl $name {
+            $(
+                $(#[$flag_meta])*
+                pub const $flag: $name = $name($value);
+            )*
+
+            /// Returns `true` if all bits of `other` are set in `self`.
+            pub fn contains(self, other: $name) -> bool {
+                self.0 & other.0 == other.0
+            }
+
+            /// Returns the raw bits of the mask.
+            pub fn bits(self) -> u32 {
+                self.0
+            }
+        }
+
+        impl ops::BitOr for $name {
+            type Output = Self;
+
+            #[inline]
+            fn bitor(self, other: Self) -> Self {
+                $name(self.0 | other.0)
+            }
+        }
+
+        impl ops::BitOrAssign for $name {
+            #[inline]
+            fn bitor_assign(&mut self, other: Self) {
+                self.0 |= other.0;
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let mut rest = self.0;
+                let mut one = false;
+                $(
+                    // Skip the combinations of other flags.
+                    if $value.count_ones() == 1 && rest & $value != 0 {
+                        if one {
+                            write!(fmt, " | ")?
+                        }
+                        write!(fmt, "{}", stringify!($flag))?;
+                        rest &= !$value;
+                        one = true
+                    }
+                )*
+                if rest != 0 || !one {
+                    if one {
+                        write!(fmt, " | ")?
+                    }
+                    write!(fmt, "{:#x}", rest)?;
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+mask! {
+    /// Kinds of changes to watch, used in [`Inotify::add_watch`].
+    pub struct WatchMask {
+        /// File was accessed, e.g. read.
+        const ACCESS = libc::IN_ACCESS;
+        /// Metadata changed, e.g. permissions or timestamps.
+        const ATTRIB = libc::IN_ATTRIB;
+        /// File opened for writing was closed.
+        const CLOSE_WRITE = libc::IN_CLOSE_WRITE;
+        /// File not opened for writing was closed.
+        const CLOSE_NOWRITE = libc::IN_CLOSE_NOWRITE;
+        /// File or directory created in the watched directory.
+        const CREATE = libc::IN_CREATE;
+        /// File or directory deleted from the watched directory.
+        const DELETE = libc::IN_DELETE;
+        /// Watched file or directory was itself deleted.
+        const DELETE_SELF = libc::IN_DELETE_SELF;
+        /// File was modified, e.g. written.
+        const MODIFY = libc::IN_MODIFY;
+        /// Watched file or directory was itself moved.
+        const MOVE_SELF = libc::IN_MOVE_SELF;
+        /// File moved out of the watched directory.
+        const MOVED_FROM = libc::IN_MOVED_FROM;
+        /// File moved into the watched directory.
+        const MOVED_TO = libc::IN_MOVED_TO;
+        /// File or directory was opened.
+        const OPEN = libc::IN_OPEN;
+        /// All of the above.
+        const ALL_EVENTS = libc::IN_ALL_EVENTS;
+        /// Both `MOVED_FROM` and `MOVED_TO`.
+        const MOVE = libc::IN_MOVE;
+        /// Both `CLOSE_WRITE` and `CLOSE_NOWRITE`.
+        const CLOSE = libc::IN_CLOSE;
+        /// Don't follow `path` if it's a symbolic link.
+        const DONT_FOLLOW = libc::IN_DONT_FOLLOW;
+        /// Don't generate events for children after they've been unlinked from
+        /// the watched directory.
+        const EXCL_UNLINK = libc::IN_EXCL_UNLINK;
+        /// Add to the mask of an existing watch, rather than replacing it.
+        const MASK_ADD = libc::IN_MASK_ADD;
+        /// Remove the watch after a single event.
+        const ONESHOT = libc::IN_ONESHOT;
+        /// Only watch `path` if it's a directory.
+        const ONLYDIR = libc::IN_ONLYDIR;
+    }
+}
+
+mask! {
+    /// Kind of a received [`InotifyEvent`].
+    pub struct EventMask {
+        /// File was accessed, e.g. read.
+        const ACCESS = libc::IN_ACCESS;
+        /// Metadata changed, e.g. permissions or timestamps.
+        const ATTRIB = libc::IN_ATTRIB;
+        /// File opened for writing was closed.
+        const CLOSE_WRITE = libc::IN_CLOSE_WRITE;
+        /// File not opened for writing was closed.
+        const CLOSE_NOWRITE = libc::IN_CLOSE_NOWRITE;
+        /// File or directory created in the watched directory.
+        const CREATE = libc::IN_CREATE;
+        /// File or directory deleted from the watched directory.
+        const DELETE = libc::IN_DELETE;
+        /// Watched file or directory was itself deleted.
+        const DELETE_SELF = libc::IN_DELETE_SELF;
+        /// File was modified, e.g. written.
+        const MODIFY = libc::IN_MODIFY;
+        /// Watched file or directory was itself moved.
+        const MOVE_SELF = libc::IN_MOVE_SELF;
+        /// File moved out of the watched directory.
+        const MOVED_FROM = libc::IN_MOVED_FROM;
+        /// File moved into the watched directory.
+        const MOVED_TO = libc::IN_MOVED_TO;
+        /// File or directory was opened.
+        const OPEN = libc::IN_OPEN;
+        /// The watch was removed, explicitly or because the watched file or
+        /// directory was deleted or its filesystem unmounted.
+        const IGNORED = libc::IN_IGNORED;
+        /// The event is for a directory.
+        const ISDIR = libc::IN_ISDIR;
+        /// The event queue overflowed, see [`InotifyEvent::is_overflow`].
+        const Q_OVERFLOW = libc::IN_Q_OVERFLOW;
+        /// The filesystem of the watched file or directory was unmounted.
+        const UNMOUNT = libc::IN_UNMOUNT;
+    }
+}
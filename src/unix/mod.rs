@@ -0,0 +1,23 @@
+//! Unix only extensions
+
+mod child;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub mod inotify;
+mod owned_fd;
+pub mod pipe;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod process;
+mod signal_pipe;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod signals;
+
+pub use self::child::{Child, CommandExt};
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub use self::inotify::Inotify;
+pub use self::owned_fd::OwnedFd;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub use self::process::Process;
+pub use self::signal_pipe::{SignalCounts, SignalCountsIter, SignalPipe};
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub use self::signals::{SignalInfo, Signals};
+pub use crate::sys::EventedFd;
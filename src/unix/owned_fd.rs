@@ -0,0 +1,48 @@
+use crate::sys;
+
+use std::fmt;
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+
+/// An owned file descriptor, which is closed when dropped.
+///
+/// This is returned by the `recv_with_fds` methods of the Unix socket types,
+/// e.g. [`UnixStream::recv_with_fds`], for the received file descriptors. Use
+/// [`into_raw_fd`] and the `FromRawFd` implementation of the expected type to
+/// convert it, for example into a [`TcpListener`].
+///
+/// [`UnixStream::recv_with_fds`]: crate::net::UnixStream::recv_with_fds
+/// [`into_raw_fd`]: IntoRawFd::into_raw_fd
+/// [`TcpListener`]: crate::net::TcpListener
+pub struct OwnedFd {
+    inner: sys::Io,
+}
+
+impl OwnedFd {
+    pub(crate) fn from_sys(inner: sys::Io) -> OwnedFd {
+        OwnedFd { inner }
+    }
+}
+
+impl fmt::Debug for OwnedFd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("OwnedFd").field(&self.as_raw_fd()).finish()
+    }
+}
+
+impl AsRawFd for OwnedFd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+impl IntoRawFd for OwnedFd {
+    fn into_raw_fd(self) -> RawFd {
+        self.inner.into_raw_fd()
+    }
+}
+
+impl FromRawFd for OwnedFd {
+    unsafe fn from_raw_fd(fd: RawFd) -> OwnedFd {
+        OwnedFd::from_sys(sys::Io::from_raw_fd(fd))
+    }
+}
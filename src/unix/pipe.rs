@@ -0,0 +1,342 @@
+//! Unix pipe.
+//!
+//! See the [`new`] function for documentation.
+
+use crate::event::Evented;
+use crate::poll::SelectorId;
+use crate::{sys, Interests, Registry, Token};
+
+use iovec::IoVec;
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::{self, Read, Write};
+use std::os::unix::fs::{FileTypeExt, OpenOptionsExt};
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use std::path::Path;
+use std::process::{ChildStderr, ChildStdin, ChildStdout};
+
+/// Create a new non-blocking Unix pipe.
+///
+/// This is a wrapper around Unix's [`pipe(2)`] system call and can be used as
+/// an inter-thread or inter-process communication channel. Both ends are
+/// non-blocking and have the close-on-exec flag set.
+///
+/// [`pipe(2)`]: http://man7.org/linux/man-pages/man2/pipe.2.html
+///
+/// # Deregistering
+///
+/// Both [`Sender`] and [`Receiver`] will deregister themselves when dropped,
+/// **iff** the file descriptors are not duplicated (via [`dup(2)`]).
+///
+/// [`dup(2)`]: http://man7.org/linux/man-pages/man2/dup.2.html
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use mio::unix::pipe;
+/// use mio::{Events, Interests, Poll, Token};
+/// use std::io::{Read, Write};
+///
+/// const PIPE_RECV: Token = Token(0);
+///
+/// let (mut sender, mut receiver) = pipe::new()?;
+///
+/// let mut poll = Poll::new()?;
+/// let mut events = Events::with_capacity(8);
+/// poll.registry().register(&receiver, PIPE_RECV, Interests::READABLE)?;
+///
+/// sender.write_all(b"Hello")?;
+/// poll.poll(&mut events, None)?;
+///
+/// let mut buf = [0; 16];
+/// let n = receiver.read(&mut buf)?;
+/// assert_eq!(&buf[..n], b"Hello");
+/// #     Ok(())
+/// # }
+/// ```
+pub fn new() -> io::Result<(Sender, Receiver)> {
+    let (receiver, sender) = sys::pipe()?;
+    Ok((Sender::new(sender), Receiver::new(receiver)))
+}
+
+/// Sending end of an Unix pipe.
+///
+/// See [`new`] for documentation, including examples.
+pub struct Sender {
+    inner: sys::Io,
+    selector_id: SelectorId,
+}
+
+/// Receiving end of an Unix pipe.
+///
+/// See [`new`] for documentation, including examples.
+pub struct Receiver {
+    inner: sys::Io,
+    selector_id: SelectorId,
+}
+
+impl Sender {
+    fn new(inner: sys::Io) -> Sender {
+        Sender {
+            inner,
+            selector_id: SelectorId::new(),
+        }
+    }
+
+    /// Open the named pipe (FIFO) at `path` for writing, without blocking.
+    ///
+    /// This returns an error if no process has the FIFO open for reading
+    /// (`ENXIO`), or if `path` isn't a FIFO.
+    pub fn open_fifo<P: AsRef<Path>>(path: P) -> io::Result<Sender> {
+        open_fifo(path.as_ref(), true).map(Sender::new)
+    }
+
+    /// Set the `Sender` into or out of non-blocking mode.
+    ///
+    /// Pipes created by [`new`] and [`open_fifo`] are non-blocking, but the
+    /// ones converted from `ChildStdin` are not.
+    ///
+    /// [`open_fifo`]: Sender::open_fifo
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.inner.set_nonblocking(nonblocking)
+    }
+
+    /// Write a list of buffers all at once.
+    ///
+    /// The buffers provided will be written sequentially. A buffer will be
+    /// entirely written before the next is written.
+    ///
+    /// The number of bytes written is returned, if successful, or an error is
+    /// returned otherwise. If the pipe is full then a "would block" error is
+    /// returned. This operation does not block.
+    pub fn write_bufs(&self, bufs: &[&IoVec]) -> io::Result<usize> {
+        self.selector_id
+            .did_io(Interests::WRITABLE, self.inner.writev(bufs))
+    }
+}
+
+impl Receiver {
+    fn new(inner: sys::Io) -> Receiver {
+        Receiver {
+            inner,
+            selector_id: SelectorId::new(),
+        }
+    }
+
+    /// Open the named pipe (FIFO) at `path` for reading, without blocking.
+    ///
+    /// Unlike a blocking open this doesn't wait for a writer. Until a process
+    /// opens the FIFO for writing, reads return 0 (end of file).
+    ///
+    /// This returns an error if `path` isn't a FIFO.
+    pub fn open_fifo<P: AsRef<Path>>(path: P) -> io::Result<Receiver> {
+        open_fifo(path.as_ref(), false).map(Receiver::new)
+    }
+
+    /// Set the `Receiver` into or out of non-blocking mode.
+    ///
+    /// Pipes created by [`new`] and [`open_fifo`] are non-blocking, but the
+    /// ones converted from `ChildStdout` or `ChildStderr` are not.
+    ///
+    /// [`open_fifo`]: Receiver::open_fifo
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.inner.set_nonblocking(nonblocking)
+    }
+
+    /// Read in a list of buffers all at once.
+    ///
+    /// The buffers provided will be filled in sequentially. A buffer will be
+    /// entirely filled up before the next is written to.
+    ///
+    /// The number of bytes read is returned, if successful, or an error is
+    /// returned otherwise. If no bytes are available to be read yet then
+    /// a "would block" error is returned. This operation does not block.
+    pub fn read_bufs(&self, bufs: &mut [&mut IoVec]) -> io::Result<usize> {
+        let len = bufs.iter().map(|buf| buf.len()).sum();
+        self.selector_id.did_read(len, self.inner.readv(bufs))
+    }
+}
+
+fn open_fifo(path: &Path, write: bool) -> io::Result<sys::Io> {
+    let file = OpenOptions::new()
+        .read(!write)
+        .write(write)
+        .custom_flags(libc::O_NONBLOCK)
+        .open(path)?;
+    if !file.metadata()?.file_type().is_fifo() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "path is not a FIFO",
+        ));
+    }
+    Ok(unsafe { sys::Io::from_raw_fd(file.into_raw_fd()) })
+}
+
+/// Converts the `ChildStdin` into a `Sender`.
+///
+/// Note that the pipe is **not** set into non-blocking mode, use
+/// [`Sender::set_nonblocking`] for that.
+impl From<ChildStdin> for Sender {
+    fn from(stdin: ChildStdin) -> Sender {
+        unsafe { Sender::from_raw_fd(stdin.into_raw_fd()) }
+    }
+}
+
+/// Converts the `ChildStdout` into a `Receiver`.
+///
+/// Note that the pipe is **not** set into non-blocking mode, use
+/// [`Receiver::set_nonblocking`] for that.
+impl From<ChildStdout> for Receiver {
+    fn from(stdout: ChildStdout) -> Receiver {
+        unsafe { Receiver::from_raw_fd(stdout.into_raw_fd()) }
+    }
+}
+
+/// Converts the `ChildStderr` into a `Receiver`.
+///
+/// Note that the pipe is **not** set into non-blocking mode, use
+/// [`Receiver::set_nonblocking`] for that.
+impl From<ChildStderr> for Receiver {
+    fn from(stderr: ChildStderr) -> Receiver {
+        unsafe { Receiver::from_raw_fd(stderr.into_raw_fd()) }
+    }
+}
+
+impl Write for Sender {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.selector_id
+            .did_io(Interests::WRITABLE, (&self.inner).write(buf))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        (&self.inner).flush()
+    }
+}
+
+impl Write for &Sender {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.selector_id
+            .did_io(Interests::WRITABLE, (&self.inner).write(buf))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        (&self.inner).flush()
+    }
+}
+
+impl Read for Receiver {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.selector_id
+            .did_read(buf.len(), (&self.inner).read(buf))
+    }
+}
+
+impl Read for &Receiver {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.selector_id
+            .did_read(buf.len(), (&self.inner).read(buf))
+    }
+}
+
+impl Evented for Sender {
+    fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        self.selector_id.associate_fd(registry, self.as_raw_fd())?;
+        self.inner.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &self,
+        registry: &Registry,
+        token: Token,
+        interests: Interests,
+    ) -> io::Result<()> {
+        self.inner.reregister(registry, token, interests)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.inner.deregister(registry)
+    }
+}
+
+impl Evented for Receiver {
+    fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        self.selector_id.associate_fd(registry, self.as_raw_fd())?;
+        self.inner.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &self,
+        registry: &Registry,
+        token: Token,
+        interests: Interests,
+    ) -> io::Result<()> {
+        self.inner.reregister(registry, token, interests)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.inner.deregister(registry)
+    }
+}
+
+impl fmt::Debug for Sender {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Sender").field(&self.as_raw_fd()).finish()
+    }
+}
+
+impl fmt::Debug for Receiver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Receiver").field(&self.as_raw_fd()).finish()
+    }
+}
+
+impl AsRawFd for Sender {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+impl Drop for Sender {
+    fn drop(&mut self) {
+        self.selector_id.release();
+    }
+}
+
+impl IntoRawFd for Sender {
+    fn into_raw_fd(self) -> RawFd {
+        unsafe { crate::poll::into_sys(self, |sender| (&sender.selector_id, &sender.inner)) }
+            .into_raw_fd()
+    }
+}
+
+impl FromRawFd for Sender {
+    unsafe fn from_raw_fd(fd: RawFd) -> Sender {
+        Sender::new(sys::Io::from_raw_fd(fd))
+    }
+}
+
+impl AsRawFd for Receiver {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+impl Drop for Receiver {
+    fn drop(&mut self) {
+        self.selector_id.release();
+    }
+}
+
+impl IntoRawFd for Receiver {
+    fn into_raw_fd(self) -> RawFd {
+        unsafe { crate::poll::into_sys(self, |receiver| (&receiver.selector_id, &receiver.inner)) }
+            .into_raw_fd()
+    }
+}
+
+impl FromRawFd for Receiver {
+    unsafe fn from_raw_fd(fd: RawFd) -> Receiver {
+        Receiver::new(sys::Io::from_raw_fd(fd))
+    }
+}
@@ -0,0 +1,149 @@
+use crate::event::Evented;
+use crate::poll::SelectorId;
+use crate::{sys, Interests, Registry, Token};
+
+use std::fmt;
+use std::io;
+use std::os::raw::c_int;
+use std::os::unix::io::{AsRawFd, IntoRawFd, RawFd};
+use std::process::{Child, ExitStatus};
+
+/// Handle to a process, which becomes readable once the process exits.
+///
+/// Once `Process` is [readable] the exit status of a child process can be
+/// collected, without blocking, using [`try_wait`].
+///
+/// Signals sent using [`send_signal`] are sent to the process the handle was
+/// created for, even if that process exited and its pid has been reused by
+/// another process since, in which case an error is returned instead.
+///
+/// [readable]: crate::event::Event::is_readable
+/// [`try_wait`]: Process::try_wait
+/// [`send_signal`]: Process::send_signal
+///
+/// # Implementation notes
+///
+/// `Process` is backed by a [pidfd] and is only available on Linux (5.3 or
+/// later) and Android.
+///
+/// [pidfd]: http://man7.org/linux/man-pages/man2/pidfd_open.2.html
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use mio::unix::Process;
+/// use mio::{Events, Interests, Poll, Token};
+/// use std::process::Command;
+///
+/// const CHILD: Token = Token(0);
+///
+/// let mut poll = Poll::new()?;
+/// let mut events = Events::with_capacity(8);
+///
+/// let child = Command::new("true").spawn()?;
+/// let process = Process::new(&child)?;
+/// poll.registry().register(&process, CHILD, Interests::READABLE)?;
+///
+/// poll.poll(&mut events, None)?;
+///
+/// for event in events.iter() {
+///     assert_eq!(event.token(), CHILD);
+///     let status = process.try_wait()?.unwrap();
+///     assert!(status.success());
+/// }
+/// #     Ok(())
+/// # }
+/// ```
+pub struct Process {
+    sys: sys::Process,
+    selector_id: SelectorId,
+}
+
+impl Process {
+    /// Create a new `Process` for the `child` process.
+    pub fn new(child: &Child) -> io::Result<Process> {
+        Process::from_pid(child.id())
+    }
+
+    /// Create a new `Process` for the process with id `pid`.
+    ///
+    /// The process doesn't have to be a child of the calling process, but
+    /// only the exit status of child processes can be collected.
+    pub fn from_pid(pid: u32) -> io::Result<Process> {
+        Ok(Process {
+            sys: sys::Process::open(pid as libc::pid_t)?,
+            selector_id: SelectorId::new(),
+        })
+    }
+
+    /// Returns the process id.
+    pub fn pid(&self) -> u32 {
+        self.sys.pid() as u32
+    }
+
+    /// Collect the exit status of the child process, if it has exited.
+    ///
+    /// If the process is still running this returns `Ok(None)`, the caller
+    /// should then wait for a readiness event. This operation does not block.
+    ///
+    /// Collecting the exit status reaps the child process, which means it can
+    /// only be collected once, and calling [`Child::wait`] or
+    /// [`Child::try_wait`] afterwards returns an error.
+    pub fn try_wait(&self) -> io::Result<Option<ExitStatus>> {
+        self.sys.try_wait()
+    }
+
+    /// Send `signal` to the process, e.g. `SIGTERM`.
+    ///
+    /// If the process already exited, and its exit status was collected, this
+    /// returns an error (`ESRCH`).
+    pub fn send_signal(&self, signal: c_int) -> io::Result<()> {
+        self.sys.send_signal(signal)
+    }
+}
+
+impl Evented for Process {
+    fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        self.selector_id.associate_fd(registry, self.as_raw_fd())?;
+        self.sys.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &self,
+        registry: &Registry,
+        token: Token,
+        interests: Interests,
+    ) -> io::Result<()> {
+        self.sys.reregister(registry, token, interests)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.sys.deregister(registry)
+    }
+}
+
+impl fmt::Debug for Process {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.sys, f)
+    }
+}
+
+impl Drop for Process {
+    fn drop(&mut self) {
+        self.selector_id.release();
+    }
+}
+
+impl IntoRawFd for Process {
+    fn into_raw_fd(self) -> RawFd {
+        unsafe { crate::poll::into_sys(self, |process| (&process.selector_id, &process.sys)) }
+            .into_raw_fd()
+    }
+}
+
+impl AsRawFd for Process {
+    fn as_raw_fd(&self) -> RawFd {
+        self.sys.as_raw_fd()
+    }
+}
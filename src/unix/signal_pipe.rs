@@ -0,0 +1,218 @@
+use crate::event::Evented;
+use crate::poll::SelectorId;
+use crate::sys::unix::MAX_SIGNAL;
+use crate::{sys, Interests, Registry, Token};
+
+use std::fmt;
+use std::io;
+use std::os::raw::c_int;
+use std::os::unix::io::{AsRawFd, RawFd};
+
+/// Receive signals as readiness events, using signal handlers.
+///
+/// This is an alternative to [`Signals`] that doesn't require blocking the
+/// signals, so it works regardless of the signal mask of the threads in the
+/// process. Instead it installs a signal handler (using `sigaction`) for the
+/// provided signals, which writes to a non-blocking pipe when the signal is
+/// delivered. This makes the `SignalPipe` [readable], after which the number
+/// of times each signal was delivered can be [received] without blocking.
+///
+/// Repeated deliveries of a signal before it's received are coalesced into a
+/// single readiness event, the [counts] report how many deliveries there were.
+///
+/// [`Signals`]: crate::unix::Signals
+/// [readable]: crate::event::Event::is_readable
+/// [received]: SignalPipe::receive
+/// [counts]: SignalCounts
+///
+/// # Notes
+///
+/// The signal handler is installed for the entire process and stays installed
+/// after the `SignalPipe` is dropped, at which point the signal is ignored.
+/// Any handler that was installed before is called after ours, but the default
+/// disposition of a signal (e.g. terminating the process) is never run. At
+/// most 32 `SignalPipe`s can exist at the same time.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use mio::unix::SignalPipe;
+/// use mio::{Events, Interests, Poll, Token};
+///
+/// const SIGNAL: Token = Token(0);
+///
+/// let mut poll = Poll::new()?;
+/// let mut events = Events::with_capacity(8);
+///
+/// let signals = SignalPipe::new(&[libc::SIGUSR1, libc::SIGHUP])?;
+/// poll.registry().register(&signals, SIGNAL, Interests::READABLE)?;
+///
+/// // Send ourselves some signals.
+/// unsafe {
+///     libc::raise(libc::SIGUSR1);
+///     libc::raise(libc::SIGUSR1);
+/// }
+///
+/// poll.poll(&mut events, None)?;
+///
+/// let counts = signals.receive()?;
+/// assert_eq!(counts.get(libc::SIGUSR1), 2);
+/// assert_eq!(counts.get(libc::SIGHUP), 0);
+/// #     Ok(())
+/// # }
+/// ```
+pub struct SignalPipe {
+    sys: sys::SignalPipe,
+    selector_id: SelectorId,
+}
+
+impl SignalPipe {
+    /// Create a new `SignalPipe` receiving the provided `signals`.
+    ///
+    /// This returns an error if a signal can't be handled, e.g. `SIGKILL`.
+    pub fn new(signals: &[c_int]) -> io::Result<SignalPipe> {
+        let sys = sys::SignalPipe::new()?;
+        for &signal in signals {
+            sys.add(signal)?;
+        }
+        Ok(SignalPipe {
+            sys,
+            selector_id: SelectorId::new(),
+        })
+    }
+
+    /// Start receiving `signal`, installing a handler for it if this wasn't
+    /// done before.
+    ///
+    /// This can be done after `SignalPipe` is registered.
+    pub fn add(&mut self, signal: c_int) -> io::Result<()> {
+        self.sys.add(signal)
+    }
+
+    /// Stop receiving `signal`.
+    ///
+    /// The signal handler stays installed. This can be done after
+    /// `SignalPipe` is registered.
+    pub fn remove(&mut self, signal: c_int) -> io::Result<()> {
+        self.sys.remove(signal)
+    }
+
+    /// Returns `true` if `signal` is received by this `SignalPipe`.
+    pub fn contains(&self, signal: c_int) -> bool {
+        self.sys.contains(signal)
+    }
+
+    /// Receive the number of times each signal was delivered since the last
+    /// call, resetting the counts to 0.
+    ///
+    /// If no signals were delivered this returns a [`WouldBlock`] error, the
+    /// caller should then wait for another readiness event.
+    ///
+    /// [`WouldBlock`]: std::io::ErrorKind::WouldBlock
+    pub fn receive(&self) -> io::Result<SignalCounts> {
+        let mut counts = SignalCounts {
+            counts: [0; MAX_SIGNAL],
+        };
+        self.selector_id
+            .did_io(Interests::READABLE, self.sys.receive(&mut counts.counts))
+            .map(|()| counts)
+    }
+}
+
+impl Evented for SignalPipe {
+    fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        self.selector_id.associate_fd(registry, self.as_raw_fd())?;
+        self.sys.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &self,
+        registry: &Registry,
+        token: Token,
+        interests: Interests,
+    ) -> io::Result<()> {
+        self.sys.reregister(registry, token, interests)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.sys.deregister(registry)
+    }
+}
+
+impl fmt::Debug for SignalPipe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.sys, f)
+    }
+}
+
+impl Drop for SignalPipe {
+    fn drop(&mut self) {
+        self.selector_id.release();
+    }
+}
+
+impl AsRawFd for SignalPipe {
+    fn as_raw_fd(&self) -> RawFd {
+        self.sys.as_raw_fd()
+    }
+}
+
+/// The number of times each signal was delivered, see
+/// [`SignalPipe::receive`].
+pub struct SignalCounts {
+    counts: [usize; MAX_SIGNAL],
+}
+
+impl SignalCounts {
+    /// Returns the number of times `signal` was delivered.
+    pub fn get(&self, signal: c_int) -> usize {
+        if signal >= 0 {
+            self.counts.get(signal as usize).cloned().unwrap_or(0)
+        } else {
+            0
+        }
+    }
+
+    /// Returns an iterator over all delivered signals and their counts.
+    pub fn iter(&self) -> SignalCountsIter<'_> {
+        SignalCountsIter {
+            inner: self.counts.iter().enumerate(),
+        }
+    }
+}
+
+impl fmt::Debug for SignalCounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a> IntoIterator for &'a SignalCounts {
+    type Item = (c_int, usize);
+    type IntoIter = SignalCountsIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the delivered signals and their counts, see
+/// [`SignalCounts::iter`].
+#[derive(Debug)]
+pub struct SignalCountsIter<'a> {
+    inner: std::iter::Enumerate<std::slice::Iter<'a, usize>>,
+}
+
+impl<'a> Iterator for SignalCountsIter<'a> {
+    type Item = (c_int, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (signal, count) in self.inner.by_ref() {
+            if *count != 0 {
+                return Some((signal as c_int, *count));
+            }
+        }
+        None
+    }
+}
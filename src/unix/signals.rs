@@ -0,0 +1,210 @@
+use crate::event::Evented;
+use crate::poll::SelectorId;
+use crate::{sys, Interests, Registry, Token};
+
+use std::fmt;
+use std::io;
+use std::os::raw::c_int;
+use std::os::unix::io::{AsRawFd, IntoRawFd, RawFd};
+
+/// Receive signals as readiness events.
+///
+/// Creating `Signals` blocks the provided signals for the calling thread, so
+/// that they are no longer handled by their disposition (which, for most
+/// signals, terminates the process) but are queued for this source instead.
+/// Once a signal is queued `Signals` becomes [readable], after which the
+/// queued signals can be [received] one by one, without blocking.
+///
+/// Signals are blocked per thread. Threads inherit the signal mask of the
+/// thread that spawned them, so `Signals` should be created before spawning
+/// any other threads. A signal that is delivered to a thread that doesn't
+/// block it is handled by its disposition and won't be received. If that
+/// can't be guaranteed, e.g. because of threads started by other libraries,
+/// use [`SignalPipe`] instead.
+///
+/// [readable]: crate::event::Event::is_readable
+/// [received]: Signals::receive
+/// [`SignalPipe`]: crate::unix::SignalPipe
+///
+/// # Implementation notes
+///
+/// `Signals` is backed by [signalfd] and is only available on Linux and
+/// Android.
+///
+/// [signalfd]: http://man7.org/linux/man-pages/man2/signalfd.2.html
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use mio::unix::Signals;
+/// use mio::{Events, Interests, Poll, Token};
+///
+/// const SIGNAL: Token = Token(0);
+///
+/// let mut poll = Poll::new()?;
+/// let mut events = Events::with_capacity(8);
+///
+/// let signals = Signals::new(&[libc::SIGUSR1, libc::SIGTERM])?;
+/// poll.registry().register(&signals, SIGNAL, Interests::READABLE)?;
+///
+/// // Send ourselves a signal.
+/// unsafe { libc::raise(libc::SIGUSR1) };
+///
+/// poll.poll(&mut events, None)?;
+///
+/// for event in events.iter() {
+///     assert_eq!(event.token(), SIGNAL);
+///     let info = signals.receive()?;
+///     assert_eq!(info.signal(), libc::SIGUSR1);
+/// }
+/// #     Ok(())
+/// # }
+/// ```
+pub struct Signals {
+    sys: sys::Signals,
+    selector_id: SelectorId,
+}
+
+/// Information about a received signal, see [`Signals::receive`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SignalInfo {
+    signal: c_int,
+    code: i32,
+    pid: u32,
+    uid: u32,
+    status: i32,
+}
+
+impl Signals {
+    /// Create a new `Signals` receiving the provided `signals`, blocking them
+    /// for the calling thread.
+    ///
+    /// Note that `SIGKILL` and `SIGSTOP` can't be blocked, including either
+    /// doesn't return an error but they are never received.
+    pub fn new(signals: &[c_int]) -> io::Result<Signals> {
+        Ok(Signals {
+            sys: sys::Signals::new(signals)?,
+            selector_id: SelectorId::new(),
+        })
+    }
+
+    /// Start receiving `signal`, blocking it for the calling thread.
+    ///
+    /// This can be done after `Signals` is registered.
+    pub fn add(&mut self, signal: c_int) -> io::Result<()> {
+        self.sys.add(signal)
+    }
+
+    /// Stop receiving `signal`.
+    ///
+    /// The signal is **not** unblocked, as that could immediately run its
+    /// disposition for signals that were sent, but not yet received. This can
+    /// be done after `Signals` is registered.
+    pub fn remove(&mut self, signal: c_int) -> io::Result<()> {
+        self.sys.remove(signal)
+    }
+
+    /// Returns `true` if `signal` is received by this `Signals`.
+    pub fn contains(&self, signal: c_int) -> io::Result<bool> {
+        self.sys.contains(signal)
+    }
+
+    /// Receive a single queued signal.
+    ///
+    /// If no signal is queued this returns a [`WouldBlock`] error, the caller
+    /// should then wait for another readiness event. Standard signals are not
+    /// queued more than once, i.e. multiple deliveries of the same signal
+    /// before it's received are merged into one.
+    ///
+    /// [`WouldBlock`]: std::io::ErrorKind::WouldBlock
+    pub fn receive(&self) -> io::Result<SignalInfo> {
+        let info = self
+            .selector_id
+            .did_io(Interests::READABLE, self.sys.receive())?;
+        Ok(SignalInfo {
+            signal: info.ssi_signo as c_int,
+            code: info.ssi_code,
+            pid: info.ssi_pid,
+            uid: info.ssi_uid,
+            status: info.ssi_status,
+        })
+    }
+}
+
+impl SignalInfo {
+    /// The signal number, e.g. `SIGINT`.
+    pub fn signal(&self) -> c_int {
+        self.signal
+    }
+
+    /// The signal code, `si_code`, describing why the signal was sent. For
+    /// example `SI_USER` for signals sent using `kill(2)` or one of the `CLD_*`
+    /// codes for `SIGCHLD`.
+    pub fn code(&self) -> i32 {
+        self.code
+    }
+
+    /// The process id of the sender, or of the child process for `SIGCHLD`.
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// The real user id of the sender.
+    pub fn uid(&self) -> u32 {
+        self.uid
+    }
+
+    /// The exit status or signal of the child process for `SIGCHLD`, see
+    /// [`code`] to determine which.
+    ///
+    /// [`code`]: SignalInfo::code
+    pub fn status(&self) -> i32 {
+        self.status
+    }
+}
+
+impl Evented for Signals {
+    fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        self.selector_id.associate_fd(registry, self.as_raw_fd())?;
+        self.sys.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &self,
+        registry: &Registry,
+        token: Token,
+        interests: Interests,
+    ) -> io::Result<()> {
+        self.sys.reregister(registry, token, interests)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.sys.deregister(registry)
+    }
+}
+
+impl fmt::Debug for Signals {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.sys, f)
+    }
+}
+
+impl Drop for Signals {
+    fn drop(&mut self) {
+        self.selector_id.release();
+    }
+}
+
+impl IntoRawFd for Signals {
+    fn into_raw_fd(self) -> RawFd {
+        unsafe { crate::poll::into_sys(self, |signals| (&signals.selector_id, &signals.sys)) }
+            .into_raw_fd()
+    }
+}
+
+impl AsRawFd for Signals {
+    fn as_raw_fd(&self) -> RawFd {
+        self.sys.as_raw_fd()
+    }
+}
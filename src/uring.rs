@@ -0,0 +1,606 @@
+//! Completion based I/O using io_uring (Linux 5.13+).
+//!
+//! Readiness, as returned by [`Poll`], doesn't work for regular files: they're
+//! always readable and writable, yet reading or writing them may block. A
+//! [`Ring`] instead performs the operations asynchronously in the kernel and
+//! returns a [`Completion`] once an operation is done, carrying the result of
+//! the operation and the [`Token`] it was submitted with.
+//!
+//! A `Ring` implements [`Evented`] and is readable once completions are
+//! available, which makes it possible to wait for completions and readiness
+//! events using the same `Poll`.
+//!
+//! This module requires the `io_uring` feature.
+//!
+//! # Buffer ownership
+//!
+//! The kernel accesses the buffers of an operation until it completes, which
+//! may well be after the call that submitted it returns. To make this safe
+//! operations take ownership of their buffers, which are handed back in the
+//! `Completion`, e.g. by [`Completion::into_buf`]. Reads fill the spare
+//! capacity of the buffers, i.e. the bytes between their length and capacity,
+//! after which the length of the buffers is increased by the number of bytes
+//! read.
+//!
+//! Dropping a `Ring` cancels the operations in flight and waits until they're
+//! completed, only then are their buffers dropped.
+//!
+//! [`Poll`]: crate::Poll
+//! [`Evented`]: crate::event::Evented
+//!
+//! # Examples
+//!
+//! ```
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use mio::uring::{Completions, Ring};
+//! use mio::{Events, Interests, Poll, Token};
+//! use std::fs::OpenOptions;
+//! use std::os::unix::io::AsRawFd;
+//! # let dir = tempdir::TempDir::new("uring")?;
+//! # let path = dir.path().join("data");
+//!
+//! const RING: Token = Token(0);
+//! const WRITE: Token = Token(1);
+//! const READ: Token = Token(2);
+//!
+//! let file = OpenOptions::new().read(true).write(true).create(true).open(path)?;
+//!
+//! let mut poll = Poll::new()?;
+//! let mut events = Events::with_capacity(8);
+//! let ring = Ring::new(32)?;
+//! poll.registry().register(&ring, RING, Interests::READABLE)?;
+//!
+//! ring.write(file.as_raw_fd(), b"hello".to_vec(), Some(0), WRITE)?;
+//! ring.submit()?;
+//!
+//! let mut completions = Completions::with_capacity(8);
+//! let mut read = None;
+//! while read.is_none() {
+//!     poll.poll(&mut events, None)?;
+//!     ring.completions(&mut completions)?;
+//!     for completion in completions.drain() {
+//!         match completion.token() {
+//!             WRITE => {
+//!                 assert_eq!(completion.result()?, 5);
+//!                 // Read the data back, into a new buffer.
+//!                 ring.read(file.as_raw_fd(), Vec::with_capacity(16), Some(0), READ)?;
+//!                 ring.submit()?;
+//!             }
+//!             READ => read = completion.into_buf(),
+//!             _ => unreachable!(),
+//!         }
+//!     }
+//! }
+//! assert_eq!(read.unwrap(), b"hello");
+//! #     Ok(())
+//! # }
+//! ```
+
+use crate::event::Evented;
+use crate::poll::SelectorId;
+use crate::sys::unix::uring::{
+    self, Sqe, IORING_FSYNC_DATASYNC, IORING_OP_READ, IORING_OP_READV, IORING_OP_WRITE,
+};
+use crate::unix::EventedFd;
+use crate::{Interests, Registry, Token};
+
+use libc;
+use slab::Slab;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::sync::Mutex;
+use std::{cmp, fmt, io, mem, slice, vec};
+
+/// User data of cancellation requests, never used by operations.
+const CANCEL: u64 = u64::max_value();
+
+/// An io_uring instance performing I/O operations.
+///
+/// Operations are queued by methods such as [`read`] and [`write`], and
+/// submitted to the kernel by [`submit`], in a single system call. Once they
+/// complete they're returned by [`completions`].
+///
+/// See the [module documentation] for more.
+///
+/// [`read`]: Ring::read
+/// [`write`]: Ring::write
+/// [`submit`]: Ring::submit
+/// [`completions`]: Ring::completions
+/// [module documentation]: crate::uring
+///
+/// # Notes
+///
+/// The file descriptors passed to the operations must stay open until the
+/// operations are submitted, after which the kernel holds on to the file
+/// until the operation completes.
+pub struct Ring {
+    sys: uring::Ring,
+    /// Operations in flight, keyed by the user data of their requests.
+    ops: Mutex<Slab<Op>>,
+    selector_id: SelectorId,
+}
+
+struct Op {
+    token: Token,
+    buf: Buf,
+}
+
+/// Buffers owned by an operation in flight.
+enum Buf {
+    None,
+    /// No buffer, but the result is a file descriptor.
+    Accept,
+    Read(Vec<u8>),
+    Write(Vec<u8>),
+    Readv {
+        bufs: Vec<Vec<u8>>,
+        /// Point into the spare capacity of `bufs`, kept alive for the
+        /// kernel.
+        _iovecs: Vec<libc::iovec>,
+    },
+}
+
+// The pointers of the iovecs point into the buffers owned by the operation.
+unsafe impl Send for Buf {}
+
+impl Ring {
+    /// Create a new `Ring` with room for at least `entries` queued
+    /// operations.
+    ///
+    /// Returns an error if the kernel doesn't support io_uring, or the
+    /// features mio requires (Linux 5.13+).
+    pub fn new(entries: u32) -> io::Result<Ring> {
+        Ok(Ring {
+            sys: uring::Ring::new(entries)?,
+            ops: Mutex::new(Slab::new()),
+            selector_id: SelectorId::new(),
+        })
+    }
+
+    /// Queue a read from `fd` into the spare capacity of `buf`.
+    ///
+    /// Reads at `offset`, or at the file position (advancing it) if `offset`
+    /// is `None`, which must be used for files that don't support seeking,
+    /// such as pipes and sockets.
+    ///
+    /// The result of the completion is the number of bytes read, the buffer
+    /// is returned by [`Completion::into_buf`].
+    pub fn read(
+        &self,
+        fd: RawFd,
+        mut buf: Vec<u8>,
+        offset: Option<u64>,
+        token: Token,
+    ) -> io::Result<()> {
+        let (addr, len) = spare_capacity(&mut buf);
+        self.push(token, Buf::Read(buf), |user_data| {
+            Sqe::rw(
+                IORING_OP_READ,
+                fd,
+                addr,
+                len,
+                file_offset(offset),
+                user_data,
+            )
+        })
+    }
+
+    /// Queue a write of `buf` to `fd`.
+    ///
+    /// Writes at `offset`, or at the file position (advancing it) if `offset`
+    /// is `None`, see [`read`].
+    ///
+    /// The result of the completion is the number of bytes written, the
+    /// buffer is returned by [`Completion::into_buf`].
+    ///
+    /// [`read`]: Ring::read
+    pub fn write(
+        &self,
+        fd: RawFd,
+        buf: Vec<u8>,
+        offset: Option<u64>,
+        token: Token,
+    ) -> io::Result<()> {
+        let addr = buf.as_ptr() as u64;
+        let len = cmp::min(buf.len(), u32::max_value() as usize) as u32;
+        self.push(token, Buf::Write(buf), |user_data| {
+            Sqe::rw(
+                IORING_OP_WRITE,
+                fd,
+                addr,
+                len,
+                file_offset(offset),
+                user_data,
+            )
+        })
+    }
+
+    /// Queue a vectored read from `fd` into the spare capacity of `bufs`,
+    /// filling the buffers in order.
+    ///
+    /// See [`read`] for `offset`. The result of the completion is the total
+    /// number of bytes read, the buffers are returned by
+    /// [`Completion::into_bufs`].
+    ///
+    /// [`read`]: Ring::read
+    pub fn readv(
+        &self,
+        fd: RawFd,
+        mut bufs: Vec<Vec<u8>>,
+        offset: Option<u64>,
+        token: Token,
+    ) -> io::Result<()> {
+        let iovecs: Vec<libc::iovec> = bufs
+            .iter_mut()
+            .map(|buf| {
+                let (addr, len) = spare_capacity(buf);
+                libc::iovec {
+                    iov_base: addr as *mut libc::c_void,
+                    iov_len: len as usize,
+                }
+            })
+            .collect();
+        let addr = iovecs.as_ptr() as u64;
+        let len = iovecs.len() as u32;
+        self.push(
+            token,
+            Buf::Readv {
+                bufs,
+                _iovecs: iovecs,
+            },
+            |user_data| {
+                Sqe::rw(
+                    IORING_OP_READV,
+                    fd,
+                    addr,
+                    len,
+                    file_offset(offset),
+                    user_data,
+                )
+            },
+        )
+    }
+
+    /// Queue an `fsync(2)` of `fd`.
+    ///
+    /// Note that operations are not ordered, the fsync only covers the writes
+    /// that completed before it's submitted.
+    pub fn fsync(&self, fd: RawFd, token: Token) -> io::Result<()> {
+        self.push(token, Buf::None, |user_data| Sqe::fsync(fd, 0, user_data))
+    }
+
+    /// Queue an `fdatasync(2)` of `fd`, see [`fsync`].
+    ///
+    /// [`fsync`]: Ring::fsync
+    pub fn fdatasync(&self, fd: RawFd, token: Token) -> io::Result<()> {
+        self.push(token, Buf::None, |user_data| {
+            Sqe::fsync(fd, IORING_FSYNC_DATASYNC, user_data)
+        })
+    }
+
+    /// Queue an accept of a connection on the listening socket `fd`.
+    ///
+    /// The result of the completion is the file descriptor of the accepted
+    /// connection, which is non-blocking and has the close-on-exec flag set.
+    /// The caller takes ownership of it, e.g. by converting it into a
+    /// [`TcpStream`] using `FromRawFd`.
+    ///
+    /// [`TcpStream`]: crate::net::TcpStream
+    pub fn accept(&self, fd: RawFd, token: Token) -> io::Result<()> {
+        self.push(token, Buf::Accept, |user_data| {
+            Sqe::accept(fd, libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC, user_data)
+        })
+    }
+
+    /// Submit the queued operations to the kernel.
+    ///
+    /// Operations are also submitted if the queue is full when an operation
+    /// is queued.
+    pub fn submit(&self) -> io::Result<()> {
+        let sq = self.sys.sq();
+        self.sys.submit(sq.pending())
+    }
+
+    /// Move the completed operations into `completions`, as long as there is
+    /// room for them.
+    ///
+    /// Any completions already in `completions` are cleared. If
+    /// `completions` is full after this call more completions may be
+    /// available, which are returned by the next call.
+    pub fn completions(&self, completions: &mut Completions) -> io::Result<()> {
+        completions.inner.clear();
+        let mut ops = self.ops.lock().unwrap();
+        let mut cq = self.sys.cq();
+        let mut flushed = false;
+        while completions.inner.len() < completions.inner.capacity() {
+            let cqe = match cq.pop() {
+                Some(cqe) => cqe,
+                None if !flushed && self.sys.sq().cq_overflow() => {
+                    // Completions the completion queue had no room for.
+                    self.sys.submit_and_wait(0, 0, None)?;
+                    flushed = true;
+                    continue;
+                }
+                None => break,
+            };
+
+            if cqe.user_data == CANCEL || !ops.contains(cqe.user_data as usize) {
+                continue;
+            }
+            let op = ops.remove(cqe.user_data as usize);
+            completions.inner.push(Completion::new(op, cqe.res));
+        }
+        self.selector_id.did_io(Interests::READABLE, Ok(()))
+    }
+
+    /// Returns the number of operations queued or in flight, i.e. those that
+    /// haven't been returned by [`completions`] yet.
+    ///
+    /// [`completions`]: Ring::completions
+    pub fn in_flight(&self) -> usize {
+        self.ops.lock().unwrap().len()
+    }
+
+    /// Queue the request returned by `sqe` for the operation, which is passed
+    /// the user data of the request.
+    fn push<F>(&self, token: Token, buf: Buf, sqe: F) -> io::Result<()>
+    where
+        F: FnOnce(u64) -> Sqe,
+    {
+        let mut ops = self.ops.lock().unwrap();
+        let entry = ops.vacant_entry();
+        let sqe = sqe(entry.key() as u64);
+        push(&self.sys, &sqe)?;
+        entry.insert(Op { token, buf });
+        Ok(())
+    }
+}
+
+/// Add `sqe` to the submission queue, submitting the queue if it's full.
+fn push(ring: &uring::Ring, sqe: &Sqe) -> io::Result<()> {
+    let mut sq = ring.sq();
+    if !sq.push(sqe) {
+        ring.submit(sq.pending())?;
+        if !sq.push(sqe) {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "io_uring submission queue is full",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Returns the address and length of the spare capacity of `buf`.
+fn spare_capacity(buf: &mut Vec<u8>) -> (u64, u32) {
+    let len = cmp::min(buf.capacity() - buf.len(), u32::max_value() as usize);
+    let addr = unsafe { buf.as_mut_ptr().add(buf.len()) };
+    (addr as u64, len as u32)
+}
+
+/// `u64::MAX` uses the file position.
+fn file_offset(offset: Option<u64>) -> u64 {
+    offset.unwrap_or(u64::max_value())
+}
+
+impl Evented for Ring {
+    fn register(&self, registry: &Registry, token: Token, interests: Interests) -> io::Result<()> {
+        self.selector_id
+            .associate_fd(registry, self.sys.as_raw_fd())?;
+        EventedFd(&self.sys.as_raw_fd()).register(registry, token, interests)
+    }
+
+    fn reregister(
+        &self,
+        registry: &Registry,
+        token: Token,
+        interests: Interests,
+    ) -> io::Result<()> {
+        EventedFd(&self.sys.as_raw_fd()).reregister(registry, token, interests)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        EventedFd(&self.sys.as_raw_fd()).deregister(registry)
+    }
+}
+
+impl AsRawFd for Ring {
+    fn as_raw_fd(&self) -> RawFd {
+        self.sys.as_raw_fd()
+    }
+}
+
+impl fmt::Debug for Ring {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ring")
+            .field("fd", &self.sys.as_raw_fd())
+            .field("in_flight", &self.in_flight())
+            .finish()
+    }
+}
+
+impl Drop for Ring {
+    fn drop(&mut self) {
+        self.selector_id.release();
+        let ops = self.ops.get_mut().unwrap();
+        // The kernel may still access the buffers of the operations in flight,
+        // so they can only be dropped once the operations are completed.
+        for (key, _) in ops.iter() {
+            if push(&self.sys, &Sqe::async_cancel(key as u64, CANCEL)).is_err() {
+                break;
+            }
+        }
+        while !ops.is_empty() {
+            let to_submit = self.sys.sq().pending();
+            match self.sys.submit_and_wait(to_submit, 1, None) {
+                Ok(()) => {}
+                Err(ref err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(_) => {
+                    // Leaking the buffers is the only safe option left.
+                    mem::forget(mem::replace(ops, Slab::new()));
+                    return;
+                }
+            }
+            let mut cq = self.sys.cq();
+            while let Some(cqe) = cq.pop() {
+                if cqe.user_data != CANCEL && ops.contains(cqe.user_data as usize) {
+                    if cqe.res >= 0 {
+                        if let Buf::Accept = ops[cqe.user_data as usize].buf {
+                            // An accepted connection, owned by us.
+                            unsafe {
+                                let _ = libc::close(cqe.res);
+                            }
+                        }
+                    }
+                    ops.remove(cqe.user_data as usize);
+                }
+            }
+        }
+    }
+}
+
+/// A completed operation, returned by [`Ring::completions`].
+pub struct Completion {
+    token: Token,
+    res: i32,
+    buf: Option<Vec<u8>>,
+    bufs: Option<Vec<Vec<u8>>>,
+}
+
+impl Completion {
+    fn new(op: Op, res: i32) -> Completion {
+        let mut n = if res > 0 { res as usize } else { 0 };
+        let (buf, bufs) = match op.buf {
+            Buf::None | Buf::Accept => (None, None),
+            Buf::Read(mut buf) => {
+                // The kernel initialised the first `n` bytes of the spare
+                // capacity.
+                unsafe { buf.set_len(buf.len() + n) };
+                (Some(buf), None)
+            }
+            Buf::Write(buf) => (Some(buf), None),
+            Buf::Readv { mut bufs, .. } => {
+                for buf in bufs.iter_mut() {
+                    let read = cmp::min(n, buf.capacity() - buf.len());
+                    unsafe { buf.set_len(buf.len() + read) };
+                    n -= read;
+                }
+                (None, Some(bufs))
+            }
+        };
+        Completion {
+            token: op.token,
+            res,
+            buf,
+            bufs,
+        }
+    }
+
+    /// Returns the token the operation was submitted with.
+    pub fn token(&self) -> Token {
+        self.token
+    }
+
+    /// Returns the result of the operation.
+    ///
+    /// What the value means depends on the operation, e.g. the number of
+    /// bytes read by [`Ring::read`].
+    pub fn result(&self) -> io::Result<usize> {
+        if self.res < 0 {
+            Err(io::Error::from_raw_os_error(-self.res))
+        } else {
+            Ok(self.res as usize)
+        }
+    }
+
+    /// Returns the buffer of a read or write operation.
+    pub fn buf(&self) -> Option<&[u8]> {
+        self.buf.as_ref().map(|buf| &**buf)
+    }
+
+    /// Returns the buffer of a read or write operation, taking ownership of
+    /// it.
+    pub fn into_buf(self) -> Option<Vec<u8>> {
+        self.buf
+    }
+
+    /// Returns the buffers of a vectored read operation.
+    pub fn bufs(&self) -> Option<&[Vec<u8>]> {
+        self.bufs.as_ref().map(|bufs| &**bufs)
+    }
+
+    /// Returns the buffers of a vectored read operation, taking ownership of
+    /// them.
+    pub fn into_bufs(self) -> Option<Vec<Vec<u8>>> {
+        self.bufs
+    }
+}
+
+impl fmt::Debug for Completion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Completion")
+            .field("token", &self.token)
+            .field("result", &self.result())
+            .finish()
+    }
+}
+
+/// A collection of [`Completion`]s, filled by [`Ring::completions`].
+///
+/// Like [`Events`], `Completions` is usually created once and reused for each
+/// call to [`Ring::completions`].
+///
+/// [`Events`]: crate::Events
+#[derive(Debug)]
+pub struct Completions {
+    inner: Vec<Completion>,
+}
+
+impl Completions {
+    /// Return a new `Completions` capable of holding up to `capacity`
+    /// completions.
+    pub fn with_capacity(capacity: usize) -> Completions {
+        Completions {
+            inner: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the number of completions `self` can hold.
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    /// Returns the number of completions.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if `self` contains no completions.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Returns an iterator over the completions.
+    pub fn iter(&self) -> slice::Iter<'_, Completion> {
+        self.inner.iter()
+    }
+
+    /// Returns an iterator removing the completions, taking ownership of
+    /// their buffers.
+    pub fn drain(&mut self) -> vec::Drain<'_, Completion> {
+        self.inner.drain(..)
+    }
+
+    /// Clear all completions.
+    pub fn clear(&mut self) {
+        self.inner.clear();
+    }
+}
+
+impl<'a> IntoIterator for &'a Completions {
+    type Item = &'a Completion;
+    type IntoIter = slice::Iter<'a, Completion>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
@@ -3,22 +3,63 @@ extern crate log;
 
 pub use ports::localhost;
 
+mod test_channel;
+#[cfg(unix)]
+mod test_child;
 mod test_close_on_drop;
 mod test_double_register;
 mod test_echo_server;
 mod test_evented;
+#[cfg(all(unix, feature = "fault"))]
+mod test_fault;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod test_inotify;
 mod test_interests;
 mod test_local_addr_ready;
 mod test_multicast;
+mod test_oneshot;
+#[cfg(unix)]
+mod test_pipe;
 mod test_poll;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod test_process;
+#[cfg(all(unix, feature = "record"))]
+mod test_record;
 mod test_register_deregister;
 mod test_register_multiple_event_loops;
+mod test_registration;
 mod test_reregister_without_poll;
+#[cfg(unix)]
+mod test_selector;
+#[cfg(unix)]
+mod test_signal_pipe;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod test_signals;
+#[cfg(all(unix, feature = "sim"))]
+mod test_sim;
+#[cfg(all(unix, feature = "sim"))]
+mod test_sim_net;
 mod test_smoke;
 mod test_tcp;
 mod test_tcp_shutdown;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod test_timer;
+mod test_trigger;
 mod test_udp_socket;
+#[cfg(unix)]
+mod test_uds;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod test_uds_cred;
+#[cfg(unix)]
+mod test_uds_datagram;
+#[cfg(unix)]
+mod test_uds_fds;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod test_uds_seqpacket;
+#[cfg(all(feature = "io_uring", any(target_os = "linux", target_os = "android")))]
+mod test_uring;
 mod test_waker;
+mod test_wheel;
 mod test_write_then_drop;
 
 use bytes::{Buf, BufMut};
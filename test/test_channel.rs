@@ -0,0 +1,163 @@
+use std::thread;
+use std::time::Duration;
+
+use mio::channel::{self, SendError, TryRecvError, TrySendError};
+use mio::{Events, Interests, Poll, Token};
+
+use super::expect_no_events;
+
+const RECEIVER: Token = Token(1);
+
+#[test]
+fn channel_smoke() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let (sender, receiver) = channel::channel();
+    poll.registry()
+        .register(&receiver, RECEIVER, Interests::READABLE)
+        .unwrap();
+    expect_no_events(&mut poll, &mut events);
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+
+    sender.send("hello").unwrap();
+    expect_receiver_event(&mut poll, &mut events);
+    assert_eq!(receiver.try_recv(), Ok("hello"));
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+    expect_no_events(&mut poll, &mut events);
+
+    sender.send("world").unwrap();
+    expect_receiver_event(&mut poll, &mut events);
+    assert_eq!(receiver.try_recv(), Ok("world"));
+}
+
+#[test]
+fn channel_coalesced_events() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let (sender, receiver) = channel::channel();
+    poll.registry()
+        .register(&receiver, RECEIVER, Interests::READABLE)
+        .unwrap();
+
+    let sender2 = sender.clone();
+    for n in 0..10 {
+        sender.send(n).unwrap();
+        sender2.send(n + 10).unwrap();
+    }
+    expect_receiver_event(&mut poll, &mut events);
+    assert_eq!(events.iter().count(), 1);
+
+    let mut received: Vec<_> = (0..20).map(|_| receiver.try_recv().unwrap()).collect();
+    received.sort();
+    assert_eq!(received, (0..20).collect::<Vec<_>>());
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+    expect_no_events(&mut poll, &mut events);
+}
+
+#[test]
+fn channel_from_other_thread() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let (sender, receiver) = channel::channel();
+    poll.registry()
+        .register(&receiver, RECEIVER, Interests::READABLE)
+        .unwrap();
+
+    let handle = thread::spawn(move || {
+        for n in 0..100 {
+            sender.send(n).unwrap();
+        }
+    });
+
+    let mut received = Vec::new();
+    loop {
+        poll.poll(&mut events, Some(Duration::from_secs(1)))
+            .unwrap();
+        assert!(!events.is_empty(), "expected receiver event");
+        loop {
+            match receiver.try_recv() {
+                Ok(n) => received.push(n),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    assert_eq!(received, (0..100).collect::<Vec<_>>());
+                    handle.join().unwrap();
+                    return;
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn channel_disconnected() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let (sender, receiver) = channel::channel::<usize>();
+    poll.registry()
+        .register(&receiver, RECEIVER, Interests::READABLE)
+        .unwrap();
+
+    let sender2 = sender.clone();
+    drop(sender);
+    expect_no_events(&mut poll, &mut events);
+    drop(sender2);
+    expect_receiver_event(&mut poll, &mut events);
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+
+    let (sender, receiver) = channel::channel();
+    drop(receiver);
+    match sender.send(1) {
+        Err(SendError::Disconnected(1)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn sync_channel_backpressure() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let (sender, receiver) = channel::sync_channel(2);
+    poll.registry()
+        .register(&receiver, RECEIVER, Interests::READABLE)
+        .unwrap();
+
+    sender.try_send(1).unwrap();
+    sender.send(2).unwrap();
+    match sender.try_send(3) {
+        Err(TrySendError::Full(3)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    expect_receiver_event(&mut poll, &mut events);
+
+    assert_eq!(receiver.try_recv(), Ok(1));
+    sender.try_send(3).unwrap();
+    assert_eq!(receiver.try_recv(), Ok(2));
+    assert_eq!(receiver.try_recv(), Ok(3));
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+
+    drop(receiver);
+    match sender.try_send(4) {
+        Err(TrySendError::Disconnected(4)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+#[should_panic(expected = "bound of zero")]
+fn sync_channel_zero_bound() {
+    let _ = channel::sync_channel::<usize>(0);
+}
+
+fn expect_receiver_event(poll: &mut Poll, events: &mut Events) {
+    poll.poll(events, Some(Duration::from_millis(500))).unwrap();
+    assert!(!events.is_empty(), "expected receiver event");
+    for event in events.iter() {
+        assert_eq!(event.token(), RECEIVER);
+        assert!(event.is_readable());
+    }
+}
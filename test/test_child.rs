@@ -0,0 +1,104 @@
+use std::io::{self, Read, Write};
+use std::process::Command;
+use std::time::Duration;
+
+use mio::unix::CommandExt;
+use mio::{Events, Interests, Poll, Token};
+
+use super::expect_no_events;
+
+const STDIN: Token = Token(0);
+const STDOUT: Token = Token(1);
+const STDERR: Token = Token(2);
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const PROCESS: Token = Token(3);
+
+#[test]
+fn child_stdio() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let mut child = Command::new("cat").spawn_evented().unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    let mut stdout = child.stdout.take().unwrap();
+    poll.registry()
+        .register(&stdin, STDIN, Interests::WRITABLE)
+        .unwrap();
+    poll.registry()
+        .register(&stdout, STDOUT, Interests::READABLE)
+        .unwrap();
+    expect_event(&mut poll, &mut events, STDIN);
+
+    let mut buf = [0; 16];
+    assert_would_block(stdout.read(&mut buf));
+
+    stdin.write_all(b"Hello").unwrap();
+    expect_event(&mut poll, &mut events, STDOUT);
+    assert_eq!(stdout.read(&mut buf).unwrap(), 5);
+    assert_eq!(&buf[..5], b"Hello");
+
+    // Closing stdin makes `cat` exit, closing its stdout.
+    drop(stdin);
+    expect_event(&mut poll, &mut events, STDOUT);
+    assert_eq!(stdout.read(&mut buf).unwrap(), 0);
+    assert!(child.wait().unwrap().success());
+}
+
+#[test]
+fn child_stderr() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg("echo Hello >&2")
+        .spawn_evented()
+        .unwrap();
+    let mut stderr = child.stderr.take().unwrap();
+    poll.registry()
+        .register(&stderr, STDERR, Interests::READABLE)
+        .unwrap();
+
+    expect_event(&mut poll, &mut events, STDERR);
+    let mut buf = [0; 16];
+    assert_eq!(stderr.read(&mut buf).unwrap(), 6);
+    assert_eq!(&buf[..6], b"Hello\n");
+    assert!(child.wait().unwrap().success());
+}
+
+#[test]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn child_exit() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let mut child = Command::new("sleep").arg("10").spawn_evented().unwrap();
+    poll.registry()
+        .register(child.process(), PROCESS, Interests::READABLE)
+        .unwrap();
+    expect_no_events(&mut poll, &mut events);
+    assert!(child.try_wait().unwrap().is_none());
+
+    child.kill().unwrap();
+    expect_event(&mut poll, &mut events, PROCESS);
+    let status = child.try_wait().unwrap().unwrap();
+    assert!(!status.success());
+    // The exit status can be collected again.
+    assert_eq!(child.try_wait().unwrap(), Some(status));
+}
+
+fn expect_event(poll: &mut Poll, events: &mut Events, token: Token) {
+    poll.poll(events, Some(Duration::from_secs(5))).unwrap();
+    assert!(
+        events.iter().any(|event| event.token() == token),
+        "expected event for {:?}",
+        token
+    );
+}
+
+fn assert_would_block<T: std::fmt::Debug>(result: io::Result<T>) {
+    match result {
+        Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {}
+        other => panic!("expected WouldBlock error, got: {:?}", other),
+    }
+}
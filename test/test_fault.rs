@@ -0,0 +1,236 @@
+use std::io::{self, Read, Write};
+use std::mem;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use mio::fault::{self, Fault, Operation, Policy, Random};
+use mio::net::{TcpListener, TcpStream, UdpSocket};
+use mio::{Events, Interests, Poll, Token};
+
+const ID1: Token = Token(1);
+const ID2: Token = Token(2);
+
+/// A policy injecting the faults pushed to the shared queue, in order, into
+/// the operations they're queued for.
+#[derive(Clone)]
+struct Script {
+    faults: Arc<Mutex<Vec<(Operation, Fault)>>>,
+}
+
+impl Script {
+    fn new() -> Script {
+        Script {
+            faults: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    fn push(&self, operation: Operation, fault: Fault) {
+        self.faults.lock().unwrap().push((operation, fault));
+    }
+}
+
+impl Policy for Script {
+    fn fault(&mut self, operation: Operation) -> Option<Fault> {
+        let mut faults = self.faults.lock().unwrap();
+        // The lengths of reads and writes are ignored.
+        let matches = match faults.first() {
+            Some(&(expected, _)) => mem::discriminant(&expected) == mem::discriminant(&operation),
+            None => false,
+        };
+        if matches {
+            Some(faults.remove(0).1)
+        } else {
+            None
+        }
+    }
+}
+
+fn setup() -> (Script, Poll, Events) {
+    let script = Script::new();
+    let poll = Poll::with_selector(fault::Selector::new(script.clone()).unwrap());
+    (script, poll, Events::with_capacity(16))
+}
+
+/// Returns a connected pair of streams, `ID1` registered for writable and
+/// `ID2` for readable events.
+fn pair(poll: &mut Poll, events: &mut Events) -> (TcpStream, TcpStream) {
+    let listener = TcpListener::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+    let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    poll.registry()
+        .register(&client, ID1, Interests::WRITABLE)
+        .unwrap();
+    let server = loop {
+        match listener.accept() {
+            Ok((server, _)) => break server,
+            Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {}
+            Err(err) => panic!("unexpected error: {}", err),
+        }
+    };
+    poll.registry()
+        .register(&server, ID2, Interests::READABLE)
+        .unwrap();
+    expect(poll, events, ID1);
+    (client, server)
+}
+
+/// Poll until an event with `token` is returned.
+fn expect(poll: &mut Poll, events: &mut Events, token: Token) {
+    for _ in 0..10 {
+        poll.poll(events, Some(Duration::from_millis(500))).unwrap();
+        if events.iter().any(|event| event.token() == token) {
+            return;
+        }
+    }
+    panic!("no event for {:?}", token);
+}
+
+fn kind<T>(result: io::Result<T>) -> io::ErrorKind {
+    match result {
+        Ok(_) => panic!("unexpected success"),
+        Err(err) => err.kind(),
+    }
+}
+
+#[test]
+fn fault_registrations() {
+    let (script, poll, _) = setup();
+    let socket = UdpSocket::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+
+    script.push(Operation::Register, Fault::Error(libc::ENOSPC));
+    let err = poll
+        .registry()
+        .register(&socket, ID1, Interests::READABLE)
+        .unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::ENOSPC));
+    poll.registry()
+        .register(&socket, ID1, Interests::READABLE)
+        .unwrap();
+
+    script.push(Operation::Reregister, Fault::Error(libc::ENOMEM));
+    assert!(poll
+        .registry()
+        .reregister(&socket, ID1, Interests::WRITABLE)
+        .is_err());
+    script.push(Operation::Deregister, Fault::Error(libc::ENOMEM));
+    assert!(poll.registry().deregister(&socket).is_err());
+    poll.registry().deregister(&socket).unwrap();
+}
+
+#[test]
+fn fault_poll_interrupted() {
+    let (script, mut poll, mut events) = setup();
+    let timeout = Some(Duration::from_millis(0));
+    script.push(Operation::Poll, Fault::Error(libc::EINTR));
+    let result = poll.poll_interruptible(&mut events, timeout);
+    assert_eq!(kind(result), io::ErrorKind::Interrupted);
+
+    // `poll` retries.
+    script.push(Operation::Poll, Fault::Error(libc::EINTR));
+    poll.poll(&mut events, timeout).unwrap();
+    assert!(script.faults.lock().unwrap().is_empty());
+}
+
+#[test]
+fn fault_spurious_wakeup() {
+    let (script, mut poll, mut events) = setup();
+    let listener = TcpListener::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+    poll.registry()
+        .register(&listener, ID1, Interests::READABLE)
+        .unwrap();
+
+    // Returns immediately, even without a timeout.
+    script.push(Operation::Poll, Fault::Spurious);
+    poll.poll(&mut events, None).unwrap();
+    let event = events.iter().next().unwrap();
+    assert_eq!(event.token(), ID1);
+    assert!(event.is_readable());
+    assert_eq!(kind(listener.accept()), io::ErrorKind::WouldBlock);
+}
+
+#[test]
+fn fault_short_io() {
+    let (script, mut poll, mut events) = setup();
+    let (mut client, mut server) = pair(&mut poll, &mut events);
+
+    script.push(Operation::Write(0), Fault::Short(3));
+    assert_eq!(client.write(b"hello world").unwrap(), 3);
+    client.write_all(b"lo world").unwrap();
+
+    expect(&mut poll, &mut events, ID2);
+    script.push(Operation::Read(0), Fault::Short(5));
+    let mut buf = [0; 16];
+    assert_eq!(server.read(&mut buf).unwrap(), 5);
+    assert_eq!(&buf[..5], b"hello");
+}
+
+#[test]
+fn fault_io_errors() {
+    let (script, mut poll, mut events) = setup();
+    let (mut client, mut server) = pair(&mut poll, &mut events);
+
+    script.push(Operation::Write(0), Fault::Error(libc::EINTR));
+    assert_eq!(kind(client.write(b"hello")), io::ErrorKind::Interrupted);
+    client.write_all(b"hello").unwrap();
+    expect(&mut poll, &mut events, ID2);
+
+    // The data is ready, but the read returns `WouldBlock`. An event follows,
+    // so that the data isn't missed.
+    script.push(Operation::Read(0), Fault::Error(libc::EAGAIN));
+    let mut buf = [0; 16];
+    assert_eq!(kind(server.read(&mut buf)), io::ErrorKind::WouldBlock);
+    poll.poll(&mut events, None).unwrap();
+    assert_eq!(events.iter().next().unwrap().token(), ID2);
+    assert_eq!(server.read(&mut buf).unwrap(), 5);
+}
+
+#[test]
+fn fault_unregistered_handles() {
+    let (script, _, _) = setup();
+    let socket1 = UdpSocket::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+    let socket2 = UdpSocket::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+
+    // Handles that aren't registered don't know the fault selector.
+    script.push(Operation::Send, Fault::Error(libc::EINTR));
+    socket1
+        .send_to(b"hello", socket2.local_addr().unwrap())
+        .unwrap();
+}
+
+#[test]
+fn fault_random_is_reproducible() {
+    fn faults(seed: u64) -> Vec<Option<Fault>> {
+        let mut policy = Random::new(seed);
+        policy.set_spurious(0.2);
+        policy.set_interrupt(0.2);
+        policy.set_would_block(0.2);
+        policy.set_short(0.2);
+        policy.set_register_error(0.2);
+        let operations = [
+            Operation::Register,
+            Operation::Poll,
+            Operation::Read(100),
+            Operation::Write(100),
+            Operation::Recv,
+        ];
+        (0..100)
+            .map(|n| policy.fault(operations[n % operations.len()]))
+            .collect()
+    }
+
+    let injected = faults(1);
+    assert_eq!(faults(1), injected);
+    assert_ne!(faults(2), injected);
+    for fault in injected {
+        match fault {
+            Some(Fault::Short(n)) => assert!(n > 0 && n < 100),
+            Some(Fault::Error(errno)) => {
+                assert!(errno == libc::ENOMEM || errno == libc::EINTR || errno == libc::EAGAIN)
+            }
+            Some(Fault::Spurious) | None => {}
+        }
+    }
+
+    // Without probabilities no faults are injected.
+    let mut policy = Random::new(1);
+    assert_eq!(policy.fault(Operation::Poll), None);
+}
@@ -0,0 +1,150 @@
+use std::fs::{self, File};
+use std::io;
+use std::time::Duration;
+
+use mio::unix::inotify::{EventMask, InotifyEvents, WatchMask};
+use mio::unix::Inotify;
+use mio::{Events, Interests, Poll, Token};
+use tempdir::TempDir;
+
+use super::expect_no_events;
+
+const INOTIFY: Token = Token(10);
+
+#[test]
+fn inotify_create_delete() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+    let dir = TempDir::new("inotify").unwrap();
+
+    let inotify = Inotify::new().unwrap();
+    let wd = inotify
+        .add_watch(dir.path(), WatchMask::CREATE | WatchMask::DELETE)
+        .unwrap();
+    poll.registry()
+        .register(&inotify, INOTIFY, Interests::READABLE)
+        .unwrap();
+    let mut buf = [0; Inotify::MIN_BUFFER_SIZE];
+    assert_would_block(inotify.read_events(&mut buf));
+
+    let path = dir.path().join("file");
+    File::create(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    expect_inotify_event(&mut poll, &mut events);
+
+    let received: Vec<_> = inotify.read_events(&mut buf).unwrap().collect();
+    assert_eq!(received.len(), 2);
+    assert_eq!(received[0].wd(), Some(wd));
+    assert_eq!(received[0].mask(), EventMask::CREATE);
+    assert_eq!(received[0].name().unwrap(), "file");
+    assert_eq!(received[1].wd(), Some(wd));
+    assert_eq!(received[1].mask(), EventMask::DELETE);
+    assert_eq!(received[1].name().unwrap(), "file");
+    assert_would_block(inotify.read_events(&mut buf));
+}
+
+#[test]
+fn inotify_remove_watch() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+    let dir = TempDir::new("inotify").unwrap();
+
+    let inotify = Inotify::new().unwrap();
+    let wd = inotify.add_watch(dir.path(), WatchMask::CREATE).unwrap();
+    poll.registry()
+        .register(&inotify, INOTIFY, Interests::READABLE)
+        .unwrap();
+
+    inotify.remove_watch(wd).unwrap();
+    expect_inotify_event(&mut poll, &mut events);
+    let mut buf = [0; Inotify::MIN_BUFFER_SIZE];
+    let mut received = inotify.read_events(&mut buf).unwrap();
+    let event = received.next().unwrap();
+    assert_eq!(event.wd(), Some(wd));
+    assert!(event.mask().contains(EventMask::IGNORED));
+    assert!(received.next().is_none());
+
+    File::create(dir.path().join("file")).unwrap();
+    expect_no_events(&mut poll, &mut events);
+}
+
+#[test]
+fn inotify_buffer_too_small() {
+    let dir = TempDir::new("inotify").unwrap();
+
+    let inotify = Inotify::new().unwrap();
+    inotify.add_watch(dir.path(), WatchMask::CREATE).unwrap();
+    File::create(dir.path().join("file")).unwrap();
+
+    // Room for the header, but not the name.
+    let mut buf = [0; 16];
+    let err = inotify.read_events(&mut buf).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+    // The event is still queued.
+    let mut buf = [0; Inotify::MIN_BUFFER_SIZE];
+    assert_eq!(inotify.read_events(&mut buf).unwrap().count(), 1);
+}
+
+#[test]
+fn inotify_events_decode() {
+    let mut buf = Vec::new();
+    push_event(&mut buf, 1, libc::IN_MOVED_FROM, 7, b"a\0\0\0");
+    push_event(&mut buf, -1, libc::IN_Q_OVERFLOW, 0, b"");
+    push_event(&mut buf, 1, libc::IN_MOVED_TO, 7, b"bb\0\0");
+
+    let received: Vec<_> = InotifyEvents::new(&buf).collect();
+    assert_eq!(received.len(), 3);
+    assert_eq!(received[0].mask(), EventMask::MOVED_FROM);
+    assert_eq!(received[0].cookie(), 7);
+    assert_eq!(received[0].name().unwrap(), "a");
+    assert!(!received[0].is_overflow());
+    assert_eq!(received[1].wd(), None);
+    assert_eq!(received[1].name(), None);
+    assert!(received[1].is_overflow());
+    assert_eq!(received[2].mask(), EventMask::MOVED_TO);
+    assert_eq!(received[2].wd(), received[0].wd());
+    assert_eq!(received[2].name().unwrap(), "bb");
+
+    // An incomplete event, in either the header or the name, is ignored.
+    for len in &[buf.len() - 1, buf.len() - 5, buf.len() - 17] {
+        assert_eq!(InotifyEvents::new(&buf[..*len]).count(), 2);
+    }
+}
+
+#[test]
+fn inotify_mask_debug() {
+    assert_eq!(format!("{:?}", WatchMask::CREATE), "CREATE");
+    assert_eq!(
+        format!("{:?}", WatchMask::MOVE | WatchMask::ONLYDIR),
+        "MOVED_FROM | MOVED_TO | ONLYDIR"
+    );
+    assert_eq!(
+        format!("{:?}", EventMask::CREATE | EventMask::ISDIR),
+        "CREATE | ISDIR"
+    );
+}
+
+fn push_event(buf: &mut Vec<u8>, wd: i32, mask: u32, cookie: u32, name: &[u8]) {
+    buf.extend_from_slice(&wd.to_ne_bytes());
+    buf.extend_from_slice(&mask.to_ne_bytes());
+    buf.extend_from_slice(&cookie.to_ne_bytes());
+    buf.extend_from_slice(&(name.len() as u32).to_ne_bytes());
+    buf.extend_from_slice(name);
+}
+
+fn expect_inotify_event(poll: &mut Poll, events: &mut Events) {
+    poll.poll(events, Some(Duration::from_millis(500))).unwrap();
+    assert!(!events.is_empty(), "expected inotify event");
+    for event in events.iter() {
+        assert_eq!(event.token(), INOTIFY);
+        assert!(event.is_readable());
+    }
+}
+
+fn assert_would_block<T: std::fmt::Debug>(result: io::Result<T>) {
+    match result {
+        Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {}
+        other => panic!("expected WouldBlock error, got: {:?}", other),
+    }
+}
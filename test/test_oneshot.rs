@@ -0,0 +1,174 @@
+use std::io::{Read, Write};
+use std::net;
+use std::time::Duration;
+
+use mio::net::{TcpListener, TcpStream, UdpSocket};
+use mio::{Events, Interests, Poll, Token, Trigger};
+
+use super::expect_no_events;
+
+const ID: Token = Token(1);
+
+#[test]
+fn oneshot_tcp_stream() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (mut peer, _) = listener.accept().unwrap();
+    poll.registry()
+        .register_with(&stream, ID, Interests::READABLE, Trigger::Oneshot)
+        .unwrap();
+
+    peer.write_all(b"Hello").unwrap();
+    expect_event(&mut poll, &mut events);
+
+    // Neither unread data nor new data returns another event.
+    expect_no_events(&mut poll, &mut events);
+    peer.write_all(b" world").unwrap();
+    expect_no_events(&mut poll, &mut events);
+
+    // The stream is still ready, so rearming returns an event right away.
+    poll.registry()
+        .reregister_with(&stream, ID, Interests::READABLE, Trigger::Oneshot)
+        .unwrap();
+    expect_event(&mut poll, &mut events);
+    expect_no_events(&mut poll, &mut events);
+
+    let mut buf = [0; 16];
+    assert_eq!(stream.read(&mut buf).unwrap(), 11);
+
+    // Once rearmed new data returns an event again.
+    poll.registry()
+        .reregister_with(&stream, ID, Interests::READABLE, Trigger::Oneshot)
+        .unwrap();
+    expect_no_events(&mut poll, &mut events);
+    peer.write_all(b"!").unwrap();
+    expect_event(&mut poll, &mut events);
+    expect_no_events(&mut poll, &mut events);
+}
+
+#[test]
+fn oneshot_tcp_stream_writable() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let _peer = listener.accept().unwrap();
+    poll.registry()
+        .register_with(
+            &stream,
+            ID,
+            Interests::READABLE | Interests::WRITABLE,
+            Trigger::Oneshot,
+        )
+        .unwrap();
+
+    // Writable, but not readable as nothing was written. So even on kqueue
+    // platforms, where the readable interest stays enabled, no other event is
+    // returned.
+    expect_event(&mut poll, &mut events);
+    expect_no_events(&mut poll, &mut events);
+}
+
+#[test]
+fn oneshot_tcp_listener() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let listener = TcpListener::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+    let addr = listener.local_addr().unwrap();
+    poll.registry()
+        .register_with(&listener, ID, Interests::READABLE, Trigger::Oneshot)
+        .unwrap();
+
+    let _stream1 = net::TcpStream::connect(addr).unwrap();
+    expect_event(&mut poll, &mut events);
+    let _stream2 = net::TcpStream::connect(addr).unwrap();
+    expect_no_events(&mut poll, &mut events);
+
+    listener.accept().unwrap();
+    listener.accept().unwrap();
+
+    poll.registry()
+        .reregister_with(&listener, ID, Interests::READABLE, Trigger::Oneshot)
+        .unwrap();
+    expect_no_events(&mut poll, &mut events);
+    let _stream3 = net::TcpStream::connect(addr).unwrap();
+    expect_event(&mut poll, &mut events);
+}
+
+#[test]
+fn oneshot_udp_socket() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let any_local_address = "127.0.0.1:0".parse().unwrap();
+    let socket = UdpSocket::bind(any_local_address).unwrap();
+    let sender = net::UdpSocket::bind(any_local_address).unwrap();
+    let addr = socket.local_addr().unwrap();
+    poll.registry()
+        .register_with(&socket, ID, Interests::READABLE, Trigger::Oneshot)
+        .unwrap();
+
+    sender.send_to(b"first", addr).unwrap();
+    expect_event(&mut poll, &mut events);
+    sender.send_to(b"second", addr).unwrap();
+    expect_no_events(&mut poll, &mut events);
+
+    // A plain reregister rearms the socket as well, but edge-triggered.
+    poll.registry()
+        .reregister(&socket, ID, Interests::READABLE)
+        .unwrap();
+    expect_event(&mut poll, &mut events);
+    let mut buf = [0; 16];
+    assert_eq!(socket.recv(&mut buf).unwrap(), 5);
+    assert_eq!(socket.recv(&mut buf).unwrap(), 6);
+    sender.send_to(b"third", addr).unwrap();
+    expect_event(&mut poll, &mut events);
+    // The emulated edge triggers of the poll(2) selector are only armed again
+    // by reading from the socket.
+    #[cfg(not(feature = "force_poll"))]
+    {
+        sender.send_to(b"fourth", addr).unwrap();
+        expect_event(&mut poll, &mut events);
+    }
+}
+
+#[test]
+#[cfg(unix)]
+fn oneshot_evented_fd() {
+    use std::os::unix::io::AsRawFd;
+    use std::os::unix::net::UnixStream;
+
+    use mio::unix::EventedFd;
+
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let (receiver, mut sender) = UnixStream::pair().unwrap();
+    receiver.set_nonblocking(true).unwrap();
+    let fd = receiver.as_raw_fd();
+    poll.registry()
+        .register_with(&EventedFd(&fd), ID, Interests::READABLE, Trigger::Oneshot)
+        .unwrap();
+
+    sender.write_all(b"Hello").unwrap();
+    expect_event(&mut poll, &mut events);
+    sender.write_all(b"Hello").unwrap();
+    expect_no_events(&mut poll, &mut events);
+
+    poll.registry()
+        .reregister_with(&EventedFd(&fd), ID, Interests::READABLE, Trigger::Oneshot)
+        .unwrap();
+    expect_event(&mut poll, &mut events);
+    expect_no_events(&mut poll, &mut events);
+}
+
+fn expect_event(poll: &mut Poll, events: &mut Events) {
+    poll.poll(events, Some(Duration::from_millis(500))).unwrap();
+    let tokens: Vec<Token> = events.iter().map(|event| event.token()).collect();
+    assert_eq!(tokens, vec![ID], "expected a single event");
+}
@@ -0,0 +1,185 @@
+use std::ffi::CString;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::io::AsRawFd;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use iovec::IoVec;
+use mio::unix::pipe::{self, Receiver, Sender};
+use mio::{Events, Interests, Poll, Token};
+use tempdir::TempDir;
+
+use super::expect_no_events;
+
+const SENDER: Token = Token(0);
+const RECEIVER: Token = Token(1);
+
+#[test]
+fn pipe_read_write() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let (mut sender, mut receiver) = pipe::new().unwrap();
+    poll.registry()
+        .register(&receiver, RECEIVER, Interests::READABLE)
+        .unwrap();
+    poll.registry()
+        .register(&sender, SENDER, Interests::WRITABLE)
+        .unwrap();
+    expect_event(&mut poll, &mut events, SENDER);
+
+    let mut buf = [0; 16];
+    assert_would_block(receiver.read(&mut buf));
+
+    sender.write_all(b"Hello").unwrap();
+    expect_event(&mut poll, &mut events, RECEIVER);
+    assert_eq!(receiver.read(&mut buf).unwrap(), 5);
+    assert_eq!(&buf[..5], b"Hello");
+    assert_would_block(receiver.read(&mut buf));
+
+    // Closing the sending end makes the receiving end readable, returning end
+    // of file.
+    drop(sender);
+    expect_event(&mut poll, &mut events, RECEIVER);
+    assert_eq!(receiver.read(&mut buf).unwrap(), 0);
+}
+
+#[test]
+fn pipe_vectored() {
+    let (sender, receiver) = pipe::new().unwrap();
+    let bufs: [&IoVec; 2] = [b"Hello "[..].into(), b"world"[..].into()];
+    assert_eq!(sender.write_bufs(&bufs).unwrap(), 11);
+
+    let mut buf1 = [0; 6];
+    let mut buf2 = [0; 16];
+    {
+        let mut bufs: [&mut IoVec; 2] = [(&mut buf1[..]).into(), (&mut buf2[..]).into()];
+        assert_eq!(receiver.read_bufs(&mut bufs).unwrap(), 11);
+    }
+    assert_eq!(&buf1, b"Hello ");
+    assert_eq!(&buf2[..5], b"world");
+}
+
+#[test]
+fn pipe_set_nonblocking() {
+    let (sender, receiver) = pipe::new().unwrap();
+    assert!(is_nonblocking(&sender));
+    assert!(is_nonblocking(&receiver));
+
+    sender.set_nonblocking(false).unwrap();
+    assert!(!is_nonblocking(&sender));
+    sender.set_nonblocking(true).unwrap();
+    assert!(is_nonblocking(&sender));
+}
+
+#[test]
+fn pipe_close_on_exec() {
+    let (sender, receiver) = pipe::new().unwrap();
+    assert!(is_close_on_exec(&sender));
+    assert!(is_close_on_exec(&receiver));
+}
+
+#[test]
+fn pipe_from_child_stdio() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let mut child = Command::new("cat")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut stdin = Sender::from(child.stdin.take().unwrap());
+    let mut stdout = Receiver::from(child.stdout.take().unwrap());
+    assert!(!is_nonblocking(&stdout));
+    stdout.set_nonblocking(true).unwrap();
+    poll.registry()
+        .register(&stdout, RECEIVER, Interests::READABLE)
+        .unwrap();
+
+    stdin.write_all(b"echo").unwrap();
+    drop(stdin);
+
+    let mut output = Vec::new();
+    let mut buf = [0; 16];
+    loop {
+        match stdout.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => output.extend_from_slice(&buf[..n]),
+            Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {
+                expect_event(&mut poll, &mut events, RECEIVER)
+            }
+            Err(err) => panic!("unexpected error: {}", err),
+        }
+    }
+    assert_eq!(output, b"echo");
+    assert!(child.wait().unwrap().success());
+}
+
+#[test]
+fn pipe_open_fifo() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let dir = TempDir::new("mio").unwrap();
+    let path = dir.path().join("fifo");
+    mkfifo(&path);
+
+    // Opening for writing fails without a reader.
+    let err = Sender::open_fifo(&path).unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::ENXIO));
+
+    // Opening for reading doesn't block.
+    let mut receiver = Receiver::open_fifo(&path).unwrap();
+    poll.registry()
+        .register(&receiver, RECEIVER, Interests::READABLE)
+        .unwrap();
+    expect_no_events(&mut poll, &mut events);
+
+    let mut sender = Sender::open_fifo(&path).unwrap();
+    sender.write_all(b"fifo").unwrap();
+    expect_event(&mut poll, &mut events, RECEIVER);
+    let mut buf = [0; 16];
+    assert_eq!(receiver.read(&mut buf).unwrap(), 4);
+
+    // Files that aren't a FIFO are rejected.
+    let file_path = dir.path().join("file");
+    File::create(&file_path).unwrap();
+    let err = Receiver::open_fifo(&file_path).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+fn mkfifo(path: &Path) {
+    let path = CString::new(path.as_os_str().as_bytes()).unwrap();
+    assert_eq!(unsafe { libc::mkfifo(path.as_ptr(), 0o600) }, 0);
+}
+
+fn is_nonblocking<T: AsRawFd>(fd: &T) -> bool {
+    let flags = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GETFL) };
+    flags & libc::O_NONBLOCK != 0
+}
+
+fn is_close_on_exec<T: AsRawFd>(fd: &T) -> bool {
+    let flags = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GETFD) };
+    flags & libc::FD_CLOEXEC != 0
+}
+
+fn assert_would_block<T: std::fmt::Debug>(result: io::Result<T>) {
+    match result {
+        Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {}
+        result => panic!("expected WouldBlock, got: {:?}", result),
+    }
+}
+
+fn expect_event(poll: &mut Poll, events: &mut Events, token: Token) {
+    poll.poll(events, Some(Duration::from_millis(500))).unwrap();
+    assert!(
+        events.iter().any(|event| event.token() == token),
+        "expected event for {:?}",
+        token
+    );
+}
@@ -1,5 +1,5 @@
 use mio::*;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 #[test]
 fn test_poll_closes_fd() {
@@ -13,3 +13,31 @@ fn test_poll_closes_fd() {
         drop(poll);
     }
 }
+
+#[test]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn test_poll_sub_millisecond_timeout() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(4);
+
+    // A rounded up timeout takes at least a millisecond every time, a loaded
+    // machine only delays some of the tries.
+    let timeout = Duration::from_micros(100);
+    let mut fastest = Duration::from_secs(1);
+    for _ in 0..50 {
+        let start = Instant::now();
+        poll.poll(&mut events, Some(timeout)).unwrap();
+        let elapsed = start.elapsed();
+        assert!(events.is_empty());
+        assert!(elapsed >= timeout, "returned early: {:?}", elapsed);
+        fastest = std::cmp::min(fastest, elapsed);
+        if fastest < Duration::from_millis(1) {
+            break;
+        }
+    }
+    assert!(
+        fastest < Duration::from_millis(1),
+        "rounded up to a millisecond: {:?}",
+        fastest
+    );
+}
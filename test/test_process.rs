@@ -0,0 +1,80 @@
+use std::os::unix::process::ExitStatusExt;
+use std::process::Command;
+use std::time::Duration;
+
+use mio::unix::Process;
+use mio::{Events, Interests, Poll, Token};
+
+use super::expect_no_events;
+
+const PROCESS: Token = Token(10);
+
+#[test]
+fn process_exit() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let child = Command::new("sh").arg("-c").arg("exit 3").spawn().unwrap();
+    let process = Process::new(&child).unwrap();
+    assert_eq!(process.pid(), child.id());
+    poll.registry()
+        .register(&process, PROCESS, Interests::READABLE)
+        .unwrap();
+
+    expect_process_event(&mut poll, &mut events);
+    let status = process.try_wait().unwrap().unwrap();
+    assert_eq!(status.code(), Some(3));
+    // Reaped by `try_wait` above.
+    drop(child);
+}
+
+#[test]
+fn process_running() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let mut child = Command::new("sleep").arg("10").spawn().unwrap();
+    let process = Process::new(&child).unwrap();
+    poll.registry()
+        .register(&process, PROCESS, Interests::READABLE)
+        .unwrap();
+
+    expect_no_events(&mut poll, &mut events);
+    assert!(process.try_wait().unwrap().is_none());
+
+    child.kill().unwrap();
+    expect_process_event(&mut poll, &mut events);
+    assert!(process.try_wait().unwrap().is_some());
+    drop(child);
+}
+
+#[test]
+fn process_send_signal() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let child = Command::new("sleep").arg("10").spawn().unwrap();
+    let process = Process::new(&child).unwrap();
+    poll.registry()
+        .register(&process, PROCESS, Interests::READABLE)
+        .unwrap();
+
+    process.send_signal(libc::SIGTERM).unwrap();
+    expect_process_event(&mut poll, &mut events);
+    let status = process.try_wait().unwrap().unwrap();
+    assert_eq!(status.signal(), Some(libc::SIGTERM));
+
+    // The process is reaped, so it can't be signalled any more.
+    let err = process.send_signal(libc::SIGTERM).unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::ESRCH));
+    drop(child);
+}
+
+fn expect_process_event(poll: &mut Poll, events: &mut Events) {
+    poll.poll(events, Some(Duration::from_secs(5))).unwrap();
+    assert!(!events.is_empty(), "expected process event");
+    for event in events.iter() {
+        assert_eq!(event.token(), PROCESS);
+        assert!(event.is_readable());
+    }
+}
@@ -0,0 +1,252 @@
+use std::io::{self, Read, Write};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use mio::net::{TcpListener, TcpStream, UdpSocket};
+use mio::record::{Recorder, Replayer};
+use mio::{Events, Interests, Poll, Token};
+
+const ID1: Token = Token(1);
+const ID2: Token = Token(2);
+const ID3: Token = Token(3);
+
+/// A log shared between the recorder and the test.
+#[derive(Clone)]
+struct Log {
+    buf: Arc<Mutex<Vec<u8>>>,
+}
+
+impl Log {
+    fn new() -> Log {
+        Log {
+            buf: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    fn bytes(&self) -> Vec<u8> {
+        self.buf.lock().unwrap().clone()
+    }
+}
+
+impl Write for Log {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buf.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn record() -> (Log, Poll) {
+    let log = Log::new();
+    let poll = Poll::with_selector(Recorder::new(log.clone()).unwrap());
+    (log, poll)
+}
+
+fn replay(log: &[u8]) -> (Replayer, Poll) {
+    let replayer = Replayer::new(log).unwrap();
+    let poll = Poll::with_selector(replayer.clone());
+    (replayer, poll)
+}
+
+fn bind() -> UdpSocket {
+    UdpSocket::bind("127.0.0.1:0".parse().unwrap()).unwrap()
+}
+
+fn kind<T>(result: io::Result<T>) -> io::ErrorKind {
+    match result {
+        Ok(_) => panic!("unexpected success"),
+        Err(err) => err.kind(),
+    }
+}
+
+/// Runs a small echo session, returning the events of each poll as token,
+/// readable and writable.
+fn session(poll: &mut Poll) -> Vec<Vec<(Token, bool, bool)>> {
+    let mut events = Events::with_capacity(16);
+    let mut polls = Vec::new();
+    let listener = TcpListener::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+    poll.registry()
+        .register(&listener, ID1, Interests::READABLE)
+        .unwrap();
+    let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    poll.registry()
+        .register(&client, ID2, Interests::WRITABLE)
+        .unwrap();
+
+    let mut server = None;
+    let mut written = false;
+    let mut buf = [0; 16];
+    while polls.len() < 20 {
+        poll.poll(&mut events, Some(Duration::from_millis(500)))
+            .unwrap();
+        polls.push(
+            events
+                .iter()
+                .map(|event| (event.token(), event.is_readable(), event.is_writable()))
+                .collect(),
+        );
+        for event in events.iter() {
+            match event.token() {
+                ID1 => {
+                    let (stream, _) = listener.accept().unwrap();
+                    poll.registry()
+                        .register(&stream, ID3, Interests::READABLE)
+                        .unwrap();
+                    server = Some(stream);
+                }
+                ID2 if !written => {
+                    client.write_all(b"hello").unwrap();
+                    written = true;
+                }
+                ID3 => {
+                    let n = server.as_mut().unwrap().read(&mut buf).unwrap();
+                    assert_eq!(&buf[..n], b"hello");
+                    poll.registry().deregister(&client).unwrap();
+                    return polls;
+                }
+                _ => {}
+            }
+        }
+    }
+    panic!("no data received");
+}
+
+#[test]
+fn record_and_replay_session() {
+    let (log, mut poll) = record();
+    let recorded = session(&mut poll);
+    drop(poll);
+
+    let (replayer, mut poll) = replay(&log.bytes());
+    assert_eq!(session(&mut poll), recorded);
+    assert_eq!(replayer.remaining(), 0);
+}
+
+#[test]
+fn replay_registration_errors() {
+    let (log, poll) = record();
+    let socket = bind();
+    poll.registry()
+        .register(&socket, ID1, Interests::READABLE)
+        .unwrap();
+    let recorded = poll
+        .registry()
+        .register(&socket, ID1, Interests::READABLE)
+        .unwrap_err();
+    drop(poll);
+
+    // Handles can't move between selectors.
+    let socket = bind();
+    let (_, poll) = replay(&log.bytes());
+    poll.registry()
+        .register(&socket, ID1, Interests::READABLE)
+        .unwrap();
+    let replayed = poll
+        .registry()
+        .register(&socket, ID1, Interests::READABLE)
+        .unwrap_err();
+    assert_eq!(replayed.raw_os_error(), recorded.raw_os_error());
+}
+
+#[test]
+fn replay_diverged() {
+    let (log, poll) = record();
+    let socket = bind();
+    poll.registry()
+        .register(&socket, ID1, Interests::READABLE)
+        .unwrap();
+    drop(poll);
+
+    // Different token.
+    let socket = bind();
+    let (_, mut poll) = replay(&log.bytes());
+    let result = poll.registry().register(&socket, ID2, Interests::READABLE);
+    assert_eq!(kind(result), io::ErrorKind::InvalidData);
+
+    // Different interests.
+    let result = poll.registry().register(&socket, ID1, Interests::WRITABLE);
+    assert_eq!(kind(result), io::ErrorKind::InvalidData);
+
+    // Poll in place of a registration.
+    let mut events = Events::with_capacity(16);
+    let result = poll.poll(&mut events, None);
+    assert_eq!(kind(result), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn replay_end_of_log() {
+    let (log, mut poll) = record();
+    let mut events = Events::with_capacity(16);
+    poll.poll(&mut events, Some(Duration::from_millis(0)))
+        .unwrap();
+    drop(poll);
+
+    let (replayer, mut poll) = replay(&log.bytes());
+    assert_eq!(replayer.remaining(), 1);
+    poll.poll(&mut events, None).unwrap();
+    assert!(events.is_empty());
+    assert_eq!(
+        kind(poll.poll(&mut events, None)),
+        io::ErrorKind::UnexpectedEof
+    );
+
+    // A truncated record is ignored.
+    let mut bytes = log.bytes();
+    bytes.pop();
+    assert_eq!(Replayer::new(&bytes[..]).unwrap().remaining(), 0);
+
+    assert_eq!(
+        kind(Replayer::new(&b"not a log"[..])),
+        io::ErrorKind::InvalidData
+    );
+}
+
+#[test]
+fn replay_timestamps() {
+    let (log, mut poll) = record();
+    let mut events = Events::with_capacity(16);
+    poll.poll(&mut events, Some(Duration::from_millis(50)))
+        .unwrap();
+    drop(poll);
+
+    let (replayer, mut poll) = replay(&log.bytes());
+    assert_eq!(replayer.elapsed(), Duration::from_millis(0));
+    poll.poll(&mut events, None).unwrap();
+    assert!(replayer.elapsed() >= Duration::from_millis(50));
+}
+
+#[test]
+fn replay_small_events() {
+    let (log, mut poll) = record();
+    let socket1 = bind();
+    let socket2 = bind();
+    poll.registry()
+        .register(&socket1, ID1, Interests::WRITABLE)
+        .unwrap();
+    poll.registry()
+        .register(&socket2, ID2, Interests::WRITABLE)
+        .unwrap();
+    let mut events = Events::with_capacity(16);
+    poll.poll(&mut events, None).unwrap();
+    assert_eq!(events.iter().count(), 2);
+    drop(poll);
+
+    // Events that don't fit are returned by the next poll.
+    let (socket1, socket2) = (bind(), bind());
+    let (replayer, mut poll) = replay(&log.bytes());
+    poll.registry()
+        .register(&socket1, ID1, Interests::WRITABLE)
+        .unwrap();
+    poll.registry()
+        .register(&socket2, ID2, Interests::WRITABLE)
+        .unwrap();
+    let mut events = Events::with_capacity(1);
+    poll.poll(&mut events, None).unwrap();
+    assert_eq!(events.iter().count(), 1);
+    assert_eq!(replayer.remaining(), 1);
+    poll.poll(&mut events, None).unwrap();
+    assert_eq!(events.iter().count(), 1);
+    assert_eq!(replayer.remaining(), 0);
+}
@@ -0,0 +1,220 @@
+use std::thread;
+use std::time::Duration;
+
+use mio::event::Registration;
+use mio::{Events, Interests, Poll, Token, Trigger};
+
+use super::expect_no_events;
+
+const ID1: Token = Token(1);
+const ID2: Token = Token(2);
+
+#[test]
+fn registration_set_readiness_from_thread() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let (registration, set_readiness) = Registration::new();
+    poll.registry()
+        .register(&registration, ID1, Interests::READABLE)
+        .unwrap();
+
+    let handle = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        set_readiness
+            .set_readiness(Some(Interests::READABLE))
+            .unwrap();
+    });
+
+    // Blocks until the other thread sets the readiness.
+    poll.poll(&mut events, None).unwrap();
+    expect_events(&events, &[(ID1, Interests::READABLE)]);
+    handle.join().unwrap();
+}
+
+#[test]
+fn registration_edge() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let (registration, set_readiness) = Registration::new();
+    poll.registry()
+        .register(&registration, ID1, Interests::READABLE)
+        .unwrap();
+    expect_no_events(&mut poll, &mut events);
+
+    set_readiness
+        .set_readiness(Some(Interests::READABLE))
+        .unwrap();
+    assert_eq!(set_readiness.readiness(), Some(Interests::READABLE));
+    poll_events(&mut poll, &mut events);
+    expect_events(&events, &[(ID1, Interests::READABLE)]);
+    expect_no_events(&mut poll, &mut events);
+
+    // Setting the readiness again returns another event.
+    set_readiness
+        .set_readiness(Some(Interests::READABLE))
+        .unwrap();
+    poll_events(&mut poll, &mut events);
+    expect_events(&events, &[(ID1, Interests::READABLE)]);
+}
+
+#[test]
+fn registration_level() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let (registration, set_readiness) = Registration::new();
+    poll.registry()
+        .register_with(&registration, ID1, Interests::READABLE, Trigger::Level)
+        .unwrap();
+
+    set_readiness
+        .set_readiness(Some(Interests::READABLE))
+        .unwrap();
+    for _ in 0..3 {
+        poll_events(&mut poll, &mut events);
+        expect_events(&events, &[(ID1, Interests::READABLE)]);
+    }
+
+    set_readiness.set_readiness(None).unwrap();
+    assert_eq!(set_readiness.readiness(), None);
+    expect_no_events(&mut poll, &mut events);
+}
+
+#[test]
+fn registration_oneshot() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let (registration, set_readiness) = Registration::new();
+    poll.registry()
+        .register_with(&registration, ID1, Interests::READABLE, Trigger::Oneshot)
+        .unwrap();
+
+    set_readiness
+        .set_readiness(Some(Interests::READABLE))
+        .unwrap();
+    poll_events(&mut poll, &mut events);
+    expect_events(&events, &[(ID1, Interests::READABLE)]);
+
+    set_readiness
+        .set_readiness(Some(Interests::READABLE))
+        .unwrap();
+    expect_no_events(&mut poll, &mut events);
+
+    // Rearming returns an event as the registration is still ready.
+    poll.registry()
+        .reregister_with(&registration, ID2, Interests::READABLE, Trigger::Oneshot)
+        .unwrap();
+    poll_events(&mut poll, &mut events);
+    expect_events(&events, &[(ID2, Interests::READABLE)]);
+}
+
+#[test]
+fn registration_interests() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let (registration, set_readiness) = Registration::new();
+    poll.registry()
+        .register(&registration, ID1, Interests::WRITABLE)
+        .unwrap();
+
+    set_readiness
+        .set_readiness(Some(Interests::READABLE))
+        .unwrap();
+    expect_no_events(&mut poll, &mut events);
+
+    set_readiness
+        .set_readiness(Some(Interests::READABLE | Interests::WRITABLE))
+        .unwrap();
+    poll_events(&mut poll, &mut events);
+    expect_events(&events, &[(ID1, Interests::WRITABLE)]);
+}
+
+#[test]
+fn registration_deregister_and_drop() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let (registration, set_readiness) = Registration::new();
+    poll.registry()
+        .register(&registration, ID1, Interests::READABLE)
+        .unwrap();
+    poll.registry().deregister(&registration).unwrap();
+    set_readiness
+        .set_readiness(Some(Interests::READABLE))
+        .unwrap();
+    expect_no_events(&mut poll, &mut events);
+
+    // Dropping a queued registration removes its event.
+    poll.registry()
+        .register(&registration, ID1, Interests::READABLE)
+        .unwrap();
+    drop(registration);
+    expect_no_events(&mut poll, &mut events);
+    set_readiness
+        .set_readiness(Some(Interests::READABLE))
+        .unwrap();
+    expect_no_events(&mut poll, &mut events);
+}
+
+#[test]
+fn registration_full_events() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(4);
+
+    let registrations: Vec<_> = (0..10)
+        .map(|i| {
+            let (registration, set_readiness) = Registration::new();
+            poll.registry()
+                .register(&registration, Token(i), Interests::READABLE)
+                .unwrap();
+            set_readiness
+                .set_readiness(Some(Interests::READABLE))
+                .unwrap();
+            (registration, set_readiness)
+        })
+        .collect();
+
+    // The events that didn't fit are returned by the next calls.
+    let mut tokens = Vec::new();
+    for _ in 0..3 {
+        poll_events(&mut poll, &mut events);
+        tokens.extend(events.iter().map(|event| event.token().0));
+    }
+    assert_eq!(tokens, (0..10).collect::<Vec<_>>());
+    expect_no_events(&mut poll, &mut events);
+    drop(registrations);
+}
+
+#[test]
+fn registration_other_poll() {
+    let poll1 = Poll::new().unwrap();
+    let poll2 = Poll::new().unwrap();
+
+    let (registration, _set_readiness) = Registration::new();
+    poll1
+        .registry()
+        .register(&registration, ID1, Interests::READABLE)
+        .unwrap();
+    assert!(poll2
+        .registry()
+        .register(&registration, ID1, Interests::READABLE)
+        .is_err());
+}
+
+fn poll_events(poll: &mut Poll, events: &mut Events) {
+    poll.poll(events, Some(Duration::from_millis(500))).unwrap();
+}
+
+fn expect_events(events: &Events, expected: &[(Token, Interests)]) {
+    let got: Vec<_> = events.iter().collect();
+    assert_eq!(got.len(), expected.len(), "unexpected events: {:?}", got);
+    for (event, &(token, interests)) in got.iter().zip(expected) {
+        assert_eq!(event.token(), token);
+        assert_eq!(event.is_readable(), interests.is_readable());
+        assert_eq!(event.is_writable(), interests.is_writable());
+    }
+}
@@ -0,0 +1,369 @@
+use std::io::{Read, Write};
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use mio::event::Registration;
+use mio::net::{TcpListener, TcpStream, UdpSocket};
+use mio::selector::{self, Selector};
+use mio::unix::EventedFd;
+use mio::{Events, Interests, Poll, Token, Trigger, Waker};
+
+use super::{expect_no_events, localhost};
+
+const LISTENER: Token = Token(0);
+const CLIENT: Token = Token(1);
+const SERVER: Token = Token(2);
+const WAKER: Token = Token(3);
+const REGISTRATION: Token = Token(4);
+
+fn builtin_selectors() -> Vec<(&'static str, Box<dyn Selector>)> {
+    let mut selectors: Vec<(&'static str, Box<dyn Selector>)> =
+        vec![("poll", Box::new(selector::Poll::new().unwrap()))];
+    #[cfg(any(target_os = "linux", target_os = "android", target_os = "solaris"))]
+    selectors.push(("epoll", Box::new(selector::Epoll::new().unwrap())));
+    #[cfg(any(
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "macos",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    selectors.push(("kqueue", Box::new(selector::Kqueue::new().unwrap())));
+    #[cfg(all(feature = "io_uring", any(target_os = "linux", target_os = "android")))]
+    selectors.push(("io_uring", Box::new(selector::Uring::new().unwrap())));
+    selectors
+}
+
+#[test]
+fn selector_builtins() {
+    for (name, selector) in builtin_selectors() {
+        debug!("testing the {} selector", name);
+        let mut poll = Poll::with_selector(selector);
+        let mut events = Events::with_capacity(16);
+
+        let addr = localhost();
+        let listener = TcpListener::bind(addr).unwrap();
+        poll.registry()
+            .register(&listener, LISTENER, Interests::READABLE)
+            .unwrap();
+        let mut client = TcpStream::connect(addr).unwrap();
+        poll.registry()
+            .register(&client, CLIENT, Interests::READABLE | Interests::WRITABLE)
+            .unwrap();
+
+        expect_event(&mut poll, &mut events, LISTENER, name);
+        let (mut server, _) = listener.accept().unwrap();
+        poll.registry()
+            .register(&server, SERVER, Interests::READABLE)
+            .unwrap();
+
+        client.write_all(b"ping").unwrap();
+        expect_event(&mut poll, &mut events, SERVER, name);
+        let mut buf = [0; 8];
+        assert_eq!(server.read(&mut buf).unwrap(), 4, "{}", name);
+
+        let waker = Waker::new(poll.registry(), WAKER).unwrap();
+        waker.wake().unwrap();
+        expect_event(&mut poll, &mut events, WAKER, name);
+
+        let (registration, set_readiness) = Registration::new();
+        poll.registry()
+            .register(&registration, REGISTRATION, Interests::READABLE)
+            .unwrap();
+        set_readiness
+            .set_readiness(Some(Interests::READABLE))
+            .unwrap();
+        expect_event(&mut poll, &mut events, REGISTRATION, name);
+    }
+}
+
+/// Selector wrapping another selector, counting the registrations.
+struct Counting {
+    inner: Box<dyn Selector>,
+    registered: Arc<AtomicUsize>,
+}
+
+impl Selector for Counting {
+    fn register(
+        &self,
+        fd: RawFd,
+        token: Token,
+        interests: Interests,
+        trigger: Trigger,
+    ) -> std::io::Result<()> {
+        self.registered.fetch_add(1, Ordering::SeqCst);
+        self.inner.register(fd, token, interests, trigger)
+    }
+
+    fn reregister(
+        &self,
+        fd: RawFd,
+        token: Token,
+        interests: Interests,
+        trigger: Trigger,
+    ) -> std::io::Result<()> {
+        self.inner.reregister(fd, token, interests, trigger)
+    }
+
+    fn deregister(&self, fd: RawFd) -> std::io::Result<()> {
+        self.registered.fetch_sub(1, Ordering::SeqCst);
+        self.inner.deregister(fd)
+    }
+
+    fn select(&self, events: &mut Events, timeout: Option<Duration>) -> std::io::Result<()> {
+        self.inner.select(events, timeout)
+    }
+
+    fn waker(&self, token: Token) -> std::io::Result<Box<dyn selector::Wake>> {
+        self.inner.waker(token)
+    }
+
+    fn hooks(&self) -> Option<selector::Hooks<'_>> {
+        self.inner.hooks()
+    }
+}
+
+impl AsRawFd for Counting {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+#[test]
+fn selector_wrapper() {
+    for (name, inner) in builtin_selectors() {
+        let registered = Arc::new(AtomicUsize::new(0));
+        let mut poll = Poll::with_selector(Counting {
+            inner,
+            registered: registered.clone(),
+        });
+        let mut events = Events::with_capacity(16);
+
+        let addr = localhost();
+        let listener = TcpListener::bind(addr).unwrap();
+        poll.registry()
+            .register(&listener, LISTENER, Interests::READABLE)
+            .unwrap();
+        assert_eq!(registered.load(Ordering::SeqCst), 1, "{}", name);
+
+        let _client = TcpStream::connect(addr).unwrap();
+        expect_event(&mut poll, &mut events, LISTENER, name);
+
+        poll.registry().deregister(&listener).unwrap();
+        assert_eq!(registered.load(Ordering::SeqCst), 0, "{}", name);
+        expect_no_events(&mut poll, &mut events);
+    }
+}
+
+/// Selector returning scripted events, on top of those of `selector::Poll`.
+struct Scripted {
+    inner: selector::Poll,
+    script: Mutex<Vec<(Token, Interests)>>,
+}
+
+impl Selector for Scripted {
+    fn register(
+        &self,
+        fd: RawFd,
+        token: Token,
+        interests: Interests,
+        trigger: Trigger,
+    ) -> std::io::Result<()> {
+        self.inner.register(fd, token, interests, trigger)
+    }
+
+    fn reregister(
+        &self,
+        fd: RawFd,
+        token: Token,
+        interests: Interests,
+        trigger: Trigger,
+    ) -> std::io::Result<()> {
+        self.inner.reregister(fd, token, interests, trigger)
+    }
+
+    fn deregister(&self, fd: RawFd) -> std::io::Result<()> {
+        self.inner.deregister(fd)
+    }
+
+    fn select(&self, events: &mut Events, timeout: Option<Duration>) -> std::io::Result<()> {
+        let mut script = self.script.lock().unwrap();
+        if script.is_empty() {
+            drop(script);
+            return self.inner.select(events, timeout);
+        }
+        while let Some(&(token, readiness)) = script.first() {
+            if !events.push(token, readiness) {
+                break;
+            }
+            script.remove(0);
+        }
+        Ok(())
+    }
+
+    fn waker(&self, token: Token) -> std::io::Result<Box<dyn selector::Wake>> {
+        self.inner.waker(token)
+    }
+
+    fn hooks(&self) -> Option<selector::Hooks<'_>> {
+        self.inner.hooks()
+    }
+}
+
+impl AsRawFd for Scripted {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+#[test]
+fn selector_scripted_events() {
+    let mut poll = Poll::with_selector(Scripted {
+        inner: selector::Poll::new().unwrap(),
+        script: Mutex::new(vec![
+            (Token(10), Interests::READABLE),
+            (Token(11), Interests::WRITABLE),
+            (Token(12), Interests::READABLE | Interests::WRITABLE),
+        ]),
+    });
+    let mut events = Events::with_capacity(2);
+
+    poll.poll(&mut events, None).unwrap();
+    let got: Vec<_> = events
+        .iter()
+        .map(|event| (event.token(), event.is_readable(), event.is_writable()))
+        .collect();
+    assert_eq!(got, [(Token(10), true, false), (Token(11), false, true)]);
+
+    // Returned by the next call, once there is room. kqueue uses separate
+    // events for reading and writing.
+    poll.poll(&mut events, None).unwrap();
+    assert!(events.iter().all(|event| event.token() == Token(12)));
+    assert!(events.iter().any(|event| event.is_readable()));
+    assert!(events.iter().any(|event| event.is_writable()));
+
+    expect_no_events(&mut poll, &mut events);
+}
+
+#[test]
+fn selector_release_before_close() {
+    for (name, selector) in builtin_selectors() {
+        let poll = Poll::with_selector(selector);
+
+        // Dropping a handle removes its registration.
+        let socket = UdpSocket::bind(localhost()).unwrap();
+        poll.registry()
+            .register(&socket, CLIENT, Interests::READABLE)
+            .unwrap();
+        let fd = socket.as_raw_fd();
+        drop(socket);
+        assert!(
+            poll.registry().deregister(&EventedFd(&fd)).is_err(),
+            "{}: registration kept after drop",
+            name
+        );
+
+        // Like the file descriptor, the registration is kept by
+        // `into_raw_fd`.
+        let socket = UdpSocket::bind(localhost()).unwrap();
+        poll.registry()
+            .register(&socket, CLIENT, Interests::READABLE)
+            .unwrap();
+        let fd = socket.into_raw_fd();
+        poll.registry()
+            .deregister(&EventedFd(&fd))
+            .unwrap_or_else(|err| panic!("{}: {}", name, err));
+        drop(unsafe { std::net::UdpSocket::from_raw_fd(fd) });
+    }
+}
+
+#[test]
+#[cfg(all(feature = "io_uring", any(target_os = "linux", target_os = "android")))]
+fn selector_uring_drop_closes_file() {
+    let poll = Poll::with_selector(selector::Uring::new().unwrap());
+    let listener = std::net::TcpListener::bind(localhost()).unwrap();
+    let stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    poll.registry()
+        .register(&stream, CLIENT, Interests::READABLE | Interests::WRITABLE)
+        .unwrap();
+    let (mut peer, _) = listener.accept().unwrap();
+
+    // The poll request, which isn't submitted yet, is removed before the
+    // stream is closed, which closes the connection.
+    drop(stream);
+    peer.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+    let mut buf = [0; 8];
+    assert_eq!(peer.read(&mut buf).unwrap(), 0);
+}
+
+#[test]
+fn selector_poll_fd_readable() {
+    let mut poll = Poll::with_selector(selector::Poll::new().unwrap());
+    let mut events = Events::with_capacity(16);
+    let fd = poll.as_raw_fd();
+    assert!(!is_readable(fd, 0));
+
+    let socket = UdpSocket::bind(localhost()).unwrap();
+    poll.registry()
+        .register(&socket, SERVER, Interests::READABLE)
+        .unwrap();
+    assert!(!is_readable(fd, 0));
+    let client = UdpSocket::bind(localhost()).unwrap();
+    client
+        .send_to(b"hello", socket.local_addr().unwrap())
+        .unwrap();
+    assert!(is_readable(fd, 500));
+    expect_event(&mut poll, &mut events, SERVER, "poll");
+    // Disarmed until the socket is read from.
+    assert!(!is_readable(fd, 0));
+
+    let waker = Waker::new(poll.registry(), WAKER).unwrap();
+    waker.wake().unwrap();
+    assert!(is_readable(fd, 0));
+    expect_event(&mut poll, &mut events, WAKER, "poll");
+    assert!(!is_readable(fd, 0));
+}
+
+#[test]
+fn selector_end_of_stream() {
+    for (name, selector) in builtin_selectors() {
+        let mut poll = Poll::with_selector(selector);
+        let mut events = Events::with_capacity(16);
+        let listener = std::net::TcpListener::bind(localhost()).unwrap();
+        let mut stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        poll.registry()
+            .register(&stream, CLIENT, Interests::READABLE)
+            .unwrap();
+        drop(listener.accept().unwrap());
+
+        expect_event(&mut poll, &mut events, CLIENT, name);
+        let mut buf = [0; 8];
+        assert_eq!(stream.read(&mut buf).unwrap(), 0);
+        // No more data arrives, so the end of the stream isn't returned again.
+        expect_no_events(&mut poll, &mut events);
+    }
+}
+
+/// Returns `true` if `fd` becomes readable within `timeout` milliseconds.
+fn is_readable(fd: RawFd, timeout: i32) -> bool {
+    let mut pollfd = libc::pollfd {
+        fd,
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    unsafe { libc::poll(&mut pollfd, 1, timeout) == 1 }
+}
+
+/// Poll until an event with `token` is returned.
+fn expect_event(poll: &mut Poll, events: &mut Events, token: Token, name: &str) {
+    for _ in 0..10 {
+        poll.poll(events, Some(Duration::from_millis(500))).unwrap();
+        if events.iter().any(|event| event.token() == token) {
+            return;
+        }
+    }
+    panic!("{}: no event for {:?}", name, token);
+}
@@ -0,0 +1,170 @@
+use std::io;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use mio::unix::SignalPipe;
+use mio::{Events, Interests, Poll, Token};
+
+use super::expect_no_events;
+
+const SIGNALS: Token = Token(10);
+
+// Note: signal handlers are process wide, so every test uses different
+// signals to not receive the signals raised by other tests.
+
+#[test]
+fn signal_pipe_receive() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let signals = SignalPipe::new(&[libc::SIGALRM]).unwrap();
+    poll.registry()
+        .register(&signals, SIGNALS, Interests::READABLE)
+        .unwrap();
+    assert_would_block(signals.receive());
+
+    raise(libc::SIGALRM);
+    raise(libc::SIGALRM);
+    raise(libc::SIGALRM);
+    expect_signals_event(&mut poll, &mut events);
+    // Coalesced into a single event.
+    expect_no_events(&mut poll, &mut events);
+
+    let counts = signals.receive().unwrap();
+    assert_eq!(counts.get(libc::SIGALRM), 3);
+    assert_eq!(counts.iter().collect::<Vec<_>>(), vec![(libc::SIGALRM, 3)]);
+    assert_would_block(signals.receive());
+
+    raise(libc::SIGALRM);
+    expect_signals_event(&mut poll, &mut events);
+    assert_eq!(signals.receive().unwrap().get(libc::SIGALRM), 1);
+}
+
+#[test]
+fn signal_pipe_add_remove() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let mut signals = SignalPipe::new(&[libc::SIGPROF]).unwrap();
+    poll.registry()
+        .register(&signals, SIGNALS, Interests::READABLE)
+        .unwrap();
+
+    assert!(!signals.contains(libc::SIGVTALRM));
+    signals.add(libc::SIGVTALRM).unwrap();
+    assert!(signals.contains(libc::SIGVTALRM));
+    raise(libc::SIGVTALRM);
+    expect_signals_event(&mut poll, &mut events);
+    assert_eq!(signals.receive().unwrap().get(libc::SIGVTALRM), 1);
+
+    // The handler stays installed, so this doesn't terminate the process.
+    signals.remove(libc::SIGPROF).unwrap();
+    assert!(!signals.contains(libc::SIGPROF));
+    raise(libc::SIGPROF);
+    expect_no_events(&mut poll, &mut events);
+    assert_would_block(signals.receive());
+}
+
+#[test]
+fn signal_pipe_other_thread() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let signals = SignalPipe::new(&[libc::SIGWINCH]).unwrap();
+    poll.registry()
+        .register(&signals, SIGNALS, Interests::READABLE)
+        .unwrap();
+
+    // The signal doesn't need to be blocked in any thread.
+    thread::spawn(|| raise(libc::SIGWINCH)).join().unwrap();
+    expect_signals_event(&mut poll, &mut events);
+    assert_eq!(signals.receive().unwrap().get(libc::SIGWINCH), 1);
+}
+
+#[test]
+fn signal_pipe_multiple() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let signals1 = SignalPipe::new(&[libc::SIGXCPU]).unwrap();
+    let signals2 = SignalPipe::new(&[libc::SIGXCPU]).unwrap();
+    poll.registry()
+        .register(&signals1, Token(1), Interests::READABLE)
+        .unwrap();
+    poll.registry()
+        .register(&signals2, Token(2), Interests::READABLE)
+        .unwrap();
+
+    raise(libc::SIGXCPU);
+    poll.poll(&mut events, Some(Duration::from_millis(500)))
+        .unwrap();
+    let mut tokens: Vec<Token> = events.iter().map(|event| event.token()).collect();
+    tokens.sort();
+    assert_eq!(tokens, vec![Token(1), Token(2)]);
+    assert_eq!(signals1.receive().unwrap().get(libc::SIGXCPU), 1);
+    assert_eq!(signals2.receive().unwrap().get(libc::SIGXCPU), 1);
+
+    drop(signals1);
+    raise(libc::SIGXCPU);
+    poll.poll(&mut events, Some(Duration::from_millis(500)))
+        .unwrap();
+    let tokens: Vec<Token> = events.iter().map(|event| event.token()).collect();
+    assert_eq!(tokens, vec![Token(2)]);
+}
+
+static PREVIOUS_CALLED: AtomicUsize = AtomicUsize::new(0);
+
+extern "C" fn previous_handler(_: libc::c_int) {
+    PREVIOUS_CALLED.fetch_add(1, Ordering::SeqCst);
+}
+
+#[test]
+fn signal_pipe_chains_previous_handler() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let previous = previous_handler as extern "C" fn(libc::c_int) as libc::sighandler_t;
+    assert_ne!(
+        unsafe { libc::signal(libc::SIGURG, previous) },
+        libc::SIG_ERR
+    );
+
+    let signals = SignalPipe::new(&[libc::SIGURG]).unwrap();
+    poll.registry()
+        .register(&signals, SIGNALS, Interests::READABLE)
+        .unwrap();
+
+    raise(libc::SIGURG);
+    expect_signals_event(&mut poll, &mut events);
+    assert_eq!(signals.receive().unwrap().get(libc::SIGURG), 1);
+    assert_eq!(PREVIOUS_CALLED.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn signal_pipe_invalid_signal() {
+    assert!(SignalPipe::new(&[libc::SIGKILL]).is_err());
+    assert!(SignalPipe::new(&[0]).is_err());
+    assert!(SignalPipe::new(&[-1]).is_err());
+    assert!(SignalPipe::new(&[1000]).is_err());
+}
+
+fn raise(signal: libc::c_int) {
+    assert_eq!(unsafe { libc::raise(signal) }, 0);
+}
+
+fn expect_signals_event(poll: &mut Poll, events: &mut Events) {
+    poll.poll(events, Some(Duration::from_millis(500))).unwrap();
+    assert!(!events.is_empty(), "expected signals event");
+    for event in events.iter() {
+        assert_eq!(event.token(), SIGNALS);
+        assert!(event.is_readable());
+    }
+}
+
+fn assert_would_block<T: std::fmt::Debug>(result: io::Result<T>) {
+    match result {
+        Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {}
+        other => panic!("expected WouldBlock error, got: {:?}", other),
+    }
+}
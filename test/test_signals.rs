@@ -0,0 +1,121 @@
+use std::io;
+use std::process;
+use std::time::Duration;
+
+use mio::unix::Signals;
+use mio::{Events, Interests, Poll, Token};
+
+use super::expect_no_events;
+
+const SIGNALS: Token = Token(10);
+
+// Note: the tests only use `raise`, which sends the signal to the calling
+// thread. A signal sent to the process could be handled by another test
+// thread, which doesn't block the signal.
+
+#[test]
+fn signals_receive() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let signals = Signals::new(&[libc::SIGUSR1, libc::SIGUSR2]).unwrap();
+    poll.registry()
+        .register(&signals, SIGNALS, Interests::READABLE)
+        .unwrap();
+    assert_would_block(signals.receive());
+
+    raise(libc::SIGUSR2);
+    expect_signals_event(&mut poll, &mut events);
+
+    let info = signals.receive().unwrap();
+    assert_eq!(info.signal(), libc::SIGUSR2);
+    assert_eq!(info.pid(), process::id());
+    assert_eq!(info.uid(), unsafe { libc::getuid() });
+    assert_would_block(signals.receive());
+}
+
+#[test]
+fn signals_coalesced() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let signals = Signals::new(&[libc::SIGUSR1, libc::SIGUSR2]).unwrap();
+    poll.registry()
+        .register(&signals, SIGNALS, Interests::READABLE)
+        .unwrap();
+
+    raise(libc::SIGUSR1);
+    raise(libc::SIGUSR1);
+    raise(libc::SIGUSR2);
+    expect_signals_event(&mut poll, &mut events);
+
+    // Standard signals are not queued more than once, signals with a lower
+    // number are received first.
+    assert_eq!(signals.receive().unwrap().signal(), libc::SIGUSR1);
+    assert_eq!(signals.receive().unwrap().signal(), libc::SIGUSR2);
+    assert_would_block(signals.receive());
+}
+
+#[test]
+fn signals_add_after_register() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let mut signals = Signals::new(&[libc::SIGUSR1]).unwrap();
+    poll.registry()
+        .register(&signals, SIGNALS, Interests::READABLE)
+        .unwrap();
+    assert!(!signals.contains(libc::SIGHUP).unwrap());
+
+    signals.add(libc::SIGHUP).unwrap();
+    assert!(signals.contains(libc::SIGHUP).unwrap());
+
+    raise(libc::SIGHUP);
+    expect_signals_event(&mut poll, &mut events);
+    assert_eq!(signals.receive().unwrap().signal(), libc::SIGHUP);
+}
+
+#[test]
+fn signals_remove_after_register() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let mut signals = Signals::new(&[libc::SIGUSR1, libc::SIGUSR2]).unwrap();
+    poll.registry()
+        .register(&signals, SIGNALS, Interests::READABLE)
+        .unwrap();
+
+    signals.remove(libc::SIGUSR2).unwrap();
+    assert!(signals.contains(libc::SIGUSR1).unwrap());
+    assert!(!signals.contains(libc::SIGUSR2).unwrap());
+
+    // The signal is still blocked, so it's not handled by its disposition
+    // (terminating the process), but it isn't received either.
+    raise(libc::SIGUSR2);
+    expect_no_events(&mut poll, &mut events);
+    assert_would_block(signals.receive());
+
+    raise(libc::SIGUSR1);
+    expect_signals_event(&mut poll, &mut events);
+    assert_eq!(signals.receive().unwrap().signal(), libc::SIGUSR1);
+}
+
+fn raise(signal: libc::c_int) {
+    assert_eq!(unsafe { libc::raise(signal) }, 0);
+}
+
+fn expect_signals_event(poll: &mut Poll, events: &mut Events) {
+    poll.poll(events, Some(Duration::from_millis(500))).unwrap();
+    assert!(!events.is_empty(), "expected signals event");
+    for event in events.iter() {
+        assert_eq!(event.token(), SIGNALS);
+        assert!(event.is_readable());
+    }
+}
+
+fn assert_would_block<T: std::fmt::Debug>(result: io::Result<T>) {
+    match result {
+        Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {}
+        other => panic!("expected WouldBlock error, got: {:?}", other),
+    }
+}
@@ -0,0 +1,188 @@
+use std::os::unix::io::AsRawFd;
+use std::thread;
+use std::time::Duration;
+
+use mio::event::Registration;
+use mio::net::UdpSocket;
+use mio::sim::{Clock, Selector};
+use mio::time::Wheel;
+use mio::{Events, Interests, Poll, Token, Trigger, Waker};
+
+const ID1: Token = Token(1);
+const ID2: Token = Token(2);
+const ID3: Token = Token(3);
+
+fn setup() -> (Clock, Selector, Poll, Events) {
+    let clock = Clock::new();
+    let selector = Selector::new(&clock);
+    let poll = Poll::with_selector(selector.clone());
+    (clock, selector, poll, Events::with_capacity(8))
+}
+
+fn tokens(events: &Events) -> Vec<Token> {
+    events.iter().map(|event| event.token()).collect()
+}
+
+#[test]
+fn sim_scheduled_events() {
+    let (clock, selector, mut poll, mut events) = setup();
+    selector.schedule(Duration::from_millis(5), ID3, Interests::READABLE);
+    selector.schedule(Duration::from_millis(2), ID1, Interests::WRITABLE);
+    selector.schedule(Duration::from_millis(5), ID2, Interests::READABLE);
+
+    poll.poll(&mut events, Some(Duration::from_millis(10)))
+        .unwrap();
+    assert_eq!(tokens(&events), [ID1]);
+    assert!(events.iter().next().unwrap().is_writable());
+    assert_eq!(clock.elapsed(), Duration::from_millis(2));
+
+    // Events at the same time are returned in the order they were scheduled.
+    poll.poll(&mut events, None).unwrap();
+    assert_eq!(tokens(&events), [ID3, ID2]);
+    assert_eq!(clock.elapsed(), Duration::from_millis(5));
+    assert_eq!(selector.pending(), 0);
+}
+
+#[test]
+fn sim_timeout_advances_clock() {
+    let (clock, selector, mut poll, mut events) = setup();
+    selector.schedule(Duration::from_millis(20), ID1, Interests::READABLE);
+
+    poll.poll(&mut events, Some(Duration::from_millis(15)))
+        .unwrap();
+    assert!(events.is_empty());
+    assert_eq!(clock.elapsed(), Duration::from_millis(15));
+
+    poll.poll(&mut events, Some(Duration::from_millis(15)))
+        .unwrap();
+    assert_eq!(tokens(&events), [ID1]);
+    assert_eq!(clock.elapsed(), Duration::from_millis(20));
+}
+
+#[test]
+fn sim_manual_advance() {
+    let (clock, selector, mut poll, mut events) = setup();
+    selector.schedule(Duration::from_millis(5), ID1, Interests::READABLE);
+
+    poll.poll(&mut events, Some(Duration::from_millis(0)))
+        .unwrap();
+    assert!(events.is_empty());
+    assert_eq!(clock.elapsed(), Duration::from_millis(0));
+
+    clock.advance(Duration::from_millis(5));
+    poll.poll(&mut events, Some(Duration::from_millis(0)))
+        .unwrap();
+    assert_eq!(tokens(&events), [ID1]);
+
+    // Scheduled in the past.
+    selector.schedule(Duration::from_millis(1), ID2, Interests::READABLE);
+    selector.set_ready(ID3, Interests::WRITABLE);
+    poll.poll(&mut events, Some(Duration::from_millis(0)))
+        .unwrap();
+    assert_eq!(tokens(&events), [ID2, ID3]);
+}
+
+#[test]
+fn sim_events_capacity() {
+    let (_, selector, mut poll, _) = setup();
+    let mut events = Events::with_capacity(2);
+    for n in 0..5 {
+        selector.set_ready(Token(n), Interests::READABLE);
+    }
+
+    let mut got = Vec::new();
+    while got.len() < 5 {
+        poll.poll(&mut events, Some(Duration::from_millis(0)))
+            .unwrap();
+        assert!(events.iter().count() <= 2);
+        got.extend(tokens(&events));
+    }
+    assert_eq!(got, [Token(0), Token(1), Token(2), Token(3), Token(4)]);
+}
+
+#[test]
+fn sim_waker_and_registration() {
+    let (_, _, mut poll, mut events) = setup();
+
+    let waker = Waker::new(poll.registry(), ID1).unwrap();
+    waker.wake().unwrap();
+    poll.poll(&mut events, Some(Duration::from_millis(0)))
+        .unwrap();
+    assert_eq!(tokens(&events), [ID1]);
+
+    let (registration, set_readiness) = Registration::new();
+    poll.registry()
+        .register(&registration, ID2, Interests::READABLE)
+        .unwrap();
+    set_readiness
+        .set_readiness(Some(Interests::READABLE))
+        .unwrap();
+    poll.poll(&mut events, Some(Duration::from_millis(0)))
+        .unwrap();
+    assert_eq!(tokens(&events), [ID2]);
+}
+
+#[test]
+fn sim_wake_from_thread() {
+    let (clock, _, mut poll, mut events) = setup();
+    let waker = Waker::new(poll.registry(), ID1).unwrap();
+
+    // Blocks without a timeout, as nothing is scheduled.
+    let handle = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        waker.wake().unwrap();
+    });
+    poll.poll(&mut events, None).unwrap();
+    assert_eq!(tokens(&events), [ID1]);
+    assert_eq!(clock.elapsed(), Duration::from_millis(0));
+    handle.join().unwrap();
+}
+
+#[test]
+fn sim_registrations() {
+    let (_, selector, mut poll, mut events) = setup();
+    let socket = UdpSocket::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+    let fd = socket.as_raw_fd();
+
+    poll.registry()
+        .register(&socket, ID1, Interests::READABLE)
+        .unwrap();
+    assert_eq!(
+        selector.registration(fd),
+        Some((ID1, Interests::READABLE, Trigger::Edge))
+    );
+    assert!(poll
+        .registry()
+        .register(&socket, ID1, Interests::READABLE)
+        .is_err());
+
+    // Registered handles don't return events by themselves.
+    poll.poll(&mut events, Some(Duration::from_millis(10)))
+        .unwrap();
+    assert!(events.is_empty());
+
+    poll.registry()
+        .reregister(&socket, ID2, Interests::WRITABLE)
+        .unwrap();
+    assert_eq!(
+        selector.registration(fd),
+        Some((ID2, Interests::WRITABLE, Trigger::Edge))
+    );
+    poll.registry().deregister(&socket).unwrap();
+    assert_eq!(selector.registration(fd), None);
+    assert!(poll.registry().deregister(&socket).is_err());
+}
+
+#[test]
+fn sim_clock_with_wheel() {
+    let (clock, _, mut poll, mut events) = setup();
+    let mut wheel = Wheel::new(Duration::from_millis(1));
+    wheel.insert_at(clock.now() + Duration::from_millis(30), ID1);
+
+    poll.poll(&mut events, Some(Duration::from_millis(20)))
+        .unwrap();
+    assert_eq!(wheel.expired_at(clock.now()).count(), 0);
+    poll.poll(&mut events, Some(Duration::from_millis(20)))
+        .unwrap();
+    assert_eq!(wheel.expired_at(clock.now()).collect::<Vec<_>>(), [ID1]);
+}
@@ -0,0 +1,302 @@
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, SocketAddr};
+use std::time::Duration;
+
+use mio::sim::{Clock, Network, Selector, TcpListener, TcpStream, UdpSocket};
+use mio::{Events, Interests, Poll, Token};
+
+const LISTENER: Token = Token(0);
+const CLIENT: Token = Token(1);
+const SERVER: Token = Token(2);
+
+fn setup(seed: u64) -> (Clock, Network, Poll, Events) {
+    let clock = Clock::new();
+    let selector = Selector::new(&clock);
+    let network = Network::new(&selector, seed);
+    let poll = Poll::with_selector(selector);
+    (clock, network, poll, Events::with_capacity(16))
+}
+
+fn addr(s: &str) -> SocketAddr {
+    s.parse().unwrap()
+}
+
+/// Poll until an event for `token` is returned, returning whether it's
+/// readable and writable.
+fn poll_for(poll: &mut Poll, events: &mut Events, token: Token) -> (bool, bool) {
+    for _ in 0..100 {
+        poll.poll(events, Some(Duration::from_secs(1))).unwrap();
+        if let Some(event) = events.iter().find(|event| event.token() == token) {
+            return (event.is_readable(), event.is_writable());
+        }
+    }
+    panic!("no event for {:?}", token);
+}
+
+fn would_block<T>(result: io::Result<T>) -> bool {
+    match result {
+        Err(ref err) => err.kind() == io::ErrorKind::WouldBlock,
+        Ok(_) => false,
+    }
+}
+
+/// Returns a connected client and server stream.
+fn connect(network: &Network, poll: &mut Poll, events: &mut Events) -> (TcpStream, TcpStream) {
+    let listener = TcpListener::bind(network, addr("10.0.0.1:80")).unwrap();
+    poll.registry()
+        .register(&listener, LISTENER, Interests::READABLE)
+        .unwrap();
+    let client = TcpStream::connect(network, addr("10.0.0.1:80")).unwrap();
+    poll.registry()
+        .register(&client, CLIENT, Interests::READABLE | Interests::WRITABLE)
+        .unwrap();
+
+    poll_for(poll, events, LISTENER);
+    let (server, _) = listener.accept().unwrap();
+    poll.registry()
+        .register(&server, SERVER, Interests::READABLE)
+        .unwrap();
+    // Without latency the client may already be connected.
+    while client.peer_addr().is_err() {
+        poll.poll(events, Some(Duration::from_secs(1))).unwrap();
+    }
+    (client, server)
+}
+
+#[test]
+fn sim_tcp_latency() {
+    let (clock, network, mut poll, mut events) = setup(0);
+    network.set_latency(Duration::from_millis(10));
+
+    let listener = TcpListener::bind(&network, addr("0.0.0.0:80")).unwrap();
+    poll.registry()
+        .register(&listener, LISTENER, Interests::READABLE)
+        .unwrap();
+    let mut client = TcpStream::connect(&network, addr("10.0.0.1:80")).unwrap();
+    poll.registry()
+        .register(&client, CLIENT, Interests::READABLE | Interests::WRITABLE)
+        .unwrap();
+    assert!(would_block(client.write(b"hello")));
+    assert!(client.peer_addr().is_err());
+
+    // The connection request arrives after the latency.
+    assert!(poll_for(&mut poll, &mut events, LISTENER).0);
+    assert_eq!(clock.elapsed(), Duration::from_millis(10));
+    let (mut server, peer) = listener.accept().unwrap();
+    assert_eq!(peer, client.local_addr().unwrap());
+    assert!(would_block(listener.accept()));
+    poll.registry()
+        .register(&server, SERVER, Interests::READABLE)
+        .unwrap();
+
+    // The client is connected after a round trip.
+    assert_eq!(poll_for(&mut poll, &mut events, CLIENT), (false, true));
+    assert_eq!(clock.elapsed(), Duration::from_millis(20));
+    assert_eq!(client.peer_addr().unwrap(), addr("10.0.0.1:80"));
+
+    assert_eq!(client.write(b"hello").unwrap(), 5);
+    assert!(would_block(server.read(&mut [0; 8])));
+    assert!(poll_for(&mut poll, &mut events, SERVER).0);
+    assert_eq!(clock.elapsed(), Duration::from_millis(30));
+    let mut buf = [0; 8];
+    assert_eq!(server.read(&mut buf).unwrap(), 5);
+    assert_eq!(&buf[..5], b"hello");
+    assert!(would_block(server.read(&mut buf)));
+}
+
+#[test]
+fn sim_tcp_connection_refused() {
+    let (_, network, mut poll, mut events) = setup(0);
+    let mut stream = TcpStream::connect(&network, addr("10.0.0.1:80")).unwrap();
+    poll.registry()
+        .register(&stream, CLIENT, Interests::READABLE | Interests::WRITABLE)
+        .unwrap();
+
+    poll_for(&mut poll, &mut events, CLIENT);
+    let err = stream.read(&mut [0; 8]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::ConnectionRefused);
+}
+
+#[test]
+fn sim_tcp_addr_in_use() {
+    let (_, network, _, _) = setup(0);
+    let listener = TcpListener::bind(&network, addr("10.0.0.1:0")).unwrap();
+    let addr = listener.local_addr().unwrap();
+    assert_ne!(addr.port(), 0);
+    let err = TcpListener::bind(&network, addr).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+
+    drop(listener);
+    TcpListener::bind(&network, addr).unwrap();
+}
+
+#[test]
+fn sim_tcp_partial_writes_and_backpressure() {
+    let (_, network, mut poll, mut events) = setup(0);
+    network.set_max_write(Some(4));
+    network.set_buffer_size(10);
+    let (mut client, mut server) = connect(&network, &mut poll, &mut events);
+
+    assert_eq!(client.write(b"hello world").unwrap(), 4);
+    assert_eq!(client.write(b"o world").unwrap(), 4);
+    assert_eq!(client.write(b"rld").unwrap(), 2);
+    assert!(would_block(client.write(b"d")));
+
+    assert!(poll_for(&mut poll, &mut events, SERVER).0);
+    let mut buf = [0; 4];
+    assert_eq!(server.read(&mut buf).unwrap(), 4);
+    assert_eq!(&buf, b"hell");
+
+    // Reading made the client writable again.
+    assert!(poll_for(&mut poll, &mut events, CLIENT).1);
+    assert_eq!(client.write(b"d").unwrap(), 1);
+    poll_for(&mut poll, &mut events, SERVER);
+    let mut received = Vec::new();
+    assert!(would_block(server.read_to_end(&mut received)));
+    assert_eq!(received, b"o world");
+}
+
+#[test]
+fn sim_tcp_shutdown() {
+    let (_, network, mut poll, mut events) = setup(0);
+    let (mut client, mut server) = connect(&network, &mut poll, &mut events);
+
+    client.write_all(b"bye").unwrap();
+    client.shutdown(Shutdown::Write).unwrap();
+    let err = client.write(b"more").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+
+    assert!(poll_for(&mut poll, &mut events, SERVER).0);
+    let mut received = Vec::new();
+    server.read_to_end(&mut received).unwrap();
+    assert_eq!(received, b"bye");
+
+    // Dropping the server ends the stream for the client.
+    drop(server);
+    assert!(poll_for(&mut poll, &mut events, CLIENT).0);
+    assert_eq!(client.read(&mut [0; 8]).unwrap(), 0);
+}
+
+#[test]
+fn sim_tcp_reset() {
+    let (_, network, mut poll, mut events) = setup(0);
+    let (mut client, mut server) = connect(&network, &mut poll, &mut events);
+
+    client.write_all(b"lost").unwrap();
+    network.reset(addr("10.0.0.1:80"));
+    for stream in &mut [&mut client, &mut server] {
+        let err = stream.read(&mut [0; 8]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::ConnectionReset);
+        let err = stream.write(b"data").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::ConnectionReset);
+    }
+}
+
+#[test]
+fn sim_tcp_random_reset() {
+    let (_, network, mut poll, mut events) = setup(0);
+    let (mut client, mut server) = connect(&network, &mut poll, &mut events);
+
+    network.set_reset(1.0);
+    client.write_all(b"data").unwrap();
+    assert!(poll_for(&mut poll, &mut events, SERVER).0);
+    let err = server.read(&mut [0; 8]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::ConnectionReset);
+    let err = client.read(&mut [0; 8]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::ConnectionReset);
+}
+
+#[test]
+fn sim_tcp_loss() {
+    let (clock, network, mut poll, mut events) = setup(0);
+    let (mut client, mut server) = connect(&network, &mut poll, &mut events);
+    network.set_latency(Duration::from_millis(10));
+
+    // A lost segment is retransmitted, data sent later is delayed as well.
+    network.set_loss(1.0);
+    client.write_all(b"first").unwrap();
+    network.set_loss(0.0);
+    client.write_all(b"second").unwrap();
+
+    assert!(poll_for(&mut poll, &mut events, SERVER).0);
+    assert_eq!(clock.elapsed(), Duration::from_millis(210));
+    let mut buf = [0; 16];
+    assert_eq!(server.read(&mut buf).unwrap(), 11);
+    assert_eq!(&buf[..11], b"firstsecond");
+}
+
+#[test]
+fn sim_udp() {
+    let (clock, network, mut poll, mut events) = setup(0);
+    network.set_latency(Duration::from_millis(5));
+    let socket1 = UdpSocket::bind(&network, addr("10.0.0.1:53")).unwrap();
+    let socket2 = UdpSocket::bind(&network, addr("10.0.0.2:0")).unwrap();
+    poll.registry()
+        .register(&socket1, Token(1), Interests::READABLE)
+        .unwrap();
+
+    let addr2 = socket2.local_addr().unwrap();
+    assert_eq!(socket2.send_to(b"query", addr("10.0.0.1:53")).unwrap(), 5);
+    assert!(would_block(socket1.recv_from(&mut [0; 8])));
+    poll_for(&mut poll, &mut events, Token(1));
+    assert_eq!(clock.elapsed(), Duration::from_millis(5));
+
+    // The excess bytes of a datagram are discarded.
+    let mut buf = [0; 2];
+    assert_eq!(socket1.recv_from(&mut buf).unwrap(), (2, addr2));
+    assert_eq!(&buf, b"qu");
+    assert!(would_block(socket1.recv_from(&mut buf)));
+}
+
+#[test]
+fn sim_udp_reorder() {
+    let (_, network, mut poll, mut events) = setup(0);
+    let socket1 = UdpSocket::bind(&network, addr("10.0.0.1:53")).unwrap();
+    let socket2 = UdpSocket::bind(&network, addr("10.0.0.2:53")).unwrap();
+    poll.registry()
+        .register(&socket1, Token(1), Interests::READABLE)
+        .unwrap();
+
+    network.set_reorder(1.0, Duration::from_millis(10));
+    socket2.send_to(&[1], addr("10.0.0.1:53")).unwrap();
+    network.set_reorder(0.0, Duration::from_millis(0));
+    socket2.send_to(&[2], addr("10.0.0.1:53")).unwrap();
+
+    let mut received = Vec::new();
+    while received.len() < 2 {
+        poll_for(&mut poll, &mut events, Token(1));
+        let mut buf = [0; 1];
+        while socket1.recv_from(&mut buf).is_ok() {
+            received.push(buf[0]);
+        }
+    }
+    assert_eq!(received, [2, 1]);
+}
+
+/// Returns the datagrams out of 100 that arrive with half of them lost.
+fn lossy(seed: u64) -> Vec<u8> {
+    let (_, network, mut poll, mut events) = setup(seed);
+    network.set_loss(0.5);
+    let socket1 = UdpSocket::bind(&network, addr("10.0.0.1:53")).unwrap();
+    let socket2 = UdpSocket::bind(&network, addr("10.0.0.2:53")).unwrap();
+    for n in 0..100 {
+        socket2.send_to(&[n], addr("10.0.0.1:53")).unwrap();
+    }
+    poll.poll(&mut events, Some(Duration::from_millis(0)))
+        .unwrap();
+
+    let mut received = Vec::new();
+    let mut buf = [0; 1];
+    while socket1.recv_from(&mut buf).is_ok() {
+        received.push(buf[0]);
+    }
+    received
+}
+
+#[test]
+fn sim_udp_loss_is_reproducible() {
+    let received = lossy(1);
+    assert!(!received.is_empty() && received.len() < 100);
+    assert_eq!(lossy(1), received);
+    assert_ne!(lossy(2), received);
+}
@@ -0,0 +1,175 @@
+use std::io;
+use std::time::{Duration, Instant};
+
+use mio::time::{Clock, Timer};
+use mio::{Events, Interests, Poll, Token};
+
+use super::expect_no_events;
+
+const TIMER: Token = Token(10);
+
+#[test]
+fn timer_timeout() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let timer = Timer::new().unwrap();
+    poll.registry()
+        .register(&timer, TIMER, Interests::READABLE)
+        .unwrap();
+
+    let start = Instant::now();
+    timer.set_timeout(Duration::from_millis(50)).unwrap();
+    expect_timer_event(&mut poll, &mut events);
+    assert!(start.elapsed() >= Duration::from_millis(50));
+
+    assert_eq!(timer.read().unwrap(), 1);
+    assert_would_block(timer.read());
+    assert_eq!(timer.remaining().unwrap(), None);
+
+    // One-shot, so no more events.
+    expect_no_events(&mut poll, &mut events);
+}
+
+#[test]
+fn timer_zero_timeout() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let timer = Timer::new().unwrap();
+    poll.registry()
+        .register(&timer, TIMER, Interests::READABLE)
+        .unwrap();
+
+    timer.set_timeout(Duration::from_millis(0)).unwrap();
+    expect_timer_event(&mut poll, &mut events);
+    assert_eq!(timer.read().unwrap(), 1);
+}
+
+#[test]
+fn timer_interval() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let timer = Timer::new().unwrap();
+    poll.registry()
+        .register(&timer, TIMER, Interests::READABLE)
+        .unwrap();
+
+    timer.set_interval(Duration::from_millis(10)).unwrap();
+    for _ in 0..3 {
+        expect_timer_event(&mut poll, &mut events);
+        assert!(timer.read().unwrap() >= 1);
+    }
+
+    // Expirations accumulate while not being read.
+    std::thread::sleep(Duration::from_millis(50));
+    assert!(timer.read().unwrap() >= 2);
+    assert!(timer.remaining().unwrap().is_some());
+}
+
+#[test]
+fn timer_rearm() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let timer = Timer::new().unwrap();
+    poll.registry()
+        .register(&timer, TIMER, Interests::READABLE)
+        .unwrap();
+
+    timer.set_timeout(Duration::from_secs(60)).unwrap();
+    let remaining = timer.remaining().unwrap().unwrap();
+    assert!(remaining > Duration::from_secs(50));
+
+    // Rearming overrides the previous timeout.
+    timer.set_timeout(Duration::from_millis(10)).unwrap();
+    expect_timer_event(&mut poll, &mut events);
+    assert_eq!(timer.read().unwrap(), 1);
+}
+
+#[test]
+fn timer_cancel() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let timer = Timer::new().unwrap();
+    poll.registry()
+        .register(&timer, TIMER, Interests::READABLE)
+        .unwrap();
+
+    timer.set_timeout(Duration::from_millis(20)).unwrap();
+    timer.cancel().unwrap();
+    assert_eq!(timer.remaining().unwrap(), None);
+
+    poll.poll(&mut events, Some(Duration::from_millis(100)))
+        .unwrap();
+    assert!(events.is_empty());
+    assert_would_block(timer.read());
+
+    // Cancelling resets the expiration count.
+    timer.set_timeout(Duration::from_millis(10)).unwrap();
+    expect_timer_event(&mut poll, &mut events);
+    timer.cancel().unwrap();
+    assert_would_block(timer.read());
+}
+
+#[test]
+fn timer_boottime() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let timer = Timer::with_clock(Clock::Boottime).unwrap();
+    poll.registry()
+        .register(&timer, TIMER, Interests::READABLE)
+        .unwrap();
+
+    timer.set_timeout(Duration::from_millis(10)).unwrap();
+    expect_timer_event(&mut poll, &mut events);
+    assert_eq!(timer.read().unwrap(), 1);
+}
+
+#[test]
+fn timer_multiple_tokens() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let timer1 = Timer::new().unwrap();
+    let timer2 = Timer::new().unwrap();
+    poll.registry()
+        .register(&timer1, Token(1), Interests::READABLE)
+        .unwrap();
+    poll.registry()
+        .register(&timer2, Token(2), Interests::READABLE)
+        .unwrap();
+
+    timer2.set_timeout(Duration::from_millis(10)).unwrap();
+    timer1.set_timeout(Duration::from_millis(100)).unwrap();
+
+    poll.poll(&mut events, Some(Duration::from_millis(1000)))
+        .unwrap();
+    let tokens: Vec<Token> = events.iter().map(|e| e.token()).collect();
+    assert_eq!(tokens, vec![Token(2)]);
+
+    poll.poll(&mut events, Some(Duration::from_millis(1000)))
+        .unwrap();
+    let tokens: Vec<Token> = events.iter().map(|e| e.token()).collect();
+    assert_eq!(tokens, vec![Token(1)]);
+}
+
+fn expect_timer_event(poll: &mut Poll, events: &mut Events) {
+    poll.poll(events, Some(Duration::from_millis(1000)))
+        .unwrap();
+    assert!(!events.is_empty(), "expected timer event");
+    for event in events.iter() {
+        assert_eq!(event.token(), TIMER);
+        assert!(event.is_readable());
+    }
+}
+
+fn assert_would_block<T: std::fmt::Debug>(result: io::Result<T>) {
+    match result {
+        Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {}
+        other => panic!("expected WouldBlock error, got: {:?}", other),
+    }
+}
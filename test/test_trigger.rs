@@ -0,0 +1,125 @@
+use std::io::{Read, Write};
+use std::net;
+use std::time::Duration;
+
+use mio::net::{TcpStream, UdpSocket};
+use mio::{Events, Interests, Poll, Token, Trigger};
+
+use super::expect_no_events;
+
+const ID: Token = Token(1);
+
+#[test]
+fn trigger_default_is_edge() {
+    assert_eq!(Trigger::default(), Trigger::Edge);
+}
+
+#[test]
+fn edge_triggered_tcp_stream() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+    let (stream, _peer) = connected_pair(&mut poll, Trigger::Edge);
+
+    // Only a single event, even though the data isn't read.
+    expect_readable(&mut poll, &mut events);
+    expect_no_events(&mut poll, &mut events);
+
+    drop(stream);
+}
+
+#[test]
+fn level_triggered_tcp_stream() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+    let (mut stream, _peer) = connected_pair(&mut poll, Trigger::Level);
+
+    // The stream stays readable as long as the data isn't read.
+    for _ in 0..3 {
+        expect_readable(&mut poll, &mut events);
+    }
+
+    // Reading only part of the data keeps the stream readable.
+    let mut buf = [0; 2];
+    stream.read_exact(&mut buf).unwrap();
+    expect_readable(&mut poll, &mut events);
+
+    let mut buf = [0; 16];
+    assert_eq!(stream.read(&mut buf).unwrap(), 3);
+    expect_no_events(&mut poll, &mut events);
+}
+
+#[test]
+fn reregister_changes_trigger() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+    let (stream, _peer) = connected_pair(&mut poll, Trigger::Edge);
+
+    expect_readable(&mut poll, &mut events);
+    expect_no_events(&mut poll, &mut events);
+
+    poll.registry()
+        .reregister_with(&stream, ID, Interests::READABLE, Trigger::Level)
+        .unwrap();
+    expect_readable(&mut poll, &mut events);
+    expect_readable(&mut poll, &mut events);
+
+    // A plain reregister makes the registration edge-triggered again.
+    poll.registry()
+        .reregister(&stream, ID, Interests::READABLE)
+        .unwrap();
+    expect_readable(&mut poll, &mut events);
+    expect_no_events(&mut poll, &mut events);
+}
+
+#[test]
+fn level_triggered_udp_socket() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let any_local_address = "127.0.0.1:0".parse().unwrap();
+    let socket = UdpSocket::bind(any_local_address).unwrap();
+    let sender = net::UdpSocket::bind(any_local_address).unwrap();
+    poll.registry()
+        .register_with(&socket, ID, Interests::READABLE, Trigger::Level)
+        .unwrap();
+
+    sender
+        .send_to(b"first", socket.local_addr().unwrap())
+        .unwrap();
+    sender
+        .send_to(b"second", socket.local_addr().unwrap())
+        .unwrap();
+
+    let mut buf = [0; 16];
+    for expected in &[&b"first"[..], &b"second"[..]] {
+        expect_readable(&mut poll, &mut events);
+        expect_readable(&mut poll, &mut events);
+        let n = socket.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..n], *expected);
+    }
+    expect_no_events(&mut poll, &mut events);
+}
+
+/// Returns a connected stream, registered with `poll` using `trigger`, and its
+/// peer. The peer has written 5 bytes to the stream.
+fn connected_pair(poll: &mut Poll, trigger: Trigger) -> (TcpStream, net::TcpStream) {
+    let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (mut peer, _) = listener.accept().unwrap();
+    peer.write_all(b"Hello").unwrap();
+
+    poll.registry()
+        .register_with(&stream, ID, Interests::READABLE, trigger)
+        .unwrap();
+    (stream, peer)
+}
+
+fn expect_readable(poll: &mut Poll, events: &mut Events) {
+    poll.poll(events, Some(Duration::from_millis(500))).unwrap();
+    assert!(
+        events
+            .iter()
+            .any(|event| event.token() == ID && event.is_readable()),
+        "expected readable event"
+    );
+}
@@ -0,0 +1,202 @@
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::net::Shutdown;
+use std::os::unix::net;
+use std::time::Duration;
+
+use mio::net::{UnixListener, UnixStream};
+use mio::{Events, Interests, Poll, Token};
+use tempdir::TempDir;
+
+use super::expect_no_events;
+
+const LISTENER: Token = Token(0);
+const CLIENT: Token = Token(1);
+const SERVER: Token = Token(2);
+
+#[test]
+fn unix_stream_pair() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let (mut a, mut b) = UnixStream::pair().unwrap();
+    poll.registry()
+        .register(&b, SERVER, Interests::READABLE)
+        .unwrap();
+
+    let mut buf = [0; 16];
+    assert_would_block(b.read(&mut buf));
+
+    a.write_all(b"Hello").unwrap();
+    expect_readable(&mut poll, &mut events, SERVER);
+    assert_eq!(b.read(&mut buf).unwrap(), 5);
+    assert_eq!(&buf[..5], b"Hello");
+
+    assert!(a.peer_addr().unwrap().is_unnamed());
+    assert!(a.take_error().unwrap().is_none());
+}
+
+#[test]
+fn unix_listener_accept() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let dir = TempDir::new("mio").unwrap();
+    let path = dir.path().join("accept.sock");
+    let listener = UnixListener::bind(&path).unwrap();
+    assert_eq!(listener.local_addr().unwrap().as_pathname(), Some(&*path));
+    poll.registry()
+        .register(&listener, LISTENER, Interests::READABLE)
+        .unwrap();
+    assert_would_block(listener.accept().map(|_| ()));
+
+    let mut client = UnixStream::connect(&path).unwrap();
+    poll.registry()
+        .register(&client, CLIENT, Interests::WRITABLE)
+        .unwrap();
+    assert_eq!(client.peer_addr().unwrap().as_pathname(), Some(&*path));
+
+    expect_readable(&mut poll, &mut events, LISTENER);
+    let (mut server, addr) = listener.accept().unwrap();
+    assert!(addr.is_unnamed());
+    poll.registry()
+        .register(&server, SERVER, Interests::READABLE)
+        .unwrap();
+
+    // The accepted stream is non-blocking.
+    let mut buf = [0; 16];
+    assert_would_block(server.read(&mut buf));
+
+    client.write_all(b"ping").unwrap();
+    expect_readable(&mut poll, &mut events, SERVER);
+    assert_eq!(server.read(&mut buf).unwrap(), 4);
+    assert_eq!(&buf[..4], b"ping");
+}
+
+#[test]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn unix_listener_abstract_namespace() {
+    let name = format!("\0mio-test-uds-{}", std::process::id());
+    let listener = UnixListener::bind(&name).unwrap();
+    let addr = listener.local_addr().unwrap();
+    assert!(addr.as_pathname().is_none());
+    assert!(!addr.is_unnamed());
+
+    let mut client = UnixStream::connect(&name).unwrap();
+    let (mut server, _) = accept(&listener);
+    client.write_all(b"abstract").unwrap();
+    let mut buf = [0; 16];
+    assert_eq!(read_blocking(&mut server, &mut buf), 8);
+
+    // Binding the same name again fails.
+    let err = UnixListener::bind(&name).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+}
+
+#[test]
+fn unix_listener_bind_unlink_stale() {
+    let dir = TempDir::new("mio").unwrap();
+    let path = dir.path().join("stale.sock");
+    drop(UnixListener::bind(&path).unwrap());
+    assert!(path.exists());
+
+    // The socket file of the dropped listener is left behind.
+    let err = UnixListener::bind(&path).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+
+    let listener = UnixListener::bind_unlink_stale(&path).unwrap();
+    let _client = UnixStream::connect(&path).unwrap();
+    accept(&listener);
+
+    // A socket that is still in use isn't removed.
+    let err = UnixListener::bind_unlink_stale(&path).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+    let _client = UnixStream::connect(&path).unwrap();
+    accept(&listener);
+
+    // Neither is a file that isn't a socket.
+    let file_path = dir.path().join("file");
+    File::create(&file_path).unwrap();
+    let err = UnixListener::bind_unlink_stale(&file_path).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+    assert!(fs::metadata(&file_path).unwrap().is_file());
+}
+
+#[test]
+fn unix_stream_connect_errors() {
+    let dir = TempDir::new("mio").unwrap();
+    let err = UnixStream::connect(dir.path().join("missing.sock")).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+
+    let long = dir.path().join("x".repeat(200));
+    let err = UnixStream::connect(long).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn unix_stream_shutdown() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let (a, mut b) = UnixStream::pair().unwrap();
+    poll.registry()
+        .register(&b, SERVER, Interests::READABLE)
+        .unwrap();
+    expect_no_events(&mut poll, &mut events);
+
+    a.shutdown(Shutdown::Write).unwrap();
+    expect_readable(&mut poll, &mut events, SERVER);
+    let mut buf = [0; 16];
+    assert_eq!(b.read(&mut buf).unwrap(), 0);
+}
+
+#[test]
+fn unix_stream_from_std() {
+    let (a, b) = net::UnixStream::pair().unwrap();
+    let mut a = UnixStream::from_std(a).unwrap();
+    let mut buf = [0; 16];
+    assert_would_block(a.read(&mut buf));
+    drop(b);
+    assert_eq!(a.read(&mut buf).unwrap(), 0);
+}
+
+fn accept(listener: &UnixListener) -> (UnixStream, net::SocketAddr) {
+    loop {
+        match listener.accept() {
+            Ok(res) => return res,
+            Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {
+                std::thread::sleep(Duration::from_millis(1))
+            }
+            Err(err) => panic!("unexpected error: {}", err),
+        }
+    }
+}
+
+fn read_blocking(stream: &mut UnixStream, buf: &mut [u8]) -> usize {
+    loop {
+        match stream.read(buf) {
+            Ok(n) => return n,
+            Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {
+                std::thread::sleep(Duration::from_millis(1))
+            }
+            Err(err) => panic!("unexpected error: {}", err),
+        }
+    }
+}
+
+fn assert_would_block<T: std::fmt::Debug>(result: io::Result<T>) {
+    match result {
+        Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {}
+        result => panic!("expected WouldBlock, got: {:?}", result),
+    }
+}
+
+fn expect_readable(poll: &mut Poll, events: &mut Events, token: Token) {
+    poll.poll(events, Some(Duration::from_millis(500))).unwrap();
+    assert!(
+        events
+            .iter()
+            .any(|event| event.token() == token && event.is_readable()),
+        "expected readable event"
+    );
+}
@@ -0,0 +1,69 @@
+use std::os::unix::io::AsRawFd;
+
+use mio::net::{UCred, UnixDatagram, UnixListener, UnixSeqpacket, UnixStream};
+use tempdir::TempDir;
+
+#[test]
+fn unix_stream_peer_cred() {
+    let (a, b) = UnixStream::pair().unwrap();
+    assert_own_cred(a.peer_cred().unwrap());
+    assert_own_cred(b.peer_cred().unwrap());
+
+    let dir = TempDir::new("mio").unwrap();
+    let path = dir.path().join("cred.sock");
+    let listener = UnixListener::bind(&path).unwrap();
+    let client = UnixStream::connect(&path).unwrap();
+    let (server, _) = listener.accept().unwrap();
+    assert_own_cred(client.peer_cred().unwrap());
+    assert_own_cred(server.peer_cred().unwrap());
+}
+
+#[test]
+fn unix_seqpacket_peer_cred() {
+    let (a, _b) = UnixSeqpacket::pair().unwrap();
+    assert_own_cred(a.peer_cred().unwrap());
+}
+
+#[test]
+fn unix_datagram_recv_with_cred() {
+    let (a, b) = UnixDatagram::pair().unwrap();
+    assert!(!b.passcred().unwrap());
+
+    let mut buf = [0; 16];
+    a.send(b"without").unwrap();
+    let (n, cred) = b.recv_with_cred(&mut buf).unwrap();
+    assert_eq!(&buf[..n], b"without");
+    assert!(cred.is_none());
+
+    b.set_passcred(true).unwrap();
+    assert!(b.passcred().unwrap());
+    a.send(b"with").unwrap();
+    let (n, cred) = b.recv_with_cred(&mut buf).unwrap();
+    assert_eq!(&buf[..n], b"with");
+    assert_own_cred(cred.unwrap());
+}
+
+#[test]
+fn unix_datagram_recv_with_fds_and_passcred() {
+    let (a, b) = UnixDatagram::pair().unwrap();
+    b.set_passcred(true).unwrap();
+
+    let mut buf = [0; 16];
+    a.send_with_fds(b"fds", &[a.as_raw_fd()]).unwrap();
+    let (n, fds) = b.recv_with_fds(&mut buf, 2).unwrap();
+    assert_eq!(&buf[..n], b"fds");
+    assert_eq!(fds.len(), 1);
+
+    a.send(b"no fds").unwrap();
+    let (n, fds) = b.recv_with_fds(&mut buf, 2).unwrap();
+    assert_eq!(&buf[..n], b"no fds");
+    assert!(fds.is_empty());
+}
+
+fn assert_own_cred(cred: UCred) {
+    unsafe {
+        assert_eq!(cred.pid(), libc::getpid() as u32);
+        assert_eq!(cred.uid(), libc::geteuid());
+        assert_eq!(cred.gid(), libc::getegid());
+    }
+}
@@ -0,0 +1,146 @@
+use std::io;
+use std::net::Shutdown;
+use std::os::unix::net;
+use std::time::Duration;
+
+use iovec::IoVec;
+use mio::net::UnixDatagram;
+use mio::{Events, Interests, Poll, Token};
+use tempdir::TempDir;
+
+use super::expect_no_events;
+
+const ID: Token = Token(1);
+
+#[test]
+fn unix_datagram_send_to_recv_from() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let dir = TempDir::new("mio").unwrap();
+    let path = dir.path().join("receiver.sock");
+    let receiver = UnixDatagram::bind(&path).unwrap();
+    assert_eq!(receiver.local_addr().unwrap().as_pathname(), Some(&*path));
+    poll.registry()
+        .register(&receiver, ID, Interests::READABLE)
+        .unwrap();
+
+    let mut buf = [0; 16];
+    assert_would_block(receiver.recv_from(&mut buf));
+
+    let sender_path = dir.path().join("sender.sock");
+    let sender = UnixDatagram::bind(&sender_path).unwrap();
+    let unbound = UnixDatagram::unbound().unwrap();
+    assert_eq!(sender.send_to(b"first", &path).unwrap(), 5);
+    assert_eq!(unbound.send_to(b"second", &path).unwrap(), 6);
+
+    expect_readable(&mut poll, &mut events);
+    let (n, addr) = receiver.recv_from(&mut buf).unwrap();
+    assert_eq!(&buf[..n], b"first");
+    assert_eq!(addr.as_pathname(), Some(&*sender_path));
+    let (n, addr) = receiver.recv_from(&mut buf).unwrap();
+    assert_eq!(&buf[..n], b"second");
+    assert!(addr.is_unnamed());
+    assert_would_block(receiver.recv_from(&mut buf));
+}
+
+#[test]
+fn unix_datagram_connect() {
+    let dir = TempDir::new("mio").unwrap();
+    let path = dir.path().join("receiver.sock");
+    let receiver = UnixDatagram::bind(&path).unwrap();
+
+    let sender = UnixDatagram::unbound().unwrap();
+    sender.connect(&path).unwrap();
+    assert_eq!(sender.peer_addr().unwrap().as_pathname(), Some(&*path));
+    sender.send(b"Hello").unwrap();
+
+    let mut buf = [0; 16];
+    let n = recv_blocking(&receiver, &mut buf);
+    assert_eq!(&buf[..n], b"Hello");
+}
+
+#[test]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn unix_datagram_abstract_namespace() {
+    let name = format!("\0mio-test-uds-datagram-{}", std::process::id());
+    let receiver = UnixDatagram::bind(&name).unwrap();
+    let sender = UnixDatagram::unbound().unwrap();
+    sender.send_to(b"abstract", &name).unwrap();
+
+    let mut buf = [0; 16];
+    let n = recv_blocking(&receiver, &mut buf);
+    assert_eq!(&buf[..n], b"abstract");
+}
+
+#[test]
+fn unix_datagram_pair_keeps_message_boundaries() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let (a, b) = UnixDatagram::pair().unwrap();
+    poll.registry()
+        .register(&b, ID, Interests::READABLE)
+        .unwrap();
+
+    a.send(b"one").unwrap();
+    let bufs: [&IoVec; 2] = [b"tw"[..].into(), b"o"[..].into()];
+    assert_eq!(a.send_bufs(&bufs).unwrap(), 3);
+    expect_readable(&mut poll, &mut events);
+
+    let mut buf = [0; 16];
+    assert_eq!(b.recv(&mut buf).unwrap(), 3);
+    assert_eq!(&buf[..3], b"one");
+
+    let mut buf1 = [0; 1];
+    let mut buf2 = [0; 8];
+    {
+        let mut bufs: [&mut IoVec; 2] = [(&mut buf1[..]).into(), (&mut buf2[..]).into()];
+        assert_eq!(b.recv_bufs(&mut bufs).unwrap(), 3);
+    }
+    assert_eq!(&buf1, b"t");
+    assert_eq!(&buf2[..2], b"wo");
+    assert_would_block(b.recv(&mut buf));
+    expect_no_events(&mut poll, &mut events);
+}
+
+#[test]
+fn unix_datagram_shutdown_and_from_std() {
+    let (a, b) = net::UnixDatagram::pair().unwrap();
+    let a = UnixDatagram::from_std(a).unwrap();
+    let mut buf = [0; 16];
+    assert_would_block(a.recv(&mut buf));
+
+    b.shutdown(Shutdown::Write).unwrap();
+    assert!(b.send(b"Hello").is_err());
+    assert!(a.take_error().unwrap().is_none());
+}
+
+fn recv_blocking(socket: &UnixDatagram, buf: &mut [u8]) -> usize {
+    loop {
+        match socket.recv(buf) {
+            Ok(n) => return n,
+            Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {
+                std::thread::sleep(Duration::from_millis(1))
+            }
+            Err(err) => panic!("unexpected error: {}", err),
+        }
+    }
+}
+
+fn assert_would_block<T: std::fmt::Debug>(result: io::Result<T>) {
+    match result {
+        Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {}
+        result => panic!("expected WouldBlock, got: {:?}", result),
+    }
+}
+
+fn expect_readable(poll: &mut Poll, events: &mut Events) {
+    poll.poll(events, Some(Duration::from_millis(500))).unwrap();
+    assert!(
+        events
+            .iter()
+            .any(|event| event.token() == ID && event.is_readable()),
+        "expected readable event"
+    );
+}
@@ -0,0 +1,122 @@
+use std::fs::File;
+use std::io::{Read, Write};
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd};
+use std::time::Duration;
+
+use mio::net::{UnixDatagram, UnixSeqpacket, UnixStream};
+use mio::unix::OwnedFd;
+use mio::{Events, Interests, Poll, Token};
+use tempdir::TempDir;
+
+const ID: Token = Token(1);
+
+#[test]
+fn unix_stream_send_fds() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let dir = TempDir::new("mio").unwrap();
+    let file = File::create(dir.path().join("file")).unwrap();
+    let (a, b) = UnixStream::pair().unwrap();
+    poll.registry()
+        .register(&b, ID, Interests::READABLE)
+        .unwrap();
+
+    assert_eq!(a.send_with_fds(b"fd", &[file.as_raw_fd()]).unwrap(), 2);
+    poll.poll(&mut events, Some(Duration::from_millis(500)))
+        .unwrap();
+    assert!(!events.is_empty());
+
+    let mut buf = [0; 16];
+    let (n, fds) = b.recv_with_fds(&mut buf, 4).unwrap();
+    assert_eq!(&buf[..n], b"fd");
+    assert_eq!(fds.len(), 1);
+    assert!(is_cloexec(&fds[0]));
+
+    // The received file descriptor refers to the same file.
+    write_to(fds.into_iter().next().unwrap(), b"Hello");
+    let mut contents = String::new();
+    File::open(dir.path().join("file"))
+        .unwrap()
+        .read_to_string(&mut contents)
+        .unwrap();
+    assert_eq!(contents, "Hello");
+}
+
+#[test]
+fn unix_stream_recv_without_fds() {
+    let (a, b) = UnixStream::pair().unwrap();
+    a.send_with_fds(b"no fds", &[]).unwrap();
+    let mut buf = [0; 16];
+    let (n, fds) = b.recv_with_fds(&mut buf, 4).unwrap();
+    assert_eq!(&buf[..n], b"no fds");
+    assert!(fds.is_empty());
+}
+
+#[test]
+fn unix_stream_recv_fds_truncated() {
+    let (a, b) = UnixStream::pair().unwrap();
+    let (x, y) = UnixStream::pair().unwrap();
+    a.send_with_fds(b"fds", &[x.as_raw_fd(), y.as_raw_fd(), x.as_raw_fd()])
+        .unwrap();
+
+    let mut buf = [0; 16];
+    assert!(b.recv_with_fds(&mut buf, 1).is_err());
+
+    // The data was consumed along with the truncated control message.
+    a.send_with_fds(b"next", &[x.as_raw_fd()]).unwrap();
+    let (n, fds) = b.recv_with_fds(&mut buf, 1).unwrap();
+    assert_eq!(&buf[..n], b"next");
+    assert_eq!(fds.len(), 1);
+}
+
+#[test]
+fn unix_datagram_send_fds() {
+    let (a, b) = UnixDatagram::pair().unwrap();
+    let (x, y) = UnixDatagram::pair().unwrap();
+    a.send_with_fds(b"one", &[x.as_raw_fd(), y.as_raw_fd()])
+        .unwrap();
+    a.send_with_fds(b"two", &[]).unwrap();
+
+    let mut buf = [0; 16];
+    let (n, fds) = b.recv_with_fds(&mut buf, 2).unwrap();
+    assert_eq!(&buf[..n], b"one");
+    assert_eq!(fds.len(), 2);
+    let (n, fds) = b.recv_with_fds(&mut buf, 2).unwrap();
+    assert_eq!(&buf[..n], b"two");
+    assert!(fds.is_empty());
+
+    // Dropping the remaining received file descriptors closes them.
+    let mut fds = {
+        a.send_with_fds(b"three", &[x.as_raw_fd(), y.as_raw_fd()])
+            .unwrap();
+        b.recv_with_fds(&mut buf, 2).unwrap().1.into_iter()
+    };
+    let x2 = unsafe { UnixDatagram::from_raw_fd(fds.next().unwrap().into_raw_fd()) };
+    drop(fds);
+    x2.send(b"dup").unwrap();
+    assert_eq!(y.recv(&mut buf).unwrap(), 3);
+}
+
+#[test]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn unix_seqpacket_send_fds() {
+    let (a, b) = UnixSeqpacket::pair().unwrap();
+    let (x, _y) = UnixSeqpacket::pair().unwrap();
+    a.send_with_fds(b"seqpacket", &[x.as_raw_fd()]).unwrap();
+
+    let mut buf = [0; 16];
+    let (n, fds) = b.recv_with_fds(&mut buf, 1).unwrap();
+    assert_eq!(&buf[..n], b"seqpacket");
+    assert_eq!(fds.len(), 1);
+}
+
+fn is_cloexec(fd: &OwnedFd) -> bool {
+    let flags = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GETFD) };
+    flags != -1 && flags & libc::FD_CLOEXEC != 0
+}
+
+fn write_to(fd: OwnedFd, data: &[u8]) {
+    let mut file = unsafe { File::from_raw_fd(fd.into_raw_fd()) };
+    file.write_all(data).unwrap();
+}
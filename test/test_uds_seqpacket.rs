@@ -0,0 +1,101 @@
+use std::io;
+use std::net::Shutdown;
+use std::time::Duration;
+
+use mio::net::{UnixSeqpacket, UnixSeqpacketListener};
+use mio::{Events, Interests, Poll, Token};
+use tempdir::TempDir;
+
+const LISTENER: Token = Token(0);
+const SERVER: Token = Token(1);
+
+#[test]
+fn unix_seqpacket_accept() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let dir = TempDir::new("mio").unwrap();
+    let path = dir.path().join("seqpacket.sock");
+    let listener = UnixSeqpacketListener::bind(&path).unwrap();
+    assert_eq!(listener.local_addr().unwrap().as_pathname(), Some(&*path));
+    poll.registry()
+        .register(&listener, LISTENER, Interests::READABLE)
+        .unwrap();
+    assert_would_block(listener.accept().map(|_| ()));
+
+    let client = UnixSeqpacket::connect(&path).unwrap();
+    assert_eq!(client.peer_addr().unwrap().as_pathname(), Some(&*path));
+    expect_readable(&mut poll, &mut events, LISTENER);
+    let (server, _) = listener.accept().unwrap();
+    poll.registry()
+        .register(&server, SERVER, Interests::READABLE)
+        .unwrap();
+
+    let mut buf = [0; 16];
+    assert_would_block(server.recv(&mut buf));
+
+    client.send(b"first").unwrap();
+    client.send(b"second").unwrap();
+    expect_readable(&mut poll, &mut events, SERVER);
+    assert_eq!(server.recv(&mut buf).unwrap(), 5);
+    assert_eq!(&buf[..5], b"first");
+
+    // Excess bytes of a message are discarded.
+    let mut small = [0; 3];
+    assert_eq!(server.recv(&mut small).unwrap(), 3);
+    assert_eq!(&small, b"sec");
+    assert_would_block(server.recv(&mut buf));
+
+    client.shutdown(Shutdown::Write).unwrap();
+    expect_readable(&mut poll, &mut events, SERVER);
+    assert_eq!(server.recv(&mut buf).unwrap(), 0);
+}
+
+#[test]
+fn unix_seqpacket_bind_unlink_stale() {
+    let dir = TempDir::new("mio").unwrap();
+    let path = dir.path().join("stale.sock");
+    drop(UnixSeqpacketListener::bind(&path).unwrap());
+
+    let err = UnixSeqpacketListener::bind(&path).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+    let listener = UnixSeqpacketListener::bind_unlink_stale(&path).unwrap();
+
+    let err = UnixSeqpacketListener::bind_unlink_stale(&path).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+    drop(listener);
+}
+
+#[test]
+fn unix_seqpacket_pair() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let (a, b) = UnixSeqpacket::pair().unwrap();
+    poll.registry()
+        .register(&b, SERVER, Interests::READABLE)
+        .unwrap();
+    a.send(b"Hello").unwrap();
+    expect_readable(&mut poll, &mut events, SERVER);
+
+    let mut buf = [0; 16];
+    assert_eq!(b.recv(&mut buf).unwrap(), 5);
+    assert!(a.peer_addr().unwrap().is_unnamed());
+}
+
+fn assert_would_block<T: std::fmt::Debug>(result: io::Result<T>) {
+    match result {
+        Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {}
+        result => panic!("expected WouldBlock, got: {:?}", result),
+    }
+}
+
+fn expect_readable(poll: &mut Poll, events: &mut Events, token: Token) {
+    poll.poll(events, Some(Duration::from_millis(500))).unwrap();
+    assert!(
+        events
+            .iter()
+            .any(|event| event.token() == token && event.is_readable()),
+        "expected readable event"
+    );
+}
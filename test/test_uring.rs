@@ -0,0 +1,224 @@
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::time::Duration;
+
+use mio::uring::{Completion, Completions, Ring};
+use mio::{Events, Interests, Poll, Token};
+use tempdir::TempDir;
+
+use super::expect_no_events;
+
+const RING: Token = Token(0);
+const ID1: Token = Token(1);
+const ID2: Token = Token(2);
+const ID3: Token = Token(3);
+
+#[test]
+fn uring_write_read_file() {
+    let (mut poll, mut events) = setup();
+    let ring = Ring::new(8).unwrap();
+    poll.registry()
+        .register(&ring, RING, Interests::READABLE)
+        .unwrap();
+    let (_dir, file) = temp_file();
+
+    ring.write(file.as_raw_fd(), b"hello world".to_vec(), Some(0), ID1)
+        .unwrap();
+    ring.submit().unwrap();
+    let completions = expect_completions(&mut poll, &mut events, &ring, 1);
+    assert_eq!(completions[0].token(), ID1);
+    assert_eq!(completions[0].result().unwrap(), 11);
+    assert_eq!(completions[0].buf(), Some(&b"hello world"[..]));
+
+    ring.fdatasync(file.as_raw_fd(), ID2).unwrap();
+    // Appends to the existing data of the buffer, up to its capacity.
+    let mut buf = Vec::with_capacity(4);
+    buf.extend_from_slice(b"> ");
+    ring.read(file.as_raw_fd(), buf, Some(6), ID3).unwrap();
+    assert_eq!(ring.in_flight(), 2);
+    ring.submit().unwrap();
+    let mut completions = expect_completions(&mut poll, &mut events, &ring, 2);
+    completions.sort_by_key(|completion| completion.token());
+    assert_eq!(completions[0].token(), ID2);
+    assert_eq!(completions[0].result().unwrap(), 0);
+    assert_eq!(completions[1].token(), ID3);
+    assert_eq!(completions[1].result().unwrap(), 2);
+    assert_eq!(completions.pop().unwrap().into_buf().unwrap(), b"> wo");
+    assert_eq!(ring.in_flight(), 0);
+}
+
+#[test]
+fn uring_readv() {
+    let (mut poll, mut events) = setup();
+    let ring = Ring::new(8).unwrap();
+    poll.registry()
+        .register(&ring, RING, Interests::READABLE)
+        .unwrap();
+    let (_dir, mut file) = temp_file();
+    file.write_all(b"0123456789").unwrap();
+
+    let bufs = vec![Vec::with_capacity(3), Vec::with_capacity(4), Vec::new()];
+    ring.readv(file.as_raw_fd(), bufs, Some(1), ID1).unwrap();
+    ring.submit().unwrap();
+    let mut completions = expect_completions(&mut poll, &mut events, &ring, 1);
+    let completion = completions.pop().unwrap();
+    assert_eq!(completion.result().unwrap(), 7);
+    let bufs = completion.into_bufs().unwrap();
+    assert_eq!(bufs, [&b"123"[..], b"4567", b""]);
+}
+
+#[test]
+fn uring_accept() {
+    let (mut poll, mut events) = setup();
+    let ring = Ring::new(8).unwrap();
+    poll.registry()
+        .register(&ring, RING, Interests::READABLE)
+        .unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+
+    ring.accept(listener.as_raw_fd(), ID1).unwrap();
+    ring.submit().unwrap();
+    expect_no_events(&mut poll, &mut events);
+
+    let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let mut completions = expect_completions(&mut poll, &mut events, &ring, 1);
+    let fd = completions.pop().unwrap().result().unwrap();
+    let stream = unsafe { TcpStream::from_raw_fd(fd as i32) };
+    assert_eq!(stream.peer_addr().unwrap(), client.local_addr().unwrap());
+
+    // Reading from a socket, which completes once data arrives.
+    ring.read(stream.as_raw_fd(), Vec::with_capacity(16), None, ID2)
+        .unwrap();
+    ring.submit().unwrap();
+    expect_no_events(&mut poll, &mut events);
+    client.write_all(b"ping").unwrap();
+    let mut completions = expect_completions(&mut poll, &mut events, &ring, 1);
+    assert_eq!(completions.pop().unwrap().into_buf().unwrap(), b"ping");
+}
+
+#[test]
+fn uring_error() {
+    let (mut poll, mut events) = setup();
+    let ring = Ring::new(8).unwrap();
+    poll.registry()
+        .register(&ring, RING, Interests::READABLE)
+        .unwrap();
+
+    ring.read(-1, Vec::with_capacity(8), None, ID1).unwrap();
+    ring.submit().unwrap();
+    let mut completions = expect_completions(&mut poll, &mut events, &ring, 1);
+    let completion = completions.pop().unwrap();
+    let err = completion.result().unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::EBADF));
+    assert_eq!(completion.into_buf().unwrap(), b"");
+}
+
+#[test]
+fn uring_full_completions() {
+    let (mut poll, mut events) = setup();
+    let ring = Ring::new(2).unwrap();
+    poll.registry()
+        .register(&ring, RING, Interests::READABLE)
+        .unwrap();
+    let (_dir, file) = temp_file();
+
+    // More operations than fit in the submission queue.
+    for n in 0..5 {
+        ring.write(
+            file.as_raw_fd(),
+            vec![b'a' + n],
+            Some(n.into()),
+            Token(n.into()),
+        )
+        .unwrap();
+    }
+    ring.submit().unwrap();
+
+    let mut completions = Completions::with_capacity(2);
+    let mut tokens = Vec::new();
+    while tokens.len() < 5 {
+        poll.poll(&mut events, Some(Duration::from_millis(500)))
+            .unwrap();
+        // Without waiting for another event.
+        loop {
+            ring.completions(&mut completions).unwrap();
+            assert!(completions.len() <= 2);
+            if completions.is_empty() {
+                break;
+            }
+            tokens.extend(completions.iter().map(|c| c.token().0));
+        }
+    }
+    tokens.sort();
+    assert_eq!(tokens, [0, 1, 2, 3, 4]);
+
+    let mut data = String::new();
+    (&file).read_to_string(&mut data).unwrap();
+    assert_eq!(data, "abcde");
+}
+
+#[test]
+fn uring_drop_in_flight() {
+    let (mut reader, writer) = pipe();
+    let ring = Ring::new(8).unwrap();
+    ring.read(reader.as_raw_fd(), Vec::with_capacity(8), None, ID1)
+        .unwrap();
+    ring.submit().unwrap();
+    // Not yet submitted.
+    ring.read(reader.as_raw_fd(), Vec::with_capacity(8), None, ID2)
+        .unwrap();
+    assert_eq!(ring.in_flight(), 2);
+
+    // Cancels the reads, rather than waiting for them.
+    drop(ring);
+    (&writer).write_all(b"data").unwrap();
+    let mut buf = [0; 8];
+    assert_eq!(reader.read(&mut buf).unwrap(), 4);
+}
+
+fn setup() -> (Poll, Events) {
+    (Poll::new().unwrap(), Events::with_capacity(8))
+}
+
+fn temp_file() -> (TempDir, File) {
+    let dir = TempDir::new("uring").unwrap();
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(dir.path().join("file"))
+        .unwrap();
+    (dir, file)
+}
+
+fn pipe() -> (File, File) {
+    let mut fds = [0; 2];
+    assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+    unsafe { (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1])) }
+}
+
+/// Poll until `n` completions are returned.
+fn expect_completions(
+    poll: &mut Poll,
+    events: &mut Events,
+    ring: &Ring,
+    n: usize,
+) -> Vec<Completion> {
+    let mut completions = Completions::with_capacity(8);
+    let mut got = Vec::new();
+    while got.len() < n {
+        poll.poll(events, Some(Duration::from_millis(500))).unwrap();
+        assert!(!events.is_empty(), "expected ring event");
+        for event in events.iter() {
+            assert_eq!(event.token(), RING);
+            assert!(event.is_readable());
+        }
+        ring.completions(&mut completions).unwrap();
+        got.extend(completions.drain());
+    }
+    assert_eq!(got.len(), n, "unexpected completions: {:?}", got);
+    got
+}
@@ -32,6 +32,22 @@ fn waker_multiple_wakeups_same_thread() {
     expect_waker_event(&mut poll, &mut events, token);
 }
 
+#[test]
+fn waker_multiple_wakeups_fill_buffer() {
+    let mut poll = Poll::new().expect("unable to create new Poll instance");
+    let mut events = Events::with_capacity(10);
+
+    let token = Token(10);
+    let waker = Waker::new(poll.registry(), token).expect("unable to create waker");
+
+    // More wake ups than fit in the buffer of the pipe based waker, which
+    // empties its pipe once it's full.
+    for _ in 0..100_000 {
+        waker.wake().expect("unable to wake");
+    }
+    expect_waker_event(&mut poll, &mut events, token);
+}
+
 #[test]
 fn waker_wakeup_different_thread() {
     let mut poll = Poll::new().expect("unable to create new Poll instance");
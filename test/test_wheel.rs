@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use mio::time::Wheel;
+use mio::{Events, Poll, Token};
+
+const TICK: Duration = Duration::from_millis(1);
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+fn expired_at(wheel: &mut Wheel, now: Instant) -> Vec<Token> {
+    wheel.expired_at(now).collect()
+}
+
+#[test]
+fn wheel_expire() {
+    let mut wheel = Wheel::new(TICK);
+    let start = Instant::now();
+
+    wheel.insert_at(start + ms(10), Token(0));
+    assert_eq!(wheel.len(), 1);
+
+    assert!(expired_at(&mut wheel, start + ms(9)).is_empty());
+    assert_eq!(expired_at(&mut wheel, start + ms(11)), vec![Token(0)]);
+    assert!(wheel.is_empty());
+    assert!(expired_at(&mut wheel, start + ms(20)).is_empty());
+}
+
+#[test]
+fn wheel_expire_in_order_across_levels() {
+    let mut wheel = Wheel::new(TICK);
+    let start = Instant::now();
+
+    let timeouts = [300_000, 5, 4_100, 100, 64, 5_000, 262_144, 63];
+    for (i, timeout) in timeouts.iter().enumerate() {
+        wheel.insert_at(start + ms(*timeout), Token(i));
+    }
+
+    let mut sorted = timeouts.to_vec();
+    sorted.sort();
+    for timeout in sorted {
+        let index = timeouts.iter().position(|t| *t == timeout).unwrap();
+        assert!(
+            expired_at(&mut wheel, start + ms(timeout - 1)).is_empty(),
+            "timeout {}ms expired early",
+            timeout
+        );
+        assert_eq!(
+            expired_at(&mut wheel, start + ms(timeout + 1)),
+            vec![Token(index)],
+            "timeout {}ms",
+            timeout
+        );
+    }
+    assert!(wheel.is_empty());
+}
+
+#[test]
+fn wheel_expire_many() {
+    let mut wheel = Wheel::with_capacity(TICK, 10_000);
+    let start = Instant::now();
+
+    // Deterministic pseudo random timeouts between 1ms and ~20 seconds.
+    let mut deadlines = HashMap::new();
+    let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+    for i in 0..10_000 {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        let timeout = 1 + seed % 20_000;
+        wheel.insert_at(start + ms(timeout), Token(i));
+        deadlines.insert(Token(i), timeout);
+    }
+
+    let mut now = 0;
+    while now < 20_010 {
+        now += 7;
+        for token in wheel.expired_at(start + ms(now)) {
+            let deadline = deadlines.remove(&token).unwrap();
+            // Never early, and at most the step size plus a tick late.
+            assert!(deadline <= now, "expired early: {} > {}", deadline, now);
+            assert!(deadline + 8 >= now, "expired late: {} < {}", deadline, now);
+        }
+    }
+    assert!(deadlines.is_empty());
+    assert!(wheel.is_empty());
+}
+
+#[test]
+fn wheel_cancel() {
+    let mut wheel = Wheel::new(TICK);
+    let start = Instant::now();
+
+    let timeout1 = wheel.insert_at(start + ms(10), Token(1));
+    let timeout2 = wheel.insert_at(start + ms(10), Token(2));
+    let timeout3 = wheel.insert_at(start + ms(5_000), Token(3));
+
+    assert_eq!(wheel.cancel(timeout1), Some(Token(1)));
+    assert_eq!(wheel.cancel(timeout1), None);
+    assert_eq!(wheel.cancel(timeout3), Some(Token(3)));
+    assert_eq!(wheel.len(), 1);
+
+    assert_eq!(expired_at(&mut wheel, start + ms(6_000)), vec![Token(2)]);
+    assert_eq!(wheel.cancel(timeout2), None);
+}
+
+#[test]
+fn wheel_cancel_expired_but_not_returned() {
+    let mut wheel = Wheel::new(TICK);
+    let start = Instant::now();
+
+    let timeout = wheel.insert_at(start + ms(10), Token(1));
+    wheel.insert_at(start + ms(10), Token(2));
+
+    {
+        let mut expired = wheel.expired_at(start + ms(20));
+        assert_eq!(expired.next(), Some(Token(1)));
+    }
+    // Token(2) expired, but it isn't returned yet.
+    assert_eq!(wheel.len(), 1);
+    assert_eq!(wheel.cancel(timeout), None);
+
+    let timeout = wheel.insert_at(start + ms(10), Token(3));
+    wheel.expired_at(start + ms(20)).take(0).count();
+    assert_eq!(wheel.cancel(timeout), Some(Token(3)));
+    assert_eq!(expired_at(&mut wheel, start + ms(20)), vec![Token(2)]);
+}
+
+#[test]
+fn wheel_stale_handle_after_reuse() {
+    let mut wheel = Wheel::new(TICK);
+    let start = Instant::now();
+
+    let stale = wheel.insert_at(start + ms(10), Token(1));
+    assert_eq!(wheel.cancel(stale), Some(Token(1)));
+
+    // This reuses the storage of the cancelled timeout.
+    let timeout = wheel.insert_at(start + ms(10), Token(2));
+    assert_ne!(stale, timeout);
+    assert_eq!(wheel.cancel(stale), None);
+    assert_eq!(wheel.len(), 1);
+
+    assert_eq!(expired_at(&mut wheel, start + ms(20)), vec![Token(2)]);
+
+    // Same after the timeout expired.
+    wheel.insert_at(start + ms(30), Token(3));
+    assert_eq!(wheel.cancel(timeout), None);
+    assert_eq!(expired_at(&mut wheel, start + ms(40)), vec![Token(3)]);
+}
+
+#[test]
+fn wheel_deadline_in_the_past() {
+    let mut wheel = Wheel::new(TICK);
+    let start = Instant::now();
+
+    assert!(expired_at(&mut wheel, start + ms(100)).is_empty());
+    wheel.insert_at(start + ms(50), Token(1));
+    assert_eq!(wheel.next_timeout(), Some(Duration::from_millis(0)));
+    assert_eq!(expired_at(&mut wheel, start + ms(100)), vec![Token(1)]);
+}
+
+#[test]
+fn wheel_far_future() {
+    let mut wheel = Wheel::new(TICK);
+    let start = Instant::now();
+
+    // Far beyond the range of the wheel (2^36 ticks).
+    let far = ms(10 * 365 * 24 * 60 * 60 * 1000);
+    let timeout = wheel.insert_at(start + far, Token(1));
+    assert!(wheel.next_timeout().is_some());
+    assert!(expired_at(&mut wheel, start + ms(1_000_000)).is_empty());
+    assert_eq!(wheel.cancel(timeout), Some(Token(1)));
+    assert_eq!(wheel.next_timeout(), None);
+}
+
+#[test]
+fn wheel_next_timeout() {
+    let mut wheel = Wheel::new(TICK);
+    assert_eq!(wheel.next_timeout(), None);
+
+    // Deadlines are rounded up to the next tick.
+    wheel.insert(ms(5_000), Token(1));
+    let next = wheel.next_timeout().unwrap();
+    assert!(
+        next <= ms(5_000) + TICK,
+        "next timeout too large: {:?}",
+        next
+    );
+
+    wheel.insert(ms(50), Token(2));
+    let next = wheel.next_timeout().unwrap();
+    assert!(next <= ms(50) + TICK, "next timeout too large: {:?}", next);
+    assert!(next >= ms(40), "next timeout too small: {:?}", next);
+}
+
+#[test]
+fn wheel_poll() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+    let mut wheel = Wheel::new(ms(5));
+
+    let start = Instant::now();
+    wheel.insert(ms(100), Token(1));
+    wheel.insert(ms(30), Token(2));
+    let cancelled = wheel.insert(ms(60), Token(3));
+    wheel.cancel(cancelled);
+
+    let mut expired = Vec::new();
+    while expired.len() < 2 {
+        poll.poll(&mut events, wheel.next_timeout()).unwrap();
+        assert!(events.is_empty());
+        for token in wheel.expired() {
+            expired.push((token, start.elapsed()));
+        }
+        assert!(start.elapsed() < ms(1_000), "timeouts didn't expire");
+    }
+
+    assert_eq!(expired[0].0, Token(2));
+    assert!(expired[0].1 >= ms(30));
+    assert_eq!(expired[1].0, Token(1));
+    assert!(expired[1].1 >= ms(100));
+    assert_eq!(wheel.next_timeout(), None);
+}